@@ -0,0 +1,32 @@
+-- migrations/006_challenge_versions.sql
+-- Configuration history for challenges and per-progress version pinning
+
+-- Every configuration a challenge has had, keyed by version
+CREATE TABLE challenge_versions (
+    challenge_id    UUID NOT NULL REFERENCES challenges(id) ON DELETE CASCADE,
+    version         INT NOT NULL,
+    name            TEXT NOT NULL,
+    description     TEXT NOT NULL,
+    author          TEXT,
+    category        TEXT NOT NULL,
+    challenge_type  TEXT NOT NULL,
+    configuration   JSONB NOT NULL,
+    invite_config   JSONB,
+    hamalert_config JSONB,
+    created_at      TIMESTAMPTZ NOT NULL DEFAULT now(),
+    PRIMARY KEY (challenge_id, version)
+);
+
+-- Seed history with the current configuration of existing challenges
+INSERT INTO challenge_versions (
+    challenge_id, version, name, description, author, category, challenge_type,
+    configuration, invite_config, hamalert_config, created_at
+)
+SELECT id, version, name, description, author, category, challenge_type,
+       configuration, invite_config, hamalert_config, updated_at
+FROM challenges
+ON CONFLICT DO NOTHING;
+
+-- Challenge version each progress row was last scored against
+-- (NULL for rows scored before version tracking existed)
+ALTER TABLE progress ADD COLUMN challenge_version INT;
@@ -0,0 +1,16 @@
+-- migrations/007_challenge_lifecycle.sql
+-- Explicit challenge lifecycle status with scheduled publishing
+
+ALTER TABLE challenges
+    ADD COLUMN status TEXT NOT NULL DEFAULT 'draft'
+        CHECK (status IN ('draft', 'scheduled', 'published', 'ended', 'archived')),
+    ADD COLUMN publish_at TIMESTAMPTZ;
+
+-- Existing challenges were already public
+UPDATE challenges SET status = CASE WHEN is_active THEN 'published' ELSE 'ended' END;
+
+-- Whether a challenge is active is derived from its status from now on, so
+-- a stored flag can't go stale when a scheduled challenge publishes
+ALTER TABLE challenges DROP COLUMN is_active;
+
+CREATE INDEX idx_challenges_status ON challenges(status);
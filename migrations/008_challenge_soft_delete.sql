@@ -0,0 +1,6 @@
+-- migrations/008_challenge_soft_delete.sql
+-- Soft delete for challenges; rows are purged after a retention window
+
+ALTER TABLE challenges ADD COLUMN deleted_at TIMESTAMPTZ;
+
+CREATE INDEX idx_challenges_deleted_at ON challenges(deleted_at) WHERE deleted_at IS NOT NULL;
@@ -0,0 +1,18 @@
+-- migrations/009_challenge_templates.sql
+-- Recurring challenge templates that instantiate a new time-bounded challenge each period
+
+CREATE TABLE challenge_templates (
+    id                  UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    name_template       TEXT NOT NULL,
+    source_challenge_id UUID NOT NULL REFERENCES challenges(id) ON DELETE CASCADE,
+    last_challenge_id   UUID REFERENCES challenges(id) ON DELETE SET NULL,
+    recurrence          TEXT NOT NULL CHECK (recurrence IN ('monthly', 'yearly')),
+    -- Later periods are counted from this so month-end series don't drift
+    first_period_start  TIMESTAMPTZ NOT NULL,
+    next_period_start   TIMESTAMPTZ NOT NULL,
+    created_at          TIMESTAMPTZ NOT NULL DEFAULT now(),
+    updated_at          TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+-- Maintenance job: templates whose next period has started
+CREATE INDEX idx_challenge_templates_next_period ON challenge_templates(next_period_start);
@@ -0,0 +1,11 @@
+-- migrations/010_challenge_waitlist.sql
+-- Waitlisted participations for challenges at maxParticipants
+
+ALTER TABLE challenge_participants DROP CONSTRAINT challenge_participants_status_check;
+ALTER TABLE challenge_participants ADD CONSTRAINT challenge_participants_status_check
+    CHECK (status IN ('active', 'left', 'completed', 'waitlisted'));
+
+-- Promotion order
+CREATE INDEX idx_challenge_participants_waitlist
+    ON challenge_participants(challenge_id, joined_at)
+    WHERE status = 'waitlisted';
@@ -0,0 +1,13 @@
+-- migrations/011_user_profiles.sql
+-- Public user profiles with optional avatar
+
+CREATE TABLE user_profiles (
+    user_id             UUID PRIMARY KEY REFERENCES users(id) ON DELETE CASCADE,
+    display_name        TEXT,
+    qth                 TEXT,
+    grid                TEXT,
+    bio                 TEXT,
+    avatar_data         BYTEA,
+    avatar_content_type TEXT,
+    updated_at          TIMESTAMPTZ NOT NULL DEFAULT now()
+);
@@ -0,0 +1,16 @@
+-- migrations/012_privacy_settings.sql
+-- Per-user privacy settings and per-activity audience
+
+CREATE TABLE user_privacy_settings (
+    user_id                   UUID PRIMARY KEY REFERENCES users(id) ON DELETE CASCADE,
+    hide_from_search          BOOLEAN NOT NULL DEFAULT false,
+    leaderboard_visibility    TEXT NOT NULL DEFAULT 'public'
+        CHECK (leaderboard_visibility IN ('public', 'anonymous', 'masked')),
+    default_activity_audience TEXT NOT NULL DEFAULT 'friends'
+        CHECK (default_activity_audience IN ('public', 'friends', 'private')),
+    updated_at                TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+-- Existing activities were only ever shown to friends
+ALTER TABLE activities ADD COLUMN audience TEXT NOT NULL DEFAULT 'friends'
+    CHECK (audience IN ('public', 'friends', 'private'));
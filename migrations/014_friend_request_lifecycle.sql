@@ -0,0 +1,18 @@
+-- migrations/014_friend_request_lifecycle.sql
+-- Cancelled and expired friend requests, and re-requests after a response
+
+ALTER TABLE friend_requests DROP CONSTRAINT friend_requests_status_check;
+ALTER TABLE friend_requests ADD CONSTRAINT friend_requests_status_check
+    CHECK (status IN ('pending', 'accepted', 'declined', 'cancelled', 'expired'));
+
+-- Answered requests are kept as history, so only one *pending* request per
+-- pair is unique. This lets a user ask again after a decline, cancel,
+-- expiry or unfriend.
+ALTER TABLE friend_requests DROP CONSTRAINT friend_requests_from_user_id_to_user_id_key;
+CREATE UNIQUE INDEX idx_friend_requests_pending_pair
+    ON friend_requests(from_user_id, to_user_id)
+    WHERE status = 'pending';
+
+CREATE INDEX idx_friend_requests_pending_requested_at
+    ON friend_requests(requested_at)
+    WHERE status = 'pending';
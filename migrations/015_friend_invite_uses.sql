@@ -0,0 +1,26 @@
+-- migrations/015_friend_invite_uses.sql
+-- Multi-use and revocable friend invite links with a redemption history
+
+ALTER TABLE friend_invites
+    ADD COLUMN max_uses   INTEGER NOT NULL DEFAULT 1 CHECK (max_uses > 0),
+    ADD COLUMN use_count  INTEGER NOT NULL DEFAULT 0,
+    ADD COLUMN revoked_at TIMESTAMPTZ;
+
+-- Everyone who redeemed a link, replacing the single used_by_user_id
+CREATE TABLE friend_invite_redemptions (
+    invite_id         UUID NOT NULL REFERENCES friend_invites(id) ON DELETE CASCADE,
+    user_id           UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    friend_request_id UUID REFERENCES friend_requests(id) ON DELETE SET NULL,
+    redeemed_at       TIMESTAMPTZ NOT NULL DEFAULT now(),
+    PRIMARY KEY (invite_id, user_id)
+);
+
+INSERT INTO friend_invite_redemptions (invite_id, user_id, redeemed_at)
+SELECT id, used_by_user_id, used_at
+FROM friend_invites
+WHERE used_by_user_id IS NOT NULL AND used_at IS NOT NULL;
+
+UPDATE friend_invites SET use_count = 1 WHERE used_at IS NOT NULL;
+
+-- used_at now records when the last allowed use was consumed
+ALTER TABLE friend_invites DROP COLUMN used_by_user_id;
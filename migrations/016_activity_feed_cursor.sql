@@ -0,0 +1,4 @@
+-- migrations/016_activity_feed_cursor.sql
+-- Keyset pagination on (created_at, id) for feeds and per-user timelines
+
+CREATE INDEX idx_activities_user_created ON activities(user_id, created_at DESC, id DESC);
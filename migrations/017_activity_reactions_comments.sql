@@ -0,0 +1,26 @@
+-- migrations/017_activity_reactions_comments.sql
+-- Reactions and threaded comments on activities
+
+CREATE TABLE activity_reactions (
+    activity_id UUID NOT NULL REFERENCES activities(id) ON DELETE CASCADE,
+    user_id     UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    kind        TEXT NOT NULL CHECK (kind IN ('73', 'congrats', 'like', 'fire', 'wow')),
+    created_at  TIMESTAMPTZ NOT NULL DEFAULT now(),
+    PRIMARY KEY (activity_id, user_id, kind)
+);
+
+CREATE TABLE activity_comments (
+    id          UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    activity_id UUID NOT NULL REFERENCES activities(id) ON DELETE CASCADE,
+    user_id     UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    -- Reply target; comments are soft-deleted so threads stay intact
+    parent_id   UUID REFERENCES activity_comments(id) ON DELETE CASCADE,
+    body        TEXT NOT NULL,
+    created_at  TIMESTAMPTZ NOT NULL DEFAULT now(),
+    deleted_at  TIMESTAMPTZ,
+    -- Author, activity owner, or NULL when removed by an admin
+    deleted_by  UUID REFERENCES users(id) ON DELETE SET NULL
+);
+
+CREATE INDEX idx_activity_comments_activity ON activity_comments(activity_id, created_at);
+CREATE INDEX idx_activity_comments_parent ON activity_comments(parent_id) WHERE parent_id IS NOT NULL;
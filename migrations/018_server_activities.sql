@@ -0,0 +1,12 @@
+-- migrations/018_server_activities.sql
+-- Server-generated milestone activities with opt-out and deduplication
+
+ALTER TABLE user_privacy_settings
+    ADD COLUMN share_milestones BOOLEAN NOT NULL DEFAULT true;
+
+-- Identifies a milestone (e.g. "badgeEarned:<badgeId>") so the server and
+-- clients never post the same one twice
+ALTER TABLE activities ADD COLUMN dedupe_key TEXT;
+
+CREATE UNIQUE INDEX idx_activities_dedupe ON activities(user_id, dedupe_key)
+    WHERE dedupe_key IS NOT NULL;
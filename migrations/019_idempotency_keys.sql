@@ -0,0 +1,20 @@
+-- migrations/019_idempotency_keys.sql
+-- Stored responses for Idempotency-Key retries on authenticated routes
+
+CREATE TABLE idempotency_keys (
+    callsign      TEXT NOT NULL,
+    key           TEXT NOT NULL,
+    -- SHA-256 of method, path and body; a retry must match it
+    request_hash  TEXT NOT NULL,
+    -- NULL while the original request is still being handled
+    status_code   SMALLINT,
+    content_type  TEXT,
+    response_body BYTEA,
+    -- When an in-flight claim can be taken over; NULL once the response is
+    -- stored
+    locked_until  TIMESTAMPTZ,
+    created_at    TIMESTAMPTZ NOT NULL DEFAULT now(),
+    PRIMARY KEY (callsign, key)
+);
+
+CREATE INDEX idx_idempotency_keys_created ON idempotency_keys(created_at);
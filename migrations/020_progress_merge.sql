@@ -0,0 +1,13 @@
+-- migrations/020_progress_merge.sql
+-- Per-goal first-completed timestamps so reports from several devices merge
+
+ALTER TABLE progress
+    ADD COLUMN goal_completions JSONB NOT NULL DEFAULT '{}';
+
+-- The first completion time of existing goals is unknown; the last update is
+-- the closest bound we have
+UPDATE progress
+SET goal_completions = (
+    SELECT COALESCE(jsonb_object_agg(goal, to_jsonb(updated_at)), '{}'::jsonb)
+    FROM jsonb_array_elements_text(completed_goals) AS goal
+);
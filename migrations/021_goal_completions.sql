@@ -0,0 +1,26 @@
+-- migrations/021_goal_completions.sql
+-- One row per completed goal, with the qualifying QSO as optional evidence
+
+CREATE TABLE goal_completions (
+    progress_id         UUID NOT NULL REFERENCES progress(id) ON DELETE CASCADE,
+    goal_id             TEXT NOT NULL,
+    completed_at        TIMESTAMPTZ NOT NULL,
+    -- Evidence; the other columns are only set with contacted_callsign
+    contacted_callsign  TEXT,
+    band                TEXT,
+    mode                TEXT,
+    qso_at              TIMESTAMPTZ,
+    confirmation_source TEXT,
+    PRIMARY KEY (progress_id, goal_id)
+);
+
+INSERT INTO goal_completions (progress_id, goal_id, completed_at)
+SELECT p.id, g.goal_id,
+       COALESCE((p.goal_completions ->> g.goal_id)::timestamptz, p.updated_at)
+FROM progress p,
+     jsonb_array_elements_text(p.completed_goals) AS g(goal_id)
+ON CONFLICT DO NOTHING;
+
+ALTER TABLE progress
+    DROP COLUMN completed_goals,
+    DROP COLUMN goal_completions;
@@ -0,0 +1,35 @@
+-- migrations/022_goal_status.sql
+-- Worked versus confirmed goal completions, with a score on each basis
+
+ALTER TABLE goal_completions
+    ADD COLUMN status TEXT NOT NULL DEFAULT 'worked'
+        CHECK (status IN ('worked', 'lotw', 'qsl'));
+
+UPDATE goal_completions
+SET status = confirmation_source
+WHERE confirmation_source IN ('lotw', 'qsl');
+
+-- `score` stays the official score, on whichever basis the challenge counts
+ALTER TABLE progress
+    ADD COLUMN worked_score INT NOT NULL DEFAULT 0,
+    ADD COLUMN confirmed_score INT NOT NULL DEFAULT 0;
+
+-- Every existing challenge counts worked goals
+UPDATE progress SET worked_score = score;
+
+-- Challenges whose stored scores the server recomputes in the background,
+-- e.g. to fill in confirmed_score with the same code that keeps it up to
+-- date, or to apply a changed scoring method. Badges, certificates and
+-- milestones are left alone.
+CREATE TABLE pending_rescores (
+    challenge_id UUID PRIMARY KEY REFERENCES challenges(id) ON DELETE CASCADE,
+    queued_at    TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+INSERT INTO pending_rescores (challenge_id)
+SELECT DISTINCT challenge_id FROM progress;
+
+CREATE INDEX idx_progress_leaderboard_worked
+    ON progress(challenge_id, worked_score DESC, updated_at ASC);
+CREATE INDEX idx_progress_leaderboard_confirmed
+    ON progress(challenge_id, confirmed_score DESC, updated_at ASC);
@@ -0,0 +1,25 @@
+-- migrations/023_certificates.sql
+-- Completion certificates with public verification codes, and per-challenge
+-- certificate templates
+
+CREATE TABLE certificates (
+    id                UUID PRIMARY KEY,
+    challenge_id      UUID NOT NULL REFERENCES challenges(id) ON DELETE CASCADE,
+    callsign          TEXT NOT NULL,
+    kind              TEXT NOT NULL CHECK (kind IN ('top_tier', 'completion')),
+    -- Names as they were when the certificate was issued
+    challenge_name    TEXT NOT NULL,
+    tier_id           TEXT,
+    tier_name         TEXT,
+    verification_code TEXT NOT NULL UNIQUE,
+    achieved_at       TIMESTAMPTZ NOT NULL DEFAULT now(),
+    UNIQUE(challenge_id, callsign, kind)
+);
+CREATE INDEX idx_certificates_callsign ON certificates(callsign);
+
+-- SVG with {{placeholders}}; challenges without one use the built-in design
+CREATE TABLE certificate_templates (
+    challenge_id UUID PRIMARY KEY REFERENCES challenges(id) ON DELETE CASCADE,
+    svg          TEXT NOT NULL,
+    updated_at   TIMESTAMPTZ NOT NULL DEFAULT now()
+);
@@ -0,0 +1,206 @@
+//! Award application packets: every goal of a challenge with the contact
+//! that completed it, as CSV for the sponsor's application form and as a
+//! printable page.
+
+use serde::Deserialize;
+
+use crate::models::{GoalCompletion, GoalStatus};
+
+/// Output format of an award application.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ApplicationFormat {
+    #[default]
+    Csv,
+    Html,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct ApplicationQuery {
+    #[serde(default)]
+    pub format: ApplicationFormat,
+}
+
+/// One goal of the challenge and its supporting contact. Fields the
+/// participant didn't report are empty.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ApplicationRow {
+    pub number: usize,
+    pub goal_id: String,
+    pub goal_name: String,
+    pub contacted_callsign: String,
+    pub band: String,
+    pub mode: String,
+    /// UTC date of the QSO, `YYYY-MM-DD`.
+    pub qso_date: String,
+    /// UTC time of the QSO, `HHMM`.
+    pub qso_time: String,
+    /// Worked, LoTW or QSL; empty when the goal isn't completed.
+    pub status: String,
+    pub confirmation_source: String,
+}
+
+const CSV_HEADER: [&str; 10] = [
+    "number",
+    "goal_id",
+    "goal_name",
+    "contacted_callsign",
+    "band",
+    "mode",
+    "qso_date",
+    "qso_time_utc",
+    "status",
+    "confirmation_source",
+];
+
+pub fn status_label(status: GoalStatus) -> &'static str {
+    match status {
+        GoalStatus::Worked => "Worked",
+        GoalStatus::Lotw => "LoTW",
+        GoalStatus::Qsl => "QSL",
+    }
+}
+
+/// A row for every item in `goals.items`, in configuration order.
+pub fn application_rows(
+    config: &serde_json::Value,
+    completions: &[GoalCompletion],
+) -> Vec<ApplicationRow> {
+    let items = config
+        .get("goals")
+        .and_then(|g| g.get("items"))
+        .and_then(|i| i.as_array())
+        .map(Vec::as_slice)
+        .unwrap_or_default();
+
+    items
+        .iter()
+        .filter_map(|item| Some((item.get("id")?.as_str()?, item)))
+        .enumerate()
+        .map(|(i, (goal_id, item))| {
+            let goal_name = item
+                .get("name")
+                .and_then(|n| n.as_str())
+                .unwrap_or(goal_id)
+                .to_string();
+            let completion = completions.iter().find(|c| c.goal_id == goal_id);
+            let evidence = completion.and_then(|c| c.evidence.as_ref());
+            let qso_at = evidence.and_then(|e| e.qso_at);
+
+            ApplicationRow {
+                number: i + 1,
+                goal_id: goal_id.to_string(),
+                goal_name,
+                contacted_callsign: evidence
+                    .map(|e| e.contacted_callsign.clone())
+                    .unwrap_or_default(),
+                band: evidence.and_then(|e| e.band.clone()).unwrap_or_default(),
+                mode: evidence.and_then(|e| e.mode.clone()).unwrap_or_default(),
+                qso_date: qso_at
+                    .map(|at| at.format("%Y-%m-%d").to_string())
+                    .unwrap_or_default(),
+                qso_time: qso_at
+                    .map(|at| at.format("%H%M").to_string())
+                    .unwrap_or_default(),
+                status: completion
+                    .map(|c| status_label(c.status).to_string())
+                    .unwrap_or_default(),
+                confirmation_source: evidence
+                    .and_then(|e| e.confirmation_source.clone())
+                    .unwrap_or_default(),
+            }
+        })
+        .collect()
+}
+
+/// Rows as CSV with a header line.
+pub fn to_csv(rows: &[ApplicationRow]) -> String {
+    let mut csv = CSV_HEADER.join(",");
+    csv.push_str("\r\n");
+
+    for row in rows {
+        let number = row.number.to_string();
+        let cells = [
+            number.as_str(),
+            &row.goal_id,
+            &row.goal_name,
+            &row.contacted_callsign,
+            &row.band,
+            &row.mode,
+            &row.qso_date,
+            &row.qso_time,
+            &row.status,
+            &row.confirmation_source,
+        ];
+        let line: Vec<String> = cells.iter().map(|c| csv_cell(c)).collect();
+        csv.push_str(&line.join(","));
+        csv.push_str("\r\n");
+    }
+
+    csv
+}
+
+/// Quote a cell when needed. User-supplied text that a spreadsheet would
+/// evaluate as a formula is prefixed with `'`.
+fn csv_cell(value: &str) -> String {
+    let value = if value.starts_with(['=', '+', '-', '@', '\t', '\r']) {
+        format!("'{}", value)
+    } else {
+        value.to_string()
+    };
+
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::GoalEvidence;
+    use chrono::{TimeZone, Utc};
+    use serde_json::json;
+
+    #[test]
+    fn test_application_rows() {
+        let config = json!({
+            "goals": { "type": "collection", "items": [
+                { "id": "US-AK", "name": "Alaska" },
+                { "id": "US-HI", "name": "Hawaii" }
+            ] }
+        });
+        let completions = [GoalCompletion {
+            goal_id: "US-AK".to_string(),
+            completed_at: Utc::now(),
+            status: GoalStatus::Lotw,
+            evidence: Some(GoalEvidence {
+                contacted_callsign: "KL7XX".to_string(),
+                band: Some("20m".to_string()),
+                mode: Some("CW".to_string()),
+                qso_at: Some(Utc.with_ymd_and_hms(2026, 3, 4, 19, 2, 0).unwrap()),
+                confirmation_source: Some("lotw".to_string()),
+            }),
+        }];
+
+        let rows = application_rows(&config, &completions);
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].goal_name, "Alaska");
+        assert_eq!(rows[0].contacted_callsign, "KL7XX");
+        assert_eq!(rows[0].qso_date, "2026-03-04");
+        assert_eq!(rows[0].qso_time, "1902");
+        assert_eq!(rows[0].status, "LoTW");
+        assert_eq!(rows[1].number, 2);
+        assert_eq!(rows[1].status, "");
+    }
+
+    #[test]
+    fn test_csv_cell() {
+        assert_eq!(csv_cell("KL7XX"), "KL7XX");
+        assert_eq!(csv_cell("Washington, DC"), "\"Washington, DC\"");
+        assert_eq!(csv_cell("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(csv_cell("=HYPERLINK(\"x\")"), "\"'=HYPERLINK(\"\"x\"\")\"");
+        assert_eq!(csv_cell("@SUM"), "'@SUM");
+    }
+}
@@ -0,0 +1,405 @@
+//! Completion certificates: which achievements earn one, verification codes,
+//! and rendering a challenge's SVG template.
+//!
+//! Templates are SVG with `{{placeholder}}` fields. They are written by
+//! admins but served from our origin, so anything that could run script is
+//! rejected and the image is served under a restrictive CSP as well.
+
+use rand::Rng;
+
+use crate::models::{Certificate, CertificateKind, GoalStatus};
+use crate::scoring::{
+    calculate_percentage, determine_tier, get_total_goals, goals_on_basis, score_basis,
+};
+
+pub const MAX_TEMPLATE_SIZE: usize = 256 * 1024; // 256KB
+
+/// Sent with every rendered certificate so a template can't load or run
+/// anything even if validation missed it.
+pub const CERTIFICATE_CSP: &str =
+    "default-src 'none'; style-src 'unsafe-inline'; img-src data:; font-src data:";
+
+/// Fields a template can use.
+pub const PLACEHOLDERS: &[&str] = &[
+    "callsign",
+    "challengeName",
+    "achievement",
+    "tierName",
+    "achievedDate",
+    "verificationCode",
+    "verifyUrl",
+];
+
+/// Markup a template may not contain (compared in lowercase, after decoding
+/// character references).
+const FORBIDDEN_MARKUP: &[&str] = &["<script", "<foreignobject", "<iframe", "javascript:"];
+
+/// Verification code alphabet, without 0/O and 1/I so codes can be typed
+/// from a printout.
+const CODE_ALPHABET: &[u8] = b"ABCDEFGHJKLMNPQRSTUVWXYZ23456789";
+
+/// Used when a challenge has no template of its own.
+pub const DEFAULT_TEMPLATE: &str = r##"<svg xmlns="http://www.w3.org/2000/svg" width="1100" height="850" viewBox="0 0 1100 850">
+  <rect width="1100" height="850" fill="#fffdf7"/>
+  <rect x="30" y="30" width="1040" height="790" fill="none" stroke="#1f3a5f" stroke-width="6"/>
+  <rect x="48" y="48" width="1004" height="754" fill="none" stroke="#c9a227" stroke-width="2"/>
+  <g font-family="Georgia, 'Times New Roman', serif" text-anchor="middle" fill="#1f3a5f">
+    <text x="550" y="170" font-size="56" letter-spacing="4">CERTIFICATE</text>
+    <text x="550" y="215" font-size="24" letter-spacing="6" fill="#c9a227">OF ACHIEVEMENT</text>
+    <text x="550" y="300" font-size="22" font-style="italic" fill="#444">This certifies that</text>
+    <text x="550" y="390" font-size="72" font-weight="bold" font-family="'Courier New', monospace">{{callsign}}</text>
+    <text x="550" y="460" font-size="22" font-style="italic" fill="#444">has achieved</text>
+    <text x="550" y="520" font-size="40">{{achievement}}</text>
+    <text x="550" y="570" font-size="26">{{challengeName}}</text>
+    <text x="550" y="650" font-size="20" fill="#444">{{achievedDate}}</text>
+  </g>
+  <g font-family="'Courier New', monospace" font-size="14" fill="#666" text-anchor="middle">
+    <text x="550" y="740">Verification code {{verificationCode}}</text>
+    <text x="550" y="762">{{verifyUrl}}</text>
+  </g>
+</svg>
+"##;
+
+/// A certificate the participant qualifies for.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EarnedCertificate {
+    pub kind: CertificateKind,
+    pub tier_id: Option<String>,
+    pub tier_name: Option<String>,
+}
+
+/// Last tier in `tiers`, the one `determine_tier` reports once every
+/// threshold is met: `(id, name)`.
+pub fn top_tier(config: &serde_json::Value) -> Option<(String, Option<String>)> {
+    let tier = config
+        .get("tiers")
+        .and_then(|t| t.as_array())?
+        .iter()
+        .rfind(|tier| tier.get("id").and_then(|id| id.as_str()).is_some())?;
+
+    Some((
+        tier.get("id")?.as_str()?.to_string(),
+        tier.get("name").and_then(|n| n.as_str()).map(String::from),
+    ))
+}
+
+/// Certificates earned by a participant with these goals, current value and
+/// score: the top tier, and 100% of a collection.
+pub fn earned_certificates(
+    config: &serde_json::Value,
+    goals: &[(String, GoalStatus)],
+    current_value: i32,
+    score: i32,
+) -> Vec<EarnedCertificate> {
+    let mut earned = Vec::new();
+
+    if let Some((tier_id, tier_name)) = top_tier(config) {
+        if determine_tier(config, score).as_ref() == Some(&tier_id) {
+            earned.push(EarnedCertificate {
+                kind: CertificateKind::TopTier,
+                tier_id: Some(tier_id),
+                tier_name,
+            });
+        }
+    }
+
+    if get_total_goals(config) > 0 {
+        let counted = goals_on_basis(config, goals, score_basis(config));
+        if calculate_percentage(config, &counted, current_value) >= 100.0 {
+            earned.push(EarnedCertificate {
+                kind: CertificateKind::Completion,
+                tier_id: None,
+                tier_name: None,
+            });
+        }
+    }
+
+    earned
+}
+
+/// New verification code, e.g. `CW-7KQ2-XM4P-9D3R`.
+pub fn generate_verification_code() -> String {
+    let mut rng = rand::thread_rng();
+    let groups: Vec<String> = (0..3)
+        .map(|_| {
+            (0..4)
+                .map(|_| CODE_ALPHABET[rng.gen_range(0..CODE_ALPHABET.len())] as char)
+                .collect()
+        })
+        .collect();
+    format!("CW-{}", groups.join("-"))
+}
+
+/// Check an admin-supplied template. Returns a message for the validation
+/// error.
+pub fn validate_template(svg: &str) -> Result<(), String> {
+    if svg.len() > MAX_TEMPLATE_SIZE {
+        return Err(format!("svg must be at most {} bytes", MAX_TEMPLATE_SIZE));
+    }
+
+    let trimmed = svg.trim_start();
+    let lower = svg.to_lowercase();
+    if !(trimmed.starts_with("<svg") || trimmed.starts_with("<?xml")) || !lower.contains("<svg") {
+        return Err("svg must be an SVG document".to_string());
+    }
+
+    let decoded = decode_char_refs(&lower).to_lowercase();
+    if let Some(markup) = FORBIDDEN_MARKUP
+        .iter()
+        .find(|m| lower.contains(*m) || decoded.contains(*m))
+    {
+        return Err(format!("svg may not contain {}", markup));
+    }
+    if has_event_handler(&lower) || has_event_handler(&decoded) {
+        return Err("svg may not contain event handler attributes".to_string());
+    }
+
+    let mut names = Vec::new();
+    substitute(svg, |name| {
+        names.push(name.to_string());
+        Some(String::new())
+    })?;
+    if let Some(unknown) = names.iter().find(|n| !PLACEHOLDERS.contains(&n.as_str())) {
+        return Err(format!(
+            "Unknown placeholder {{{{{}}}}}. Allowed: {}",
+            unknown,
+            PLACEHOLDERS.join(", ")
+        ));
+    }
+    if !names.iter().any(|n| n == "verificationCode") {
+        return Err("svg must include {{verificationCode}}".to_string());
+    }
+
+    Ok(())
+}
+
+/// Fill in a template for `certificate`. Values are XML-escaped.
+pub fn render(template: &str, certificate: &Certificate, verify_url: &str) -> String {
+    let value = |name: &str| -> Option<String> {
+        Some(match name {
+            "callsign" => certificate.callsign.clone(),
+            "challengeName" => certificate.challenge_name.clone(),
+            "achievement" => certificate.achievement(),
+            "tierName" => certificate.tier_name.clone().unwrap_or_default(),
+            "achievedDate" => certificate.achieved_at.format("%-d %B %Y").to_string(),
+            "verificationCode" => certificate.verification_code.clone(),
+            "verifyUrl" => verify_url.to_string(),
+            _ => return None,
+        })
+    };
+
+    // Stored templates have been validated; an unknown field renders empty
+    substitute(template, |name| {
+        Some(xml_escape(&value(name).unwrap_or_default()))
+    })
+    .unwrap_or_else(|_| template.to_string())
+}
+
+/// Replace each `{{name}}` in one pass, so substituted values are never
+/// scanned for placeholders themselves.
+fn substitute(
+    template: &str,
+    mut value: impl FnMut(&str) -> Option<String>,
+) -> Result<String, String> {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+
+    while let Some(start) = rest.find("{{") {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        let end = after
+            .find("}}")
+            .ok_or_else(|| "Unclosed {{ in svg".to_string())?;
+        let name = after[..end].trim();
+        out.push_str(&value(name).unwrap_or_default());
+        rest = &after[end + 2..];
+    }
+    out.push_str(rest);
+
+    Ok(out)
+}
+
+/// Whether any attribute looks like `onload=` or `onclick =`. Anything that
+/// can't be part of an attribute name starts one, e.g. `<svg/onload=`.
+fn has_event_handler(lower: &str) -> bool {
+    let bytes = lower.as_bytes();
+    let is_name_byte = |b: u8| b.is_ascii_alphanumeric() || matches!(b, b'-' | b'_' | b':' | b'.');
+    lower.match_indices("on").any(|(i, _)| {
+        let at_boundary = i == 0 || !is_name_byte(bytes[i - 1]);
+        let name_len = bytes[i + 2..]
+            .iter()
+            .take_while(|b| b.is_ascii_alphabetic())
+            .count();
+        let after_name =
+            lower[i + 2 + name_len..].trim_start_matches(|c: char| !c.is_ascii_graphic());
+        at_boundary && name_len > 0 && after_name.starts_with('=')
+    })
+}
+
+/// Decode numeric character references and the named ones that can spell
+/// out a URL scheme, and drop the tabs and newlines browsers ignore in URLs,
+/// so `&#106;avascript:` or `java&tab;script:` reads as `javascript:`.
+fn decode_char_refs(svg: &str) -> String {
+    let mut out = String::with_capacity(svg.len());
+    let mut rest = svg;
+
+    while let Some(start) = rest.find('&') {
+        out.push_str(&rest[..start]);
+        rest = &rest[start + 1..];
+
+        let name_len = rest
+            .bytes()
+            .take_while(|b| b.is_ascii_alphanumeric() || *b == b'#')
+            .count();
+        let name = &rest[..name_len];
+        let decoded = match name.strip_prefix('#') {
+            Some(hex) if hex.starts_with('x') => u32::from_str_radix(&hex[1..], 16).ok(),
+            Some(dec) => dec.parse().ok(),
+            None => match name {
+                "colon" => Some(':' as u32),
+                "tab" => Some('\t' as u32),
+                "newline" => Some('\n' as u32),
+                _ => None,
+            },
+        };
+
+        match decoded.and_then(char::from_u32) {
+            Some(c) => {
+                out.push(c);
+                rest = &rest[name_len..];
+                rest = rest.strip_prefix(';').unwrap_or(rest);
+            }
+            None => out.push('&'),
+        }
+    }
+    out.push_str(rest);
+
+    out.retain(|c| !matches!(c, '\t' | '\n' | '\r'));
+    out
+}
+
+fn xml_escape(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+    use serde_json::json;
+    use uuid::Uuid;
+
+    fn certificate(kind: CertificateKind) -> Certificate {
+        Certificate {
+            challenge_id: Uuid::new_v4(),
+            callsign: "W1AW".to_string(),
+            kind: kind.as_str().to_string(),
+            challenge_name: "Parks & Summits".to_string(),
+            tier_id: Some("tier-gold".to_string()),
+            tier_name: Some("Gold".to_string()),
+            verification_code: "CW-ABCD-EFGH-JKLM".to_string(),
+            achieved_at: Utc.with_ymd_and_hms(2026, 10, 3, 12, 0, 0).unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_earned_certificates() {
+        let config = json!({
+            "goals": { "type": "collection", "items": [{ "id": "A" }, { "id": "B" }] },
+            "tiers": [
+                { "id": "tier-1", "name": "One", "threshold": 1 },
+                { "id": "tier-2", "name": "Two", "threshold": 2 }
+            ]
+        });
+        let one = vec![("A".to_string(), GoalStatus::Worked)];
+        let both = vec![
+            ("A".to_string(), GoalStatus::Worked),
+            ("B".to_string(), GoalStatus::Qsl),
+        ];
+
+        assert!(earned_certificates(&config, &one, 0, 1).is_empty());
+
+        let earned = earned_certificates(&config, &both, 0, 2);
+        assert_eq!(earned.len(), 2);
+        assert_eq!(earned[0].kind, CertificateKind::TopTier);
+        assert_eq!(earned[0].tier_name.as_deref(), Some("Two"));
+        assert_eq!(earned[1].kind, CertificateKind::Completion);
+
+        // Counting confirmed goals only, the worked goal doesn't complete it
+        let mut confirmed = config.clone();
+        confirmed["scoring"] = json!({ "countedStatuses": ["lotw", "qsl"] });
+        confirmed["tiers"] = json!([]);
+        assert!(earned_certificates(&confirmed, &both, 0, 1).is_empty());
+    }
+
+    #[test]
+    fn test_validate_template() {
+        assert!(validate_template(DEFAULT_TEMPLATE).is_ok());
+        assert!(validate_template("<svg>{{verificationCode}}</svg>").is_ok());
+        assert!(validate_template("<html>{{verificationCode}}</html>").is_err());
+        assert!(validate_template("<svg>{{callsign}}</svg>").is_err());
+        assert!(validate_template("<svg>{{verificationCode}} {{score}}</svg>").is_err());
+        assert!(validate_template("<svg>{{verificationCode}</svg>").is_err());
+        assert!(
+            validate_template("<svg><script>alert(1)</script>{{verificationCode}}</svg>").is_err()
+        );
+        assert!(validate_template("<svg onload=\"alert(1)\">{{verificationCode}}</svg>").is_err());
+        assert!(validate_template("<svg/onload=alert(1)>{{verificationCode}}</svg>").is_err());
+        assert!(validate_template("<svg\x0conload=alert(1)>{{verificationCode}}</svg>").is_err());
+        assert!(validate_template(
+            "<svg><a href=\"&#106;avascript:alert(1)\">{{verificationCode}}</a></svg>"
+        )
+        .is_err());
+        assert!(validate_template(
+            "<svg><a href=\"&#74;avascript:alert(1)\">{{verificationCode}}</a></svg>"
+        )
+        .is_err());
+        assert!(validate_template(
+            "<svg><a href=\"java&#x9;script&colon;alert(1)\">{{verificationCode}}</a></svg>"
+        )
+        .is_err());
+        assert!(validate_template(
+            "<svg><text font-family=\"Monaco\">{{verificationCode}}</text></svg>"
+        )
+        .is_ok());
+    }
+
+    #[test]
+    fn test_render_escapes_values() {
+        let svg = render(
+            "<svg>{{callsign}} {{achievement}} {{challengeName}} {{achievedDate}}</svg>",
+            &certificate(CertificateKind::TopTier),
+            "https://example.com",
+        );
+        assert_eq!(
+            svg,
+            "<svg>W1AW Gold Parks &amp; Summits 3 October 2026</svg>"
+        );
+
+        let mut cert = certificate(CertificateKind::Completion);
+        cert.challenge_name = "{{verifyUrl}}".to_string();
+        let svg = render("<svg>{{achievement}} {{challengeName}}</svg>", &cert, "x");
+        assert_eq!(svg, "<svg>Completed {{verifyUrl}}</svg>");
+    }
+
+    #[test]
+    fn test_verification_code_format() {
+        let code = generate_verification_code();
+        assert_eq!(code.len(), 17);
+        assert!(code.starts_with("CW-"));
+        assert!(code[3..]
+            .bytes()
+            .all(|b| b == b'-' || CODE_ALPHABET.contains(&b)));
+    }
+}
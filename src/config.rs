@@ -9,6 +9,11 @@ pub struct Config {
     pub base_url: Option<String>,
     pub invite_base_url: String,
     pub invite_expiry_days: i64,
+    pub challenge_retention_days: i64,
+    pub friend_request_expiry_days: i64,
+    pub friend_request_cooldown_days: i64,
+    pub custom_activity_max_bytes: usize,
+    pub idempotency_key_ttl_hours: i64,
 }
 
 impl Config {
@@ -34,6 +39,31 @@ impl Config {
             .parse()
             .map_err(|_| ConfigError::Invalid("INVITE_EXPIRY_DAYS must be a number"))?;
 
+        let challenge_retention_days = env::var("CHALLENGE_RETENTION_DAYS")
+            .unwrap_or_else(|_| "30".to_string())
+            .parse()
+            .map_err(|_| ConfigError::Invalid("CHALLENGE_RETENTION_DAYS must be a number"))?;
+
+        let friend_request_expiry_days = env::var("FRIEND_REQUEST_EXPIRY_DAYS")
+            .unwrap_or_else(|_| "30".to_string())
+            .parse()
+            .map_err(|_| ConfigError::Invalid("FRIEND_REQUEST_EXPIRY_DAYS must be a number"))?;
+
+        let friend_request_cooldown_days = env::var("FRIEND_REQUEST_COOLDOWN_DAYS")
+            .unwrap_or_else(|_| "7".to_string())
+            .parse()
+            .map_err(|_| ConfigError::Invalid("FRIEND_REQUEST_COOLDOWN_DAYS must be a number"))?;
+
+        let custom_activity_max_bytes = env::var("CUSTOM_ACTIVITY_MAX_BYTES")
+            .unwrap_or_else(|_| "4096".to_string())
+            .parse()
+            .map_err(|_| ConfigError::Invalid("CUSTOM_ACTIVITY_MAX_BYTES must be a number"))?;
+
+        let idempotency_key_ttl_hours = env::var("IDEMPOTENCY_KEY_TTL_HOURS")
+            .unwrap_or_else(|_| "24".to_string())
+            .parse()
+            .map_err(|_| ConfigError::Invalid("IDEMPOTENCY_KEY_TTL_HOURS must be a number"))?;
+
         Ok(Self {
             database_url,
             admin_token,
@@ -41,6 +71,11 @@ impl Config {
             base_url,
             invite_base_url,
             invite_expiry_days,
+            challenge_retention_days,
+            friend_request_expiry_days,
+            friend_request_cooldown_days,
+            custom_activity_max_bytes,
+            idempotency_key_ttl_hours,
         })
     }
 }
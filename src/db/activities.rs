@@ -1,82 +1,216 @@
-use chrono::{DateTime, Utc};
 use sqlx::PgPool;
 use uuid::Uuid;
 
 use crate::error::AppError;
-use crate::models::activity::{Activity, FeedItemRow};
+use crate::models::activity::{
+    Activity, FeedFilter, FeedItemRow, FeedScope, ReportActivityRequest,
+};
+use crate::models::ActivityAudience;
 
-/// Insert a new activity and return the created row.
+/// Insert a new activity and return the created row. Returns `None` if the
+/// user already has an activity with the same `dedupe_key`.
 pub async fn insert_activity(
+    pool: &PgPool,
+    user_id: Uuid,
+    callsign: &str,
+    req: &ReportActivityRequest,
+    audience: ActivityAudience,
+    dedupe_key: Option<&str>,
+) -> Result<Option<Activity>, AppError> {
+    let activity = sqlx::query_as::<_, Activity>(
+        r#"
+        INSERT INTO activities (user_id, callsign, activity_type, timestamp, details, audience, dedupe_key)
+        VALUES ($1, $2, $3, $4, $5, $6, $7)
+        ON CONFLICT (user_id, dedupe_key) WHERE dedupe_key IS NOT NULL DO NOTHING
+        RETURNING id, user_id, callsign, activity_type, timestamp, details, audience, created_at
+        "#,
+    )
+    .bind(user_id)
+    .bind(callsign)
+    .bind(&req.activity_type)
+    .bind(req.timestamp)
+    .bind(&req.details)
+    .bind(audience.as_str())
+    .bind(dedupe_key)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(activity)
+}
+
+/// A user's activity with the given deduplication key.
+pub async fn get_activity_by_dedupe_key(
+    pool: &PgPool,
+    user_id: Uuid,
+    dedupe_key: &str,
+) -> Result<Option<Activity>, AppError> {
+    let activity = sqlx::query_as::<_, Activity>(
+        r#"
+        SELECT id, user_id, callsign, activity_type, timestamp, details, audience, created_at
+        FROM activities
+        WHERE user_id = $1 AND dedupe_key = $2
+        "#,
+    )
+    .bind(user_id)
+    .bind(dedupe_key)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(activity)
+}
+
+/// Insert a server-generated milestone activity with the user's default
+/// audience, unless they turned off `share_milestones` or already have an
+/// activity with the same `dedupe_key`. Returns whether one was inserted.
+pub async fn insert_milestone_activity(
     pool: &PgPool,
     user_id: Uuid,
     callsign: &str,
     activity_type: &str,
-    timestamp: DateTime<Utc>,
     details: &serde_json::Value,
-) -> Result<Activity, AppError> {
-    let activity = sqlx::query_as::<_, Activity>(
+    dedupe_key: Option<&str>,
+) -> Result<bool, AppError> {
+    let result = sqlx::query(
         r#"
-        INSERT INTO activities (user_id, callsign, activity_type, timestamp, details)
-        VALUES ($1, $2, $3, $4, $5)
-        RETURNING id, user_id, callsign, activity_type, timestamp, details, created_at
+        INSERT INTO activities (user_id, callsign, activity_type, timestamp, details, audience, dedupe_key)
+        SELECT u.user_id, $2, $3, now(), $4,
+               COALESCE(s.default_activity_audience, 'friends'), $5
+        FROM (SELECT $1::uuid as user_id) u
+        LEFT JOIN user_privacy_settings s ON s.user_id = u.user_id
+        WHERE COALESCE(s.share_milestones, true)
+        ON CONFLICT (user_id, dedupe_key) WHERE dedupe_key IS NOT NULL DO NOTHING
         "#,
     )
     .bind(user_id)
     .bind(callsign)
     .bind(activity_type)
-    .bind(timestamp)
     .bind(details)
-    .fetch_one(pool)
+    .bind(dedupe_key)
+    .execute(pool)
     .await?;
 
-    Ok(activity)
+    Ok(result.rows_affected() > 0)
 }
 
-/// Get the activity feed for a user: activities from their friends,
-/// cursor-paginated by created_at DESC.
+/// Get the activity feed for a user: their friends' non-private activities
+/// (excluding muted and blocked users) and/or their own activities,
+/// depending on `scope`, with reaction and comment counts. Ordered by
+/// `(created_at, id)` descending.
 pub async fn get_feed_for_user(
     pool: &PgPool,
     user_id: Uuid,
-    limit: i64,
-    before: Option<DateTime<Utc>>,
+    scope: FeedScope,
+    filter: &FeedFilter,
 ) -> Result<Vec<FeedItemRow>, AppError> {
-    let limit = limit.min(100).max(1);
+    let limit = filter.limit.clamp(1, 101);
 
-    let rows = if let Some(cursor) = before {
-        sqlx::query_as::<_, FeedItemRow>(
-            r#"
-            SELECT a.id, a.callsign, a.user_id, a.activity_type,
-                   a.timestamp, a.details, a.created_at
-            FROM activities a
-            JOIN friendships f ON f.friend_id = a.user_id
-            WHERE f.user_id = $1
-              AND a.created_at < $2
-            ORDER BY a.created_at DESC
-            LIMIT $3
-            "#,
-        )
-        .bind(user_id)
-        .bind(cursor)
-        .bind(limit)
-        .fetch_all(pool)
-        .await?
-    } else {
-        sqlx::query_as::<_, FeedItemRow>(
-            r#"
-            SELECT a.id, a.callsign, a.user_id, a.activity_type,
-                   a.timestamp, a.details, a.created_at
-            FROM activities a
-            JOIN friendships f ON f.friend_id = a.user_id
-            WHERE f.user_id = $1
-            ORDER BY a.created_at DESC
-            LIMIT $2
-            "#,
+    let rows = sqlx::query_as::<_, FeedItemRow>(
+        r#"
+        SELECT a.id, a.callsign, a.user_id, pr.display_name, a.activity_type,
+               a.timestamp, a.details, a.created_at,
+               COALESCE((
+                   SELECT jsonb_object_agg(rc.kind, rc.n)
+                   FROM (
+                       SELECT kind, COUNT(*) AS n FROM activity_reactions
+                       WHERE activity_id = a.id GROUP BY kind
+                   ) rc
+               ), '{}'::jsonb) AS reaction_counts,
+               (SELECT COUNT(*) FROM activity_comments c
+                WHERE c.activity_id = a.id AND c.deleted_at IS NULL) AS comment_count,
+               ARRAY(SELECT r.kind FROM activity_reactions r
+                     WHERE r.activity_id = a.id AND r.user_id = $1
+                     ORDER BY r.created_at) AS my_reactions
+        FROM activities a
+        LEFT JOIN user_profiles pr ON pr.user_id = a.user_id
+        WHERE (
+            ($2 AND a.user_id = $1)
+            OR ($3
+                AND a.audience IN ('public', 'friends')
+                AND EXISTS (
+                    SELECT 1 FROM friendships f
+                    WHERE f.user_id = $1 AND f.friend_id = a.user_id
+                )
+                AND NOT EXISTS (
+                    SELECT 1 FROM user_mutes m
+                    WHERE m.user_id = $1 AND m.muted_user_id = a.user_id
+                )
+                AND NOT EXISTS (
+                    SELECT 1 FROM user_blocks b
+                    WHERE (b.blocker_id = $1 AND b.blocked_id = a.user_id)
+                       OR (b.blocker_id = a.user_id AND b.blocked_id = $1)
+                ))
         )
-        .bind(user_id)
-        .bind(limit)
-        .fetch_all(pool)
-        .await?
-    };
+          AND ($4::text[] IS NULL OR a.activity_type = ANY($4))
+          AND ($5::timestamptz IS NULL OR (a.created_at, a.id) < ($5, $6))
+        ORDER BY a.created_at DESC, a.id DESC
+        LIMIT $7
+        "#,
+    )
+    .bind(user_id)
+    .bind(scope.includes_own())
+    .bind(scope.includes_friends())
+    .bind(&filter.activity_types)
+    .bind(filter.before.map(|c| c.created_at))
+    .bind(filter.before.map(|c| c.id))
+    .bind(limit)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows)
+}
+
+/// One user's activities as seen by `viewer_id`: everything for the user
+/// themself, public and friends-only activities for friends, and public
+/// activities for everyone else. Ordered by `(created_at, id)` descending.
+pub async fn get_user_timeline(
+    pool: &PgPool,
+    user_id: Uuid,
+    viewer_id: Option<Uuid>,
+    filter: &FeedFilter,
+) -> Result<Vec<FeedItemRow>, AppError> {
+    let limit = filter.limit.clamp(1, 101);
+
+    let rows = sqlx::query_as::<_, FeedItemRow>(
+        r#"
+        SELECT a.id, a.callsign, a.user_id, pr.display_name, a.activity_type,
+               a.timestamp, a.details, a.created_at,
+               COALESCE((
+                   SELECT jsonb_object_agg(rc.kind, rc.n)
+                   FROM (
+                       SELECT kind, COUNT(*) AS n FROM activity_reactions
+                       WHERE activity_id = a.id GROUP BY kind
+                   ) rc
+               ), '{}'::jsonb) AS reaction_counts,
+               (SELECT COUNT(*) FROM activity_comments c
+                WHERE c.activity_id = a.id AND c.deleted_at IS NULL) AS comment_count,
+               ARRAY(SELECT r.kind FROM activity_reactions r
+                     WHERE r.activity_id = a.id AND r.user_id = $2
+                     ORDER BY r.created_at) AS my_reactions
+        FROM activities a
+        LEFT JOIN user_profiles pr ON pr.user_id = a.user_id
+        WHERE a.user_id = $1
+          AND (
+              a.audience = 'public'
+              OR a.user_id = $2
+              OR (a.audience = 'friends' AND EXISTS (
+                  SELECT 1 FROM friendships f
+                  WHERE f.user_id = $2 AND f.friend_id = a.user_id
+              ))
+          )
+          AND ($3::text[] IS NULL OR a.activity_type = ANY($3))
+          AND ($4::timestamptz IS NULL OR (a.created_at, a.id) < ($4, $5))
+        ORDER BY a.created_at DESC, a.id DESC
+        LIMIT $6
+        "#,
+    )
+    .bind(user_id)
+    .bind(viewer_id)
+    .bind(&filter.activity_types)
+    .bind(filter.before.map(|c| c.created_at))
+    .bind(filter.before.map(|c| c.id))
+    .bind(limit)
+    .fetch_all(pool)
+    .await?;
 
     Ok(rows)
 }
@@ -52,6 +52,26 @@ pub async fn list_badges(
     Ok(badges)
 }
 
+/// List badges for a challenge including image data.
+pub async fn list_badges_with_images(
+    pool: &PgPool,
+    challenge_id: Uuid,
+) -> Result<Vec<Badge>, AppError> {
+    let badges = sqlx::query_as::<_, Badge>(
+        r#"
+        SELECT id, challenge_id, name, tier_id, image_data, content_type, created_at
+        FROM badges
+        WHERE challenge_id = $1
+        ORDER BY created_at ASC
+        "#,
+    )
+    .bind(challenge_id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(badges)
+}
+
 pub async fn get_badge(pool: &PgPool, badge_id: Uuid) -> Result<Option<Badge>, AppError> {
     let badge = sqlx::query_as::<_, Badge>(
         r#"
@@ -75,3 +95,72 @@ pub async fn delete_badge(pool: &PgPool, badge_id: Uuid) -> Result<bool, AppErro
 
     Ok(result.rows_affected() > 0)
 }
+
+/// Record earned badges for every tier badge of the challenge whose tier is
+/// in `tier_ids`. Returns the IDs of badges that were newly earned.
+pub async fn award_tier_badges<'e, E>(
+    executor: E,
+    challenge_id: Uuid,
+    callsign: &str,
+    tier_ids: &[String],
+) -> Result<Vec<Uuid>, AppError>
+where
+    E: sqlx::PgExecutor<'e>,
+{
+    if tier_ids.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let callsign_upper = callsign.to_uppercase();
+
+    let awarded: Vec<(Uuid,)> = sqlx::query_as(
+        r#"
+        INSERT INTO earned_badges (id, badge_id, callsign)
+        SELECT gen_random_uuid(), b.id, $2
+        FROM badges b
+        WHERE b.challenge_id = $1 AND b.tier_id = ANY($3)
+        ON CONFLICT (badge_id, callsign) DO NOTHING
+        RETURNING badge_id
+        "#,
+    )
+    .bind(challenge_id)
+    .bind(&callsign_upper)
+    .bind(tier_ids)
+    .fetch_all(executor)
+    .await?;
+
+    Ok(awarded.into_iter().map(|(id,)| id).collect())
+}
+
+/// Remove earned tier badges of the challenge whose tier is no longer in
+/// `tier_ids`. Returns the number of badges revoked.
+pub async fn revoke_tier_badges<'e, E>(
+    executor: E,
+    challenge_id: Uuid,
+    callsign: &str,
+    tier_ids: &[String],
+) -> Result<u64, AppError>
+where
+    E: sqlx::PgExecutor<'e>,
+{
+    let callsign_upper = callsign.to_uppercase();
+
+    let result = sqlx::query(
+        r#"
+        DELETE FROM earned_badges eb
+        USING badges b
+        WHERE eb.badge_id = b.id
+          AND b.challenge_id = $1
+          AND eb.callsign = $2
+          AND b.tier_id IS NOT NULL
+          AND NOT (b.tier_id = ANY($3))
+        "#,
+    )
+    .bind(challenge_id)
+    .bind(&callsign_upper)
+    .bind(tier_ids)
+    .execute(executor)
+    .await?;
+
+    Ok(result.rows_affected())
+}
@@ -0,0 +1,206 @@
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::error::AppError;
+use crate::models::RelatedUser;
+
+/// Block a user. Removes any friendship and pending friend requests between
+/// the two users, and their reactions and comments on each other's
+/// activities. Blocking an already-blocked user is a no-op.
+pub async fn block_user(pool: &PgPool, blocker_id: Uuid, blocked_id: Uuid) -> Result<(), AppError> {
+    let mut tx = pool.begin().await?;
+
+    sqlx::query(
+        r#"
+        INSERT INTO user_blocks (blocker_id, blocked_id)
+        VALUES ($1, $2)
+        ON CONFLICT DO NOTHING
+        "#,
+    )
+    .bind(blocker_id)
+    .bind(blocked_id)
+    .execute(&mut *tx)
+    .await?;
+
+    sqlx::query(
+        r#"
+        DELETE FROM friendships
+        WHERE (user_id = $1 AND friend_id = $2)
+           OR (user_id = $2 AND friend_id = $1)
+        "#,
+    )
+    .bind(blocker_id)
+    .bind(blocked_id)
+    .execute(&mut *tx)
+    .await?;
+
+    sqlx::query(
+        r#"
+        DELETE FROM friend_requests
+        WHERE status = 'pending'
+          AND ((from_user_id = $1 AND to_user_id = $2)
+            OR (from_user_id = $2 AND to_user_id = $1))
+        "#,
+    )
+    .bind(blocker_id)
+    .bind(blocked_id)
+    .execute(&mut *tx)
+    .await?;
+
+    // A blocked user is no longer a friend, so a mute is meaningless
+    sqlx::query(
+        r#"
+        DELETE FROM user_mutes
+        WHERE (user_id = $1 AND muted_user_id = $2)
+           OR (user_id = $2 AND muted_user_id = $1)
+        "#,
+    )
+    .bind(blocker_id)
+    .bind(blocked_id)
+    .execute(&mut *tx)
+    .await?;
+
+    // Reactions on each other's activities go; comments are soft-deleted so
+    // other replies in the thread survive
+    sqlx::query(
+        r#"
+        DELETE FROM activity_reactions r
+        USING activities a
+        WHERE a.id = r.activity_id
+          AND ((a.user_id = $1 AND r.user_id = $2)
+            OR (a.user_id = $2 AND r.user_id = $1))
+        "#,
+    )
+    .bind(blocker_id)
+    .bind(blocked_id)
+    .execute(&mut *tx)
+    .await?;
+
+    sqlx::query(
+        r#"
+        UPDATE activity_comments c
+        SET deleted_at = now(), deleted_by = $1
+        FROM activities a
+        WHERE a.id = c.activity_id
+          AND c.deleted_at IS NULL
+          AND ((a.user_id = $1 AND c.user_id = $2)
+            OR (a.user_id = $2 AND c.user_id = $1))
+        "#,
+    )
+    .bind(blocker_id)
+    .bind(blocked_id)
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+
+    Ok(())
+}
+
+/// Remove a block. Returns false if there was none.
+pub async fn unblock_user(
+    pool: &PgPool,
+    blocker_id: Uuid,
+    blocked_id: Uuid,
+) -> Result<bool, AppError> {
+    let result = sqlx::query("DELETE FROM user_blocks WHERE blocker_id = $1 AND blocked_id = $2")
+        .bind(blocker_id)
+        .bind(blocked_id)
+        .execute(pool)
+        .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// Users blocked by `user_id`, most recent first.
+pub async fn list_blocks(pool: &PgPool, user_id: Uuid) -> Result<Vec<RelatedUser>, AppError> {
+    let users = sqlx::query_as::<_, RelatedUser>(
+        r#"
+        SELECT u.id as user_id, u.callsign, p.display_name, b.created_at
+        FROM user_blocks b
+        JOIN users u ON u.id = b.blocked_id
+        LEFT JOIN user_profiles p ON p.user_id = u.id
+        WHERE b.blocker_id = $1
+        ORDER BY b.created_at DESC
+        "#,
+    )
+    .bind(user_id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(users)
+}
+
+/// Whether either user has blocked the other.
+pub async fn is_blocked_between(
+    pool: &PgPool,
+    user_id_1: Uuid,
+    user_id_2: Uuid,
+) -> Result<bool, AppError> {
+    let blocked: bool = sqlx::query_scalar(
+        r#"
+        SELECT EXISTS(
+            SELECT 1 FROM user_blocks
+            WHERE (blocker_id = $1 AND blocked_id = $2)
+               OR (blocker_id = $2 AND blocked_id = $1)
+        )
+        "#,
+    )
+    .bind(user_id_1)
+    .bind(user_id_2)
+    .fetch_one(pool)
+    .await?;
+
+    Ok(blocked)
+}
+
+/// Mute a user's activities. Muting an already-muted user is a no-op.
+pub async fn mute_user(pool: &PgPool, user_id: Uuid, muted_user_id: Uuid) -> Result<(), AppError> {
+    sqlx::query(
+        r#"
+        INSERT INTO user_mutes (user_id, muted_user_id)
+        VALUES ($1, $2)
+        ON CONFLICT DO NOTHING
+        "#,
+    )
+    .bind(user_id)
+    .bind(muted_user_id)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// Remove a mute. Returns false if there was none.
+pub async fn unmute_user(
+    pool: &PgPool,
+    user_id: Uuid,
+    muted_user_id: Uuid,
+) -> Result<bool, AppError> {
+    let result = sqlx::query("DELETE FROM user_mutes WHERE user_id = $1 AND muted_user_id = $2")
+        .bind(user_id)
+        .bind(muted_user_id)
+        .execute(pool)
+        .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// Users muted by `user_id`, most recent first.
+pub async fn list_mutes(pool: &PgPool, user_id: Uuid) -> Result<Vec<RelatedUser>, AppError> {
+    let users = sqlx::query_as::<_, RelatedUser>(
+        r#"
+        SELECT u.id as user_id, u.callsign, p.display_name, m.created_at
+        FROM user_mutes m
+        JOIN users u ON u.id = m.muted_user_id
+        LEFT JOIN user_profiles p ON p.user_id = u.id
+        WHERE m.user_id = $1
+        ORDER BY m.created_at DESC
+        "#,
+    )
+    .bind(user_id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(users)
+}
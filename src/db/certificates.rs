@@ -0,0 +1,153 @@
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::certificate::{generate_verification_code, EarnedCertificate};
+use crate::error::AppError;
+use crate::models::{Certificate, CertificateTemplate, Challenge};
+
+const CERTIFICATE_COLUMNS: &str =
+    "challenge_id, callsign, kind, challenge_name, tier_id, tier_name, verification_code, achieved_at";
+
+/// Issue the earned certificates the participant doesn't hold yet. A
+/// certificate is issued once per kind and kept even if progress later
+/// drops. Returns the newly issued certificates.
+pub async fn issue_certificates<'e, E>(
+    executor: E,
+    challenge: &Challenge,
+    callsign: &str,
+    earned: &[EarnedCertificate],
+) -> Result<Vec<Certificate>, AppError>
+where
+    E: sqlx::PgExecutor<'e>,
+{
+    if earned.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let callsign_upper = callsign.to_uppercase();
+    let ids: Vec<Uuid> = earned.iter().map(|_| Uuid::new_v4()).collect();
+    let kinds: Vec<&str> = earned.iter().map(|e| e.kind.as_str()).collect();
+    let tier_ids: Vec<Option<String>> = earned.iter().map(|e| e.tier_id.clone()).collect();
+    let tier_names: Vec<Option<String>> = earned.iter().map(|e| e.tier_name.clone()).collect();
+    let codes: Vec<String> = earned
+        .iter()
+        .map(|_| generate_verification_code())
+        .collect();
+
+    let issued = sqlx::query_as::<_, Certificate>(&format!(
+        r#"
+        INSERT INTO certificates
+            (id, challenge_id, callsign, kind, challenge_name, tier_id, tier_name,
+             verification_code)
+        SELECT e.id, $2, $3, e.kind, $4, e.tier_id, e.tier_name, e.code
+        FROM UNNEST($1::uuid[], $5::text[], $6::text[], $7::text[], $8::text[])
+            AS e(id, kind, tier_id, tier_name, code)
+        ON CONFLICT (challenge_id, callsign, kind) DO NOTHING
+        RETURNING {CERTIFICATE_COLUMNS}
+        "#
+    ))
+    .bind(&ids)
+    .bind(challenge.id)
+    .bind(&callsign_upper)
+    .bind(&challenge.name)
+    .bind(&kinds)
+    .bind(&tier_ids)
+    .bind(&tier_names)
+    .bind(&codes)
+    .fetch_all(executor)
+    .await?;
+
+    Ok(issued)
+}
+
+/// The participant's certificates for a challenge.
+pub async fn list_certificates(
+    pool: &PgPool,
+    challenge_id: Uuid,
+    callsign: &str,
+) -> Result<Vec<Certificate>, AppError> {
+    let certificates = sqlx::query_as::<_, Certificate>(&format!(
+        r#"
+        SELECT {CERTIFICATE_COLUMNS}
+        FROM certificates
+        WHERE challenge_id = $1 AND callsign = $2
+        ORDER BY achieved_at ASC
+        "#
+    ))
+    .bind(challenge_id)
+    .bind(callsign.to_uppercase())
+    .fetch_all(pool)
+    .await?;
+
+    Ok(certificates)
+}
+
+/// Look up a certificate by verification code, ignoring case.
+pub async fn get_certificate_by_code(
+    pool: &PgPool,
+    code: &str,
+) -> Result<Option<Certificate>, AppError> {
+    let certificate = sqlx::query_as::<_, Certificate>(&format!(
+        r#"
+        SELECT {CERTIFICATE_COLUMNS}
+        FROM certificates
+        WHERE verification_code = $1
+        "#
+    ))
+    .bind(code.trim().to_uppercase())
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(certificate)
+}
+
+pub async fn get_certificate_template(
+    pool: &PgPool,
+    challenge_id: Uuid,
+) -> Result<Option<CertificateTemplate>, AppError> {
+    let template = sqlx::query_as::<_, CertificateTemplate>(
+        r#"
+        SELECT challenge_id, svg, updated_at
+        FROM certificate_templates
+        WHERE challenge_id = $1
+        "#,
+    )
+    .bind(challenge_id)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(template)
+}
+
+pub async fn upsert_certificate_template(
+    pool: &PgPool,
+    challenge_id: Uuid,
+    svg: &str,
+) -> Result<CertificateTemplate, AppError> {
+    let template = sqlx::query_as::<_, CertificateTemplate>(
+        r#"
+        INSERT INTO certificate_templates (challenge_id, svg)
+        VALUES ($1, $2)
+        ON CONFLICT (challenge_id) DO UPDATE SET svg = EXCLUDED.svg, updated_at = now()
+        RETURNING challenge_id, svg, updated_at
+        "#,
+    )
+    .bind(challenge_id)
+    .bind(svg)
+    .fetch_one(pool)
+    .await?;
+
+    Ok(template)
+}
+
+pub async fn delete_certificate_template(
+    pool: &PgPool,
+    challenge_id: Uuid,
+) -> Result<bool, AppError> {
+    let result = sqlx::query("DELETE FROM certificate_templates WHERE challenge_id = $1")
+        .bind(challenge_id)
+        .execute(pool)
+        .await?;
+
+    Ok(result.rows_affected() > 0)
+}
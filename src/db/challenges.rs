@@ -1,19 +1,33 @@
-use sqlx::PgPool;
+use std::collections::HashMap;
+
+use serde_json::Value;
+use sqlx::{PgConnection, PgPool};
 use uuid::Uuid;
 
 use crate::error::AppError;
 use crate::models::{
-    Challenge, ChallengeListItem, CreateChallengeRequest, ListChallengesQuery,
+    remap_badge_ids, BadgeMetadata, BundleChallenge, Challenge, ChallengeListItem,
+    ChallengeStatus, ChallengeVersion, ChallengeVersionListItem, CreateChallengeRequest,
+    DecodedBadge, ListChallengesQuery,
 };
 
+/// SQL expression for a challenge's lifecycle status, treating scheduled
+/// challenges whose `publish_at` has passed as published.
+pub(super) const EFFECTIVE_STATUS: &str =
+    "CASE WHEN c.status = 'scheduled' AND c.publish_at <= now() THEN 'published' ELSE c.status END";
+
+/// List challenges whose effective status is one of `statuses`. When
+/// `deleted` is true, only soft-deleted challenges are listed instead.
 pub async fn list_challenges(
     pool: &PgPool,
     query: &ListChallengesQuery,
+    statuses: &[&str],
+    deleted: bool,
 ) -> Result<(Vec<ChallengeListItem>, i64), AppError> {
     let limit = query.limit.unwrap_or(50).min(100);
     let offset = query.offset.unwrap_or(0);
 
-    let challenges = sqlx::query_as::<_, ChallengeListItem>(
+    let challenges = sqlx::query_as::<_, ChallengeListItem>(&format!(
         r#"
         SELECT
             c.id,
@@ -21,51 +35,75 @@ pub async fn list_challenges(
             c.description,
             c.category,
             c.challenge_type,
-            c.is_active,
+            ({status}) = 'published' as is_active,
+            ({status}) as status,
+            c.publish_at,
             COALESCE(COUNT(cp.id), 0) as participant_count
         FROM challenges c
         LEFT JOIN challenge_participants cp ON cp.challenge_id = c.id AND cp.status = 'active'
         WHERE ($1::text IS NULL OR c.category = $1)
           AND ($2::text IS NULL OR c.challenge_type = $2)
-          AND ($3::bool IS NULL OR c.is_active = $3)
+          AND ($3::bool IS NULL OR (({status}) = 'published') = $3)
+          AND ({status}) = ANY($6)
+          AND (c.deleted_at IS NOT NULL) = $7
         GROUP BY c.id
         ORDER BY c.created_at DESC
         LIMIT $4 OFFSET $5
         "#,
-    )
+        status = EFFECTIVE_STATUS
+    ))
     .bind(&query.category)
     .bind(&query.challenge_type)
     .bind(query.active)
     .bind(limit)
     .bind(offset)
+    .bind(statuses)
+    .bind(deleted)
     .fetch_all(pool)
     .await?;
 
-    let total: (i64,) = sqlx::query_as(
+    let total: (i64,) = sqlx::query_as(&format!(
         r#"
         SELECT COUNT(*)
         FROM challenges c
         WHERE ($1::text IS NULL OR c.category = $1)
           AND ($2::text IS NULL OR c.challenge_type = $2)
-          AND ($3::bool IS NULL OR c.is_active = $3)
+          AND ($3::bool IS NULL OR (({status}) = 'published') = $3)
+          AND ({status}) = ANY($4)
+          AND (c.deleted_at IS NOT NULL) = $5
         "#,
-    )
+        status = EFFECTIVE_STATUS
+    ))
     .bind(&query.category)
     .bind(&query.challenge_type)
     .bind(query.active)
+    .bind(statuses)
+    .bind(deleted)
     .fetch_one(pool)
     .await?;
 
     Ok((challenges, total.0))
 }
 
+/// Get a challenge by ID. Soft-deleted challenges are not returned.
 pub async fn get_challenge(pool: &PgPool, id: Uuid) -> Result<Option<Challenge>, AppError> {
+    let challenge = get_challenge_including_deleted(pool, id)
+        .await?
+        .filter(|c| c.deleted_at.is_none());
+
+    Ok(challenge)
+}
+
+pub async fn get_challenge_including_deleted(
+    pool: &PgPool,
+    id: Uuid,
+) -> Result<Option<Challenge>, AppError> {
     let challenge = sqlx::query_as::<_, Challenge>(
         r#"
         SELECT
             id, version, name, description, author, category, challenge_type,
-            configuration, invite_config, hamalert_config, is_active,
-            created_at, updated_at
+            configuration, invite_config, hamalert_config,
+            created_at, updated_at, status, publish_at, deleted_at
         FROM challenges
         WHERE id = $1
         "#,
@@ -80,16 +118,19 @@ pub async fn get_challenge(pool: &PgPool, id: Uuid) -> Result<Option<Challenge>,
 pub async fn create_challenge(
     pool: &PgPool,
     req: &CreateChallengeRequest,
+    status: ChallengeStatus,
 ) -> Result<Challenge, AppError> {
     let id = Uuid::new_v4();
 
+    let mut tx = pool.begin().await?;
+
     let challenge = sqlx::query_as::<_, Challenge>(
         r#"
-        INSERT INTO challenges (id, name, description, author, category, challenge_type, configuration, invite_config, hamalert_config)
-        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+        INSERT INTO challenges (id, name, description, author, category, challenge_type, configuration, invite_config, hamalert_config, status, publish_at)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
         RETURNING id, version, name, description, author, category, challenge_type,
-                  configuration, invite_config, hamalert_config, is_active,
-                  created_at, updated_at
+                  configuration, invite_config, hamalert_config,
+                  created_at, updated_at, status, publish_at, deleted_at
         "#,
     )
     .bind(id)
@@ -101,9 +142,14 @@ pub async fn create_challenge(
     .bind(&req.configuration)
     .bind(&req.invite_config)
     .bind(&req.hamalert_config)
-    .fetch_one(pool)
+    .bind(status.as_str())
+    .bind(req.publish_at)
+    .fetch_one(&mut *tx)
     .await?;
 
+    record_challenge_version(&mut tx, &challenge).await?;
+
+    tx.commit().await?;
     Ok(challenge)
 }
 
@@ -112,16 +158,18 @@ pub async fn update_challenge(
     id: Uuid,
     req: &CreateChallengeRequest,
 ) -> Result<Option<Challenge>, AppError> {
+    let mut tx = pool.begin().await?;
+
     let challenge = sqlx::query_as::<_, Challenge>(
         r#"
         UPDATE challenges
         SET name = $2, description = $3, author = $4, category = $5,
             challenge_type = $6, configuration = $7, invite_config = $8,
             hamalert_config = $9, version = version + 1, updated_at = now()
-        WHERE id = $1
+        WHERE id = $1 AND deleted_at IS NULL
         RETURNING id, version, name, description, author, category, challenge_type,
-                  configuration, invite_config, hamalert_config, is_active,
-                  created_at, updated_at
+                  configuration, invite_config, hamalert_config,
+                  created_at, updated_at, status, publish_at, deleted_at
         "#,
     )
     .bind(id)
@@ -133,17 +181,333 @@ pub async fn update_challenge(
     .bind(&req.configuration)
     .bind(&req.invite_config)
     .bind(&req.hamalert_config)
+    .fetch_optional(&mut *tx)
+    .await?;
+
+    if let Some(ref challenge) = challenge {
+        record_challenge_version(&mut tx, challenge).await?;
+    }
+
+    tx.commit().await?;
+    Ok(challenge)
+}
+
+/// Copy a challenge under a new name: configuration, invite and HamAlert
+/// settings, and every badge including its image. Tier `badgeId`s are
+/// pointed at the copied badges. Used by [`clone_challenge`] and by
+/// recurring templates.
+pub(crate) async fn insert_challenge_copy(
+    conn: &mut PgConnection,
+    source: &Challenge,
+    name: &str,
+    configuration: &Value,
+    status: ChallengeStatus,
+) -> Result<Challenge, AppError> {
+    let id = Uuid::new_v4();
+
+    let source_badge_ids: Vec<Uuid> =
+        sqlx::query_scalar("SELECT id FROM badges WHERE challenge_id = $1")
+            .bind(source.id)
+            .fetch_all(&mut *conn)
+            .await?;
+    let new_badge_ids: Vec<Uuid> = source_badge_ids.iter().map(|_| Uuid::new_v4()).collect();
+    let badge_ids: HashMap<Uuid, Uuid> = source_badge_ids
+        .iter()
+        .copied()
+        .zip(new_badge_ids.iter().copied())
+        .collect();
+
+    let mut configuration = configuration.clone();
+    remap_badge_ids(&mut configuration, &badge_ids);
+
+    let challenge = sqlx::query_as::<_, Challenge>(
+        r#"
+        INSERT INTO challenges (id, name, description, author, category, challenge_type, configuration, invite_config, hamalert_config, status)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+        RETURNING id, version, name, description, author, category, challenge_type,
+                  configuration, invite_config, hamalert_config,
+                  created_at, updated_at, status, publish_at, deleted_at
+        "#,
+    )
+    .bind(id)
+    .bind(name)
+    .bind(&source.description)
+    .bind(&source.author)
+    .bind(&source.category)
+    .bind(&source.challenge_type)
+    .bind(&configuration)
+    .bind(&source.invite_config)
+    .bind(&source.hamalert_config)
+    .bind(status.as_str())
+    .fetch_one(&mut *conn)
+    .await?;
+
+    sqlx::query(
+        r#"
+        INSERT INTO badges (id, challenge_id, name, tier_id, image_data, content_type)
+        SELECT m.new_id, $1, b.name, b.tier_id, b.image_data, b.content_type
+        FROM badges b
+        JOIN unnest($2::uuid[], $3::uuid[]) AS m(old_id, new_id) ON m.old_id = b.id
+        "#,
+    )
+    .bind(challenge.id)
+    .bind(&source_badge_ids)
+    .bind(&new_badge_ids)
+    .execute(&mut *conn)
+    .await?;
+
+    record_challenge_version(conn, &challenge).await?;
+
+    Ok(challenge)
+}
+
+/// Clone a challenge as a new draft.
+pub async fn clone_challenge(
+    pool: &PgPool,
+    source: &Challenge,
+    name: &str,
+) -> Result<Challenge, AppError> {
+    let mut tx = pool.begin().await?;
+    let challenge = insert_challenge_copy(
+        &mut tx,
+        source,
+        name,
+        &source.configuration,
+        ChallengeStatus::Draft,
+    )
+    .await?;
+    tx.commit().await?;
+
+    Ok(challenge)
+}
+
+/// Create a draft challenge from an imported bundle with fresh challenge and
+/// badge IDs. Returns the challenge, its badges and the source-to-new badge
+/// ID mapping.
+pub async fn import_challenge(
+    pool: &PgPool,
+    bundle: &BundleChallenge,
+    badges: &[DecodedBadge],
+) -> Result<(Challenge, Vec<BadgeMetadata>, HashMap<Uuid, Uuid>), AppError> {
+    let id = Uuid::new_v4();
+    let badge_ids: HashMap<Uuid, Uuid> = badges
+        .iter()
+        .map(|b| (b.source_id, Uuid::new_v4()))
+        .collect();
+
+    let mut configuration = bundle.configuration.clone();
+    remap_badge_ids(&mut configuration, &badge_ids);
+
+    let mut tx = pool.begin().await?;
+
+    let challenge = sqlx::query_as::<_, Challenge>(
+        r#"
+        INSERT INTO challenges (id, name, description, author, category, challenge_type, configuration, invite_config, hamalert_config, status)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, 'draft')
+        RETURNING id, version, name, description, author, category, challenge_type,
+                  configuration, invite_config, hamalert_config,
+                  created_at, updated_at, status, publish_at, deleted_at
+        "#,
+    )
+    .bind(id)
+    .bind(&bundle.name)
+    .bind(&bundle.description)
+    .bind(&bundle.author)
+    .bind(&bundle.category)
+    .bind(&bundle.challenge_type)
+    .bind(&configuration)
+    .bind(&bundle.invite_config)
+    .bind(&bundle.hamalert_config)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    let mut created = Vec::with_capacity(badges.len());
+    for badge in badges {
+        let row = sqlx::query_as::<_, BadgeMetadata>(
+            r#"
+            INSERT INTO badges (id, challenge_id, name, tier_id, image_data, content_type)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            RETURNING id, challenge_id, name, tier_id, content_type, created_at
+            "#,
+        )
+        .bind(badge_ids[&badge.source_id])
+        .bind(challenge.id)
+        .bind(&badge.name)
+        .bind(&badge.tier_id)
+        .bind(&badge.image_data)
+        .bind(&badge.content_type)
+        .fetch_one(&mut *tx)
+        .await?;
+        created.push(row);
+    }
+
+    record_challenge_version(&mut tx, &challenge).await?;
+
+    tx.commit().await?;
+    Ok((challenge, created, badge_ids))
+}
+
+/// Store the challenge's current configuration in its version history.
+async fn record_challenge_version(
+    conn: &mut PgConnection,
+    challenge: &Challenge,
+) -> Result<(), AppError> {
+    sqlx::query(
+        r#"
+        INSERT INTO challenge_versions (
+            challenge_id, version, name, description, author, category, challenge_type,
+            configuration, invite_config, hamalert_config, created_at
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+        ON CONFLICT (challenge_id, version) DO NOTHING
+        "#,
+    )
+    .bind(challenge.id)
+    .bind(challenge.version)
+    .bind(&challenge.name)
+    .bind(&challenge.description)
+    .bind(&challenge.author)
+    .bind(&challenge.category)
+    .bind(&challenge.challenge_type)
+    .bind(&challenge.configuration)
+    .bind(&challenge.invite_config)
+    .bind(&challenge.hamalert_config)
+    .bind(challenge.updated_at)
+    .execute(conn)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn list_challenge_versions(
+    pool: &PgPool,
+    challenge_id: Uuid,
+) -> Result<Vec<ChallengeVersionListItem>, AppError> {
+    let versions = sqlx::query_as::<_, ChallengeVersionListItem>(
+        r#"
+        SELECT version, name, created_at
+        FROM challenge_versions
+        WHERE challenge_id = $1
+        ORDER BY version DESC
+        "#,
+    )
+    .bind(challenge_id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(versions)
+}
+
+pub async fn get_challenge_version(
+    pool: &PgPool,
+    challenge_id: Uuid,
+    version: i32,
+) -> Result<Option<ChallengeVersion>, AppError> {
+    let version = sqlx::query_as::<_, ChallengeVersion>(
+        r#"
+        SELECT challenge_id, version, name, description, author, category, challenge_type,
+               configuration, invite_config, hamalert_config, created_at
+        FROM challenge_versions
+        WHERE challenge_id = $1 AND version = $2
+        "#,
+    )
+    .bind(challenge_id)
+    .bind(version)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(version)
+}
+
+/// Move a challenge to a new lifecycle status.
+pub async fn set_challenge_status(
+    pool: &PgPool,
+    id: Uuid,
+    status: ChallengeStatus,
+    publish_at: Option<chrono::DateTime<chrono::Utc>>,
+) -> Result<Option<Challenge>, AppError> {
+    let challenge = sqlx::query_as::<_, Challenge>(
+        r#"
+        UPDATE challenges
+        SET status = $2, publish_at = $3, updated_at = now()
+        WHERE id = $1 AND deleted_at IS NULL
+        RETURNING id, version, name, description, author, category, challenge_type,
+                  configuration, invite_config, hamalert_config,
+                  created_at, updated_at, status, publish_at, deleted_at
+        "#,
+    )
+    .bind(id)
+    .bind(status.as_str())
+    .bind(publish_at)
     .fetch_optional(pool)
     .await?;
 
     Ok(challenge)
 }
 
+/// Soft-delete a challenge. Participants, progress and badges are kept
+/// until the challenge is purged.
 pub async fn delete_challenge(pool: &PgPool, id: Uuid) -> Result<bool, AppError> {
-    let result = sqlx::query("DELETE FROM challenges WHERE id = $1")
+    let result = sqlx::query(
+        "UPDATE challenges SET deleted_at = now() WHERE id = $1 AND deleted_at IS NULL",
+    )
+    .bind(id)
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// Undo a soft delete that is still within the retention window.
+pub async fn restore_challenge(
+    pool: &PgPool,
+    id: Uuid,
+    retention_days: i64,
+) -> Result<Option<Challenge>, AppError> {
+    let challenge = sqlx::query_as::<_, Challenge>(
+        r#"
+        UPDATE challenges
+        SET deleted_at = NULL, updated_at = now()
+        WHERE id = $1
+          AND deleted_at IS NOT NULL
+          AND deleted_at > now() - make_interval(days => $2::int)
+        RETURNING id, version, name, description, author, category, challenge_type,
+                  configuration, invite_config, hamalert_config,
+                  created_at, updated_at, status, publish_at, deleted_at
+        "#,
+    )
+    .bind(id)
+    .bind(retention_days)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(challenge)
+}
+
+/// Permanently delete a soft-deleted challenge. Cascades to participants,
+/// progress, badges, earned badges, snapshots and invites.
+pub async fn purge_challenge(pool: &PgPool, id: Uuid) -> Result<bool, AppError> {
+    let result = sqlx::query("DELETE FROM challenges WHERE id = $1 AND deleted_at IS NOT NULL")
         .bind(id)
         .execute(pool)
         .await?;
 
     Ok(result.rows_affected() > 0)
 }
+
+/// Permanently delete challenges soft-deleted longer ago than the
+/// retention window.
+pub async fn purge_expired_challenges(pool: &PgPool, retention_days: i64) -> Result<u64, AppError> {
+    let result = sqlx::query(
+        r#"
+        DELETE FROM challenges
+        WHERE deleted_at IS NOT NULL
+          AND deleted_at <= now() - make_interval(days => $1::int)
+        "#,
+    )
+    .bind(retention_days)
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected())
+}
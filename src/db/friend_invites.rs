@@ -1,10 +1,12 @@
-use chrono::{Duration, Utc};
+use chrono::{DateTime, Utc};
 use rand::Rng;
 use sqlx::PgPool;
 use uuid::Uuid;
 
 use crate::error::AppError;
-use crate::models::FriendInvite;
+use crate::models::{FriendInvite, FriendInviteRedemption, FriendRequestWithCallsigns};
+
+use super::friend_requests::insert_friend_request;
 
 fn generate_friend_invite_token() -> String {
     const CHARSET: &[u8] = b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789";
@@ -23,21 +25,22 @@ fn generate_friend_invite_token() -> String {
 pub async fn create_friend_invite(
     pool: &PgPool,
     user_id: Uuid,
-    expiry_days: i64,
+    expires_at: DateTime<Utc>,
+    max_uses: i32,
 ) -> Result<FriendInvite, AppError> {
     let token = generate_friend_invite_token();
-    let expires_at = Utc::now() + Duration::days(expiry_days);
 
     let invite = sqlx::query_as::<_, FriendInvite>(
         r#"
-        INSERT INTO friend_invites (token, user_id, expires_at)
-        VALUES ($1, $2, $3)
-        RETURNING id, token, user_id, created_at, expires_at, used_at, used_by_user_id
+        INSERT INTO friend_invites (token, user_id, expires_at, max_uses)
+        VALUES ($1, $2, $3, $4)
+        RETURNING id, token, user_id, created_at, expires_at, used_at, max_uses, use_count, revoked_at
         "#,
     )
     .bind(&token)
     .bind(user_id)
     .bind(expires_at)
+    .bind(max_uses)
     .fetch_one(pool)
     .await?;
 
@@ -50,7 +53,7 @@ pub async fn get_friend_invite(
 ) -> Result<Option<FriendInvite>, AppError> {
     let invite = sqlx::query_as::<_, FriendInvite>(
         r#"
-        SELECT id, token, user_id, created_at, expires_at, used_at, used_by_user_id
+        SELECT id, token, user_id, created_at, expires_at, used_at, max_uses, use_count, revoked_at
         FROM friend_invites
         WHERE token = $1
         "#,
@@ -62,53 +65,131 @@ pub async fn get_friend_invite(
     Ok(invite)
 }
 
-pub async fn get_valid_friend_invite(
+/// A user's invite links, newest first.
+pub async fn list_friend_invites(
     pool: &PgPool,
-    token: &str,
-) -> Result<Option<FriendInvite>, AppError> {
-    let invite = sqlx::query_as::<_, FriendInvite>(
+    user_id: Uuid,
+) -> Result<Vec<FriendInvite>, AppError> {
+    let invites = sqlx::query_as::<_, FriendInvite>(
         r#"
-        SELECT id, token, user_id, created_at, expires_at, used_at, used_by_user_id
+        SELECT id, token, user_id, created_at, expires_at, used_at, max_uses, use_count, revoked_at
         FROM friend_invites
-        WHERE token = $1
-          AND expires_at > now()
-          AND used_at IS NULL
+        WHERE user_id = $1
+        ORDER BY created_at DESC
         "#,
     )
-    .bind(token)
-    .fetch_optional(pool)
+    .bind(user_id)
+    .fetch_all(pool)
     .await?;
 
-    Ok(invite)
+    Ok(invites)
 }
 
-pub async fn mark_invite_used(
+/// Redemptions of all of a user's invite links, oldest first.
+pub async fn list_friend_invite_redemptions(
+    pool: &PgPool,
+    user_id: Uuid,
+) -> Result<Vec<FriendInviteRedemption>, AppError> {
+    let redemptions = sqlx::query_as::<_, FriendInviteRedemption>(
+        r#"
+        SELECT r.invite_id, r.user_id, u.callsign, r.friend_request_id, r.redeemed_at
+        FROM friend_invite_redemptions r
+        JOIN friend_invites i ON i.id = r.invite_id
+        JOIN users u ON u.id = r.user_id
+        WHERE i.user_id = $1
+        ORDER BY r.redeemed_at
+        "#,
+    )
+    .bind(user_id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(redemptions)
+}
+
+/// Revoke one of a user's invite links. Revoking twice keeps the original
+/// time. Returns None if the token doesn't exist or belongs to someone else.
+pub async fn revoke_friend_invite(
     pool: &PgPool,
     token: &str,
-    used_by_user_id: Uuid,
+    user_id: Uuid,
 ) -> Result<Option<FriendInvite>, AppError> {
     let invite = sqlx::query_as::<_, FriendInvite>(
         r#"
         UPDATE friend_invites
-        SET used_at = now(), used_by_user_id = $2
-        WHERE token = $1
-        RETURNING id, token, user_id, created_at, expires_at, used_at, used_by_user_id
+        SET revoked_at = COALESCE(revoked_at, now())
+        WHERE token = $1 AND user_id = $2
+        RETURNING id, token, user_id, created_at, expires_at, used_at, max_uses, use_count, revoked_at
         "#,
     )
     .bind(token)
-    .bind(used_by_user_id)
+    .bind(user_id)
     .fetch_optional(pool)
     .await?;
 
     Ok(invite)
 }
 
+/// Consume one use of an invite and send a friend request from the
+/// redeeming user to the invite's owner, in one transaction. Fails with
+/// `FriendInviteUsed` if the last use was taken concurrently.
+pub async fn redeem_friend_invite(
+    pool: &PgPool,
+    invite: &FriendInvite,
+    redeemed_by: Uuid,
+) -> Result<FriendRequestWithCallsigns, AppError> {
+    let mut tx = pool.begin().await?;
+
+    let consumed = sqlx::query(
+        r#"
+        UPDATE friend_invites
+        SET use_count = use_count + 1,
+            used_at = CASE WHEN use_count + 1 >= max_uses THEN now() END
+        WHERE id = $1
+          AND use_count < max_uses
+          AND revoked_at IS NULL
+          AND expires_at > now()
+        "#,
+    )
+    .bind(invite.id)
+    .execute(&mut *tx)
+    .await?;
+
+    if consumed.rows_affected() == 0 {
+        return Err(AppError::FriendInviteUsed {
+            token: invite.token.clone(),
+        });
+    }
+
+    let request = insert_friend_request(&mut tx, redeemed_by, invite.user_id).await?;
+
+    sqlx::query(
+        r#"
+        INSERT INTO friend_invite_redemptions (invite_id, user_id, friend_request_id)
+        VALUES ($1, $2, $3)
+        ON CONFLICT (invite_id, user_id) DO UPDATE SET
+            friend_request_id = EXCLUDED.friend_request_id,
+            redeemed_at = now()
+        "#,
+    )
+    .bind(invite.id)
+    .bind(redeemed_by)
+    .bind(request.id)
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+
+    Ok(request)
+}
+
 pub async fn cleanup_expired_invites(pool: &PgPool) -> Result<u64, AppError> {
     let result = sqlx::query(
         r#"
         DELETE FROM friend_invites
         WHERE expires_at < now() - INTERVAL '30 days'
            OR used_at < now() - INTERVAL '30 days'
+           OR revoked_at < now() - INTERVAL '30 days'
         "#,
     )
     .execute(pool)
@@ -1,13 +1,25 @@
-use sqlx::PgPool;
+use chrono::{DateTime, Utc};
+use sqlx::{PgConnection, PgPool};
 use uuid::Uuid;
 
 use crate::error::AppError;
-use crate::models::{FriendRequest, FriendRequestWithCallsigns, Friendship};
+use crate::models::{
+    friend_request_expiry_cutoff, FriendRequest, FriendRequestWithCallsigns, Friendship,
+};
 
 pub async fn create_friend_request(
     pool: &PgPool,
     from_user_id: Uuid,
     to_user_id: Uuid,
+) -> Result<FriendRequestWithCallsigns, AppError> {
+    let mut conn = pool.acquire().await?;
+    insert_friend_request(&mut conn, from_user_id, to_user_id).await
+}
+
+pub(crate) async fn insert_friend_request(
+    conn: &mut PgConnection,
+    from_user_id: Uuid,
+    to_user_id: Uuid,
 ) -> Result<FriendRequestWithCallsigns, AppError> {
     let request = sqlx::query_as::<_, FriendRequestWithCallsigns>(
         r#"
@@ -26,7 +38,7 @@ pub async fn create_friend_request(
     )
     .bind(from_user_id)
     .bind(to_user_id)
-    .fetch_one(pool)
+    .fetch_one(&mut *conn)
     .await?;
 
     Ok(request)
@@ -154,24 +166,90 @@ pub async fn decline_friend_request(
     Ok(request)
 }
 
+/// Withdraw a pending request. Returns None if it is no longer pending.
+pub async fn cancel_friend_request(
+    pool: &PgPool,
+    request_id: Uuid,
+) -> Result<Option<FriendRequest>, AppError> {
+    let request = sqlx::query_as::<_, FriendRequest>(
+        r#"
+        UPDATE friend_requests
+        SET status = 'cancelled', responded_at = now()
+        WHERE id = $1 AND status = 'pending'
+        RETURNING id, from_user_id, to_user_id, status, requested_at, responded_at
+        "#,
+    )
+    .bind(request_id)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(request)
+}
+
+/// When `to_user_id` last declined a request from `from_user_id`, if ever.
+pub async fn get_last_declined_at(
+    pool: &PgPool,
+    from_user_id: Uuid,
+    to_user_id: Uuid,
+) -> Result<Option<DateTime<Utc>>, AppError> {
+    let declined_at: Option<DateTime<Utc>> = sqlx::query_scalar(
+        r#"
+        SELECT MAX(responded_at)
+        FROM friend_requests
+        WHERE from_user_id = $1 AND to_user_id = $2 AND status = 'declined'
+        "#,
+    )
+    .bind(from_user_id)
+    .bind(to_user_id)
+    .fetch_one(pool)
+    .await?;
+
+    Ok(declined_at)
+}
+
+/// Mark pending requests older than `expiry_days` as expired. Returns the
+/// number of requests expired; a non-positive `expiry_days` disables expiry.
+pub async fn expire_friend_requests(pool: &PgPool, expiry_days: i64) -> Result<u64, AppError> {
+    let Some(cutoff) = friend_request_expiry_cutoff(Utc::now(), expiry_days) else {
+        return Ok(0);
+    };
+
+    let result = sqlx::query(
+        r#"
+        UPDATE friend_requests
+        SET status = 'expired', responded_at = now()
+        WHERE status = 'pending' AND requested_at < $1
+        "#,
+    )
+    .bind(cutoff)
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected())
+}
+
 /// Find registered users from a list of callsigns, excluding:
 /// - The requesting user
 /// - Users already friends with the requester
 /// - Users with pending friend requests (either direction)
+/// - Users blocked by or blocking the requester
 pub async fn find_suggested_friends(
     pool: &PgPool,
     user_id: Uuid,
     callsigns: &[String],
-) -> Result<Vec<crate::models::User>, AppError> {
+) -> Result<Vec<crate::models::UserSummary>, AppError> {
     if callsigns.is_empty() {
         return Ok(vec![]);
     }
 
-    let users = sqlx::query_as::<_, crate::models::User>(
+    let users = sqlx::query_as::<_, crate::models::UserSummary>(
         r#"
-        SELECT u.id, u.callsign, u.created_at
+        SELECT u.id, u.callsign, p.display_name
         FROM users u
-        WHERE UPPER(u.callsign) = ANY(
+        LEFT JOIN user_profiles p ON p.user_id = u.id
+        LEFT JOIN user_privacy_settings s ON s.user_id = u.id
+        WHERE NOT COALESCE(s.hide_from_search, false)
+        AND UPPER(u.callsign) = ANY(
             SELECT UPPER(unnest($2::text[]))
         )
         AND u.id != $1
@@ -185,6 +263,11 @@ pub async fn find_suggested_friends(
             SELECT from_user_id FROM friend_requests
             WHERE to_user_id = $1 AND status = 'pending'
         )
+        AND NOT EXISTS (
+            SELECT 1 FROM user_blocks b
+            WHERE (b.blocker_id = $1 AND b.blocked_id = u.id)
+               OR (b.blocker_id = u.id AND b.blocked_id = $1)
+        )
         "#,
     )
     .bind(user_id)
@@ -202,6 +285,7 @@ pub struct FriendWithCallsign {
     pub friendship_id: Uuid,
     pub user_id: Uuid,
     pub callsign: String,
+    pub display_name: Option<String>,
     pub created_at: chrono::DateTime<chrono::Utc>,
 }
 
@@ -212,9 +296,10 @@ pub async fn get_friends_for_user(
 ) -> Result<Vec<FriendWithCallsign>, AppError> {
     let friends = sqlx::query_as::<_, FriendWithCallsign>(
         r#"
-        SELECT f.id as friendship_id, u.id as user_id, u.callsign, f.created_at
+        SELECT f.id as friendship_id, u.id as user_id, u.callsign, p.display_name, f.created_at
         FROM friendships f
         JOIN users u ON u.id = f.friend_id
+        LEFT JOIN user_profiles p ON p.user_id = u.id
         WHERE f.user_id = $1
         ORDER BY u.callsign
         "#,
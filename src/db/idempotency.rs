@@ -0,0 +1,129 @@
+use sqlx::PgPool;
+
+use crate::error::AppError;
+use crate::models::IdempotencyRecord;
+
+/// Claim `key` for a new request, holding it for `lease_seconds`. Returns
+/// `None` if the claim succeeded, or the existing record if the key was
+/// already used within `ttl_hours`. An expired record, or an in-flight one
+/// whose lease ran out, is replaced.
+pub async fn claim_idempotency_key(
+    pool: &PgPool,
+    callsign: &str,
+    key: &str,
+    request_hash: &str,
+    ttl_hours: i64,
+    lease_seconds: i64,
+) -> Result<Option<IdempotencyRecord>, AppError> {
+    let mut tx = pool.begin().await?;
+
+    sqlx::query(
+        r#"
+        DELETE FROM idempotency_keys
+        WHERE callsign = $1 AND key = $2
+          AND (created_at < now() - make_interval(hours => $3::int)
+               OR (status_code IS NULL AND locked_until < now()))
+        "#,
+    )
+    .bind(callsign)
+    .bind(key)
+    .bind(ttl_hours)
+    .execute(&mut *tx)
+    .await?;
+
+    let claimed = sqlx::query(
+        r#"
+        INSERT INTO idempotency_keys (callsign, key, request_hash, locked_until)
+        VALUES ($1, $2, $3, now() + make_interval(secs => $4::int))
+        ON CONFLICT DO NOTHING
+        "#,
+    )
+    .bind(callsign)
+    .bind(key)
+    .bind(request_hash)
+    .bind(lease_seconds)
+    .execute(&mut *tx)
+    .await?
+    .rows_affected()
+        > 0;
+
+    let existing = if claimed {
+        None
+    } else {
+        sqlx::query_as::<_, IdempotencyRecord>(
+            r#"
+            SELECT request_hash, status_code, content_type, response_body
+            FROM idempotency_keys
+            WHERE callsign = $1 AND key = $2
+            "#,
+        )
+        .bind(callsign)
+        .bind(key)
+        .fetch_optional(&mut *tx)
+        .await?
+    };
+
+    tx.commit().await?;
+
+    Ok(existing)
+}
+
+/// Store the response for a claimed key so retries can replay it.
+pub async fn complete_idempotency_key(
+    pool: &PgPool,
+    callsign: &str,
+    key: &str,
+    status_code: u16,
+    content_type: Option<&str>,
+    response_body: &[u8],
+) -> Result<(), AppError> {
+    sqlx::query(
+        r#"
+        UPDATE idempotency_keys
+        SET status_code = $3, content_type = $4, response_body = $5, locked_until = NULL
+        WHERE callsign = $1 AND key = $2 AND status_code IS NULL
+        "#,
+    )
+    .bind(callsign)
+    .bind(key)
+    .bind(status_code as i16)
+    .bind(content_type)
+    .bind(response_body)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// Forget a claimed key so the request can be retried, e.g. after a server
+/// error. Keys that already have a stored response are kept.
+pub async fn release_idempotency_key(
+    pool: &PgPool,
+    callsign: &str,
+    key: &str,
+) -> Result<(), AppError> {
+    sqlx::query(
+        "DELETE FROM idempotency_keys WHERE callsign = $1 AND key = $2 AND status_code IS NULL",
+    )
+    .bind(callsign)
+    .bind(key)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// Delete keys older than `ttl_hours`. Returns the number deleted.
+pub async fn purge_expired_idempotency_keys(
+    pool: &PgPool,
+    ttl_hours: i64,
+) -> Result<u64, AppError> {
+    let result = sqlx::query(
+        "DELETE FROM idempotency_keys WHERE created_at < now() - make_interval(hours => $1::int)",
+    )
+    .bind(ttl_hours)
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected())
+}
@@ -1,19 +1,33 @@
 pub mod activities;
 pub mod badges;
+pub mod blocks;
+pub mod certificates;
 pub mod challenges;
 pub mod friend_invites;
 pub mod friend_requests;
+pub mod idempotency;
 pub mod invites;
 pub mod participants;
+pub mod privacy;
+pub mod profiles;
 pub mod progress;
+pub mod reactions;
+pub mod templates;
 pub mod users;
 
 pub use activities::*;
 pub use badges::*;
+pub use blocks::*;
+pub use certificates::*;
 pub use challenges::*;
 pub use friend_invites::*;
 pub use friend_requests::*;
+pub use idempotency::*;
 pub use invites::*;
 pub use participants::*;
+pub use privacy::*;
+pub use profiles::*;
 pub use progress::*;
+pub use reactions::*;
+pub use templates::*;
 pub use users::*;
@@ -1,9 +1,9 @@
-use sqlx::PgPool;
+use sqlx::{PgConnection, PgPool};
 use uuid::Uuid;
 
 use crate::auth::generate_device_token;
 use crate::error::AppError;
-use crate::models::{ChallengeParticipant, ChallengeParticipation, Participant};
+use crate::models::{ChallengeCapacity, ChallengeParticipant, ChallengeParticipation, Participant};
 
 pub async fn get_or_create_participant(
     pool: &PgPool,
@@ -82,7 +82,7 @@ pub async fn get_challenges_for_callsign(
             cp.status
         FROM challenge_participants cp
         JOIN challenges c ON c.id = cp.challenge_id
-        WHERE cp.callsign = $1 AND cp.status = 'active'
+        WHERE cp.callsign = $1 AND cp.status IN ('active', 'waitlisted') AND c.deleted_at IS NULL
         ORDER BY cp.joined_at DESC
         "#,
     )
@@ -93,19 +93,39 @@ pub async fn get_challenges_for_callsign(
     Ok(challenges)
 }
 
+/// Join a challenge, enforcing its participant cap. Joins on a capped
+/// challenge are serialized on the challenge row so concurrent requests
+/// can't overshoot; past the cap the participation is created as
+/// `waitlisted` when the challenge has a waitlist, otherwise
+/// `MaxParticipants` is returned.
 pub async fn join_challenge(
     pool: &PgPool,
     challenge_id: Uuid,
     callsign: &str,
     invite_token: Option<&str>,
+    capacity: ChallengeCapacity,
 ) -> Result<ChallengeParticipant, AppError> {
     let id = Uuid::new_v4();
     let callsign_upper = callsign.to_uppercase();
 
+    let mut tx = pool.begin().await?;
+
+    let mut status = "active";
+    if let Some(max) = capacity.max_participants {
+        lock_challenge(&mut tx, challenge_id).await?;
+
+        if count_active(&mut tx, challenge_id).await? >= max {
+            if !capacity.waitlist {
+                return Err(AppError::MaxParticipants);
+            }
+            status = "waitlisted";
+        }
+    }
+
     let participation = sqlx::query_as::<_, ChallengeParticipant>(
         r#"
-        INSERT INTO challenge_participants (id, challenge_id, callsign, invite_token)
-        VALUES ($1, $2, $3, $4)
+        INSERT INTO challenge_participants (id, challenge_id, callsign, invite_token, status)
+        VALUES ($1, $2, $3, $4, $5)
         RETURNING id, challenge_id, callsign, invite_token, joined_at, status
         "#,
     )
@@ -113,7 +133,8 @@ pub async fn join_challenge(
     .bind(challenge_id)
     .bind(&callsign_upper)
     .bind(invite_token)
-    .fetch_one(pool)
+    .bind(status)
+    .fetch_one(&mut *tx)
     .await
     .map_err(|e| {
         if let sqlx::Error::Database(ref db_err) = e {
@@ -124,9 +145,94 @@ pub async fn join_challenge(
         AppError::Database(e)
     })?;
 
+    tx.commit().await?;
     Ok(participation)
 }
 
+/// Take the per-challenge join lock. `NO KEY UPDATE` doesn't block inserts
+/// that only reference the challenge through a foreign key.
+async fn lock_challenge(conn: &mut PgConnection, challenge_id: Uuid) -> Result<(), AppError> {
+    sqlx::query("SELECT id FROM challenges WHERE id = $1 FOR NO KEY UPDATE")
+        .bind(challenge_id)
+        .execute(conn)
+        .await?;
+
+    Ok(())
+}
+
+/// Number of active participants in a challenge.
+pub async fn count_participants(pool: &PgPool, challenge_id: Uuid) -> Result<i64, AppError> {
+    let mut conn = pool.acquire().await?;
+    count_active(&mut conn, challenge_id).await
+}
+
+async fn count_active(conn: &mut PgConnection, challenge_id: Uuid) -> Result<i64, AppError> {
+    let count: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM challenge_participants WHERE challenge_id = $1 AND status = 'active'",
+    )
+    .bind(challenge_id)
+    .fetch_one(conn)
+    .await?;
+
+    Ok(count)
+}
+
+/// Promote waitlisted participants, oldest first, into free places.
+/// `joined_at` is reset to the promotion time. Returns promoted callsigns.
+async fn promote_waitlisted(
+    conn: &mut PgConnection,
+    challenge_id: Uuid,
+    capacity: ChallengeCapacity,
+) -> Result<Vec<String>, AppError> {
+    if !capacity.waitlist {
+        return Ok(Vec::new());
+    }
+
+    lock_challenge(conn, challenge_id).await?;
+
+    let free = match capacity.max_participants {
+        Some(max) => (max - count_active(conn, challenge_id).await?).max(0),
+        None => i64::MAX,
+    };
+    if free == 0 {
+        return Ok(Vec::new());
+    }
+
+    let promoted: Vec<String> = sqlx::query_scalar(
+        r#"
+        UPDATE challenge_participants
+        SET status = 'active', joined_at = now()
+        WHERE id IN (
+            SELECT id FROM challenge_participants
+            WHERE challenge_id = $1 AND status = 'waitlisted'
+            ORDER BY joined_at ASC, id ASC
+            LIMIT $2
+        )
+        RETURNING callsign
+        "#,
+    )
+    .bind(challenge_id)
+    .bind(free)
+    .fetch_all(conn)
+    .await?;
+
+    Ok(promoted)
+}
+
+/// Fill free places from the waitlist, e.g. after the cap was raised.
+/// Returns promoted callsigns.
+pub async fn fill_from_waitlist(
+    pool: &PgPool,
+    challenge_id: Uuid,
+    capacity: ChallengeCapacity,
+) -> Result<Vec<String>, AppError> {
+    let mut tx = pool.begin().await?;
+    let promoted = promote_waitlisted(&mut tx, challenge_id, capacity).await?;
+    tx.commit().await?;
+
+    Ok(promoted)
+}
+
 pub async fn get_participation(
     pool: &PgPool,
     challenge_id: Uuid,
@@ -149,26 +255,53 @@ pub async fn get_participation(
     Ok(participation)
 }
 
+/// Leave a challenge or its waitlist. When an active participant leaves,
+/// the oldest waitlisted participant is promoted in the same transaction.
+/// Returns promoted callsigns, or `None` if the caller was not participating.
 pub async fn leave_challenge(
     pool: &PgPool,
     challenge_id: Uuid,
     callsign: &str,
-) -> Result<bool, AppError> {
+    capacity: ChallengeCapacity,
+) -> Result<Option<Vec<String>>, AppError> {
     let callsign_upper = callsign.to_uppercase();
 
-    let result = sqlx::query(
+    let mut tx = pool.begin().await?;
+
+    let previous: Option<String> = sqlx::query_scalar(
         r#"
-        UPDATE challenge_participants
-        SET status = 'left'
-        WHERE challenge_id = $1 AND callsign = $2 AND status = 'active'
+        SELECT status FROM challenge_participants
+        WHERE challenge_id = $1 AND callsign = $2 AND status IN ('active', 'waitlisted')
+        FOR UPDATE
         "#,
     )
     .bind(challenge_id)
     .bind(&callsign_upper)
-    .execute(pool)
+    .fetch_optional(&mut *tx)
+    .await?;
+
+    let Some(previous) = previous else {
+        return Ok(None);
+    };
+
+    sqlx::query(
+        "UPDATE challenge_participants SET status = 'left' WHERE challenge_id = $1 AND callsign = $2",
+    )
+    .bind(challenge_id)
+    .bind(&callsign_upper)
+    .execute(&mut *tx)
     .await?;
 
-    Ok(result.rows_affected() > 0)
+    let mut promoted = Vec::new();
+    if previous == "active" {
+        promoted = promote_waitlisted(&mut tx, challenge_id, capacity).await?;
+        if !promoted.is_empty() {
+            tracing::info!(%challenge_id, ?promoted, "Promoted from waitlist");
+        }
+    }
+
+    tx.commit().await?;
+    Ok(Some(promoted))
 }
 
 pub async fn revoke_tokens(pool: &PgPool, callsign: &str) -> Result<u64, AppError> {
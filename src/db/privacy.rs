@@ -0,0 +1,100 @@
+use std::collections::HashMap;
+
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::error::AppError;
+use crate::models::{LeaderboardVisibility, PrivacySettings};
+
+/// Get a user's privacy settings, falling back to defaults.
+pub async fn get_privacy_settings(
+    pool: &PgPool,
+    user_id: Uuid,
+) -> Result<PrivacySettings, AppError> {
+    let settings = sqlx::query_as::<_, PrivacySettings>(
+        r#"
+        SELECT COALESCE(s.hide_from_search, false) as hide_from_search,
+               COALESCE(s.leaderboard_visibility, 'public') as leaderboard_visibility,
+               COALESCE(s.default_activity_audience, 'friends') as default_activity_audience,
+               COALESCE(s.share_milestones, true) as share_milestones
+        FROM (SELECT $1::uuid as user_id) u
+        LEFT JOIN user_privacy_settings s ON s.user_id = u.user_id
+        "#,
+    )
+    .bind(user_id)
+    .fetch_one(pool)
+    .await?;
+
+    Ok(settings)
+}
+
+pub async fn upsert_privacy_settings(
+    pool: &PgPool,
+    user_id: Uuid,
+    settings: &PrivacySettings,
+) -> Result<PrivacySettings, AppError> {
+    let settings = sqlx::query_as::<_, PrivacySettings>(
+        r#"
+        INSERT INTO user_privacy_settings (user_id, hide_from_search, leaderboard_visibility, default_activity_audience, share_milestones)
+        VALUES ($1, $2, $3, $4, $5)
+        ON CONFLICT (user_id) DO UPDATE SET
+            hide_from_search = EXCLUDED.hide_from_search,
+            leaderboard_visibility = EXCLUDED.leaderboard_visibility,
+            default_activity_audience = EXCLUDED.default_activity_audience,
+            share_milestones = EXCLUDED.share_milestones,
+            updated_at = now()
+        RETURNING hide_from_search, leaderboard_visibility, default_activity_audience, share_milestones
+        "#,
+    )
+    .bind(user_id)
+    .bind(settings.hide_from_search)
+    .bind(&settings.leaderboard_visibility)
+    .bind(&settings.default_activity_audience)
+    .bind(settings.share_milestones)
+    .fetch_one(pool)
+    .await?;
+
+    Ok(settings)
+}
+
+/// Leaderboard visibility for each of `callsigns` that must be hidden from
+/// `viewer` (an uppercase callsign, if authenticated). Users are always
+/// shown to themselves and their friends; callsigns not in the map are
+/// shown as-is.
+pub async fn get_hidden_leaderboard_callsigns(
+    pool: &PgPool,
+    callsigns: &[String],
+    viewer: Option<&str>,
+) -> Result<HashMap<String, LeaderboardVisibility>, AppError> {
+    if callsigns.is_empty() {
+        return Ok(HashMap::new());
+    }
+
+    let rows: Vec<(String, String)> = sqlx::query_as(
+        r#"
+        SELECT UPPER(u.callsign), s.leaderboard_visibility
+        FROM users u
+        JOIN user_privacy_settings s ON s.user_id = u.id
+        WHERE UPPER(u.callsign) = ANY($1)
+          AND s.leaderboard_visibility <> 'public'
+          AND UPPER(u.callsign) <> COALESCE($2, '')
+          AND NOT EXISTS (
+              SELECT 1
+              FROM users v
+              JOIN friendships f ON f.user_id = v.id
+              WHERE UPPER(v.callsign) = $2 AND f.friend_id = u.id
+          )
+        "#,
+    )
+    .bind(callsigns)
+    .bind(viewer)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .filter_map(|(callsign, visibility)| {
+            LeaderboardVisibility::parse(&visibility).map(|v| (callsign, v))
+        })
+        .collect())
+}
@@ -0,0 +1,161 @@
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::error::AppError;
+use crate::models::{
+    ProfileBadge, ProfileChallengeRow, UpdateProfileRequest, UserAvatar, UserProfile,
+};
+
+use super::challenges::EFFECTIVE_STATUS;
+
+pub async fn get_user_profile(
+    pool: &PgPool,
+    user_id: Uuid,
+) -> Result<Option<UserProfile>, AppError> {
+    let profile = sqlx::query_as::<_, UserProfile>(
+        r#"
+        SELECT u.id as user_id, u.callsign, p.display_name, p.qth, p.grid, p.bio,
+               p.avatar_data IS NOT NULL as has_avatar, u.created_at as member_since
+        FROM users u
+        LEFT JOIN user_profiles p ON p.user_id = u.id
+        WHERE u.id = $1
+        "#,
+    )
+    .bind(user_id)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(profile)
+}
+
+/// Replace the editable profile fields, keeping any avatar.
+pub async fn upsert_user_profile(
+    pool: &PgPool,
+    user_id: Uuid,
+    req: &UpdateProfileRequest,
+) -> Result<(), AppError> {
+    sqlx::query(
+        r#"
+        INSERT INTO user_profiles (user_id, display_name, qth, grid, bio)
+        VALUES ($1, $2, $3, $4, $5)
+        ON CONFLICT (user_id) DO UPDATE SET
+            display_name = EXCLUDED.display_name,
+            qth = EXCLUDED.qth,
+            grid = EXCLUDED.grid,
+            bio = EXCLUDED.bio,
+            updated_at = now()
+        "#,
+    )
+    .bind(user_id)
+    .bind(&req.display_name)
+    .bind(&req.qth)
+    .bind(&req.grid)
+    .bind(&req.bio)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn set_user_avatar(
+    pool: &PgPool,
+    user_id: Uuid,
+    avatar: Option<(&[u8], &str)>,
+) -> Result<(), AppError> {
+    sqlx::query(
+        r#"
+        INSERT INTO user_profiles (user_id, avatar_data, avatar_content_type)
+        VALUES ($1, $2, $3)
+        ON CONFLICT (user_id) DO UPDATE SET
+            avatar_data = EXCLUDED.avatar_data,
+            avatar_content_type = EXCLUDED.avatar_content_type,
+            updated_at = now()
+        "#,
+    )
+    .bind(user_id)
+    .bind(avatar.map(|(data, _)| data))
+    .bind(avatar.map(|(_, content_type)| content_type))
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn get_user_avatar(pool: &PgPool, user_id: Uuid) -> Result<Option<UserAvatar>, AppError> {
+    let avatar = sqlx::query_as::<_, UserAvatar>(
+        r#"
+        SELECT avatar_data, avatar_content_type
+        FROM user_profiles
+        WHERE user_id = $1 AND avatar_data IS NOT NULL
+        "#,
+    )
+    .bind(user_id)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(avatar)
+}
+
+/// Badges earned by a callsign in publicly visible challenges.
+pub async fn get_earned_badges(
+    pool: &PgPool,
+    callsign: &str,
+) -> Result<Vec<ProfileBadge>, AppError> {
+    let badges = sqlx::query_as::<_, ProfileBadge>(&format!(
+        r#"
+        SELECT b.id as badge_id, b.name, b.tier_id, c.id as challenge_id,
+               c.name as challenge_name, eb.earned_at
+        FROM earned_badges eb
+        JOIN badges b ON b.id = eb.badge_id
+        JOIN challenges c ON c.id = b.challenge_id
+        WHERE eb.callsign = $1
+          AND c.deleted_at IS NULL
+          AND ({status}) IN ('published', 'ended', 'archived')
+        ORDER BY eb.earned_at DESC
+        "#,
+        status = EFFECTIVE_STATUS
+    ))
+    .bind(callsign.to_uppercase())
+    .fetch_all(pool)
+    .await?;
+
+    Ok(badges)
+}
+
+/// Publicly visible challenges a callsign takes part in, with progress.
+pub async fn get_profile_challenges(
+    pool: &PgPool,
+    callsign: &str,
+) -> Result<Vec<ProfileChallengeRow>, AppError> {
+    let rows = sqlx::query_as::<_, ProfileChallengeRow>(&format!(
+        r#"
+        SELECT c.id as challenge_id, c.name as challenge_name, c.configuration,
+               cp.joined_at,
+               ARRAY(
+                   SELECT gc.goal_id FROM goal_completions gc WHERE gc.progress_id = p.id
+                   ORDER BY gc.goal_id
+               ) as completed_goals,
+               ARRAY(
+                   SELECT gc.status FROM goal_completions gc WHERE gc.progress_id = p.id
+                   ORDER BY gc.goal_id
+               ) as goal_statuses,
+               COALESCE(p.current_value, 0) as current_value,
+               COALESCE(p.score, 0) as score,
+               p.current_tier
+        FROM challenge_participants cp
+        JOIN challenges c ON c.id = cp.challenge_id
+        LEFT JOIN progress p ON p.challenge_id = cp.challenge_id AND p.callsign = cp.callsign
+        WHERE cp.callsign = $1
+          AND cp.status IN ('active', 'completed')
+          AND c.deleted_at IS NULL
+          AND ({status}) IN ('published', 'ended', 'archived')
+        ORDER BY cp.joined_at DESC
+        "#,
+        status = EFFECTIVE_STATUS
+    ))
+    .bind(callsign.to_uppercase())
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows)
+}
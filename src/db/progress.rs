@@ -1,8 +1,43 @@
-use sqlx::PgPool;
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use sqlx::{PgConnection, PgPool};
 use uuid::Uuid;
 
+use crate::certificate::earned_certificates;
 use crate::error::AppError;
-use crate::models::{LeaderboardEntry, LeaderboardQuery, Progress, ReportProgressRequest};
+use crate::models::{
+    Challenge, GoalCompletion, GoalCompletionRow, LeaderboardEntry, LeaderboardQuery, Progress,
+    ProgressConflict, ReportProgressRequest, RescoreSummary, ScoreBasis,
+};
+use crate::scoring::{calculate_scores, determine_tier, reached_tiers};
+
+use super::badges::{award_tier_badges, revoke_tier_badges};
+use super::certificates::issue_certificates;
+
+/// `Progress` columns, with completed goal IDs and statuses aggregated
+/// from `goal_completions`. Usable in SELECT and RETURNING on `progress`.
+const PROGRESS_COLUMNS: &str = r#"
+    progress.id, progress.challenge_id, progress.callsign,
+    ARRAY(
+        SELECT gc.goal_id FROM goal_completions gc
+        WHERE gc.progress_id = progress.id
+        ORDER BY gc.completed_at, gc.goal_id
+    ) AS completed_goals,
+    ARRAY(
+        SELECT gc.status FROM goal_completions gc
+        WHERE gc.progress_id = progress.id
+        ORDER BY gc.completed_at, gc.goal_id
+    ) AS goal_statuses,
+    progress.current_value, progress.score, progress.worked_score, progress.confirmed_score,
+    progress.current_tier, progress.last_qso_date, progress.updated_at,
+    progress.challenge_version
+"#;
+
+/// Leaderboard score column: the basis asked for, or the official score.
+fn leaderboard_column(rank_by: Option<ScoreBasis>) -> &'static str {
+    rank_by.map_or("score", ScoreBasis::score_column)
+}
 
 pub async fn get_progress(
     pool: &PgPool,
@@ -11,14 +46,13 @@ pub async fn get_progress(
 ) -> Result<Option<Progress>, AppError> {
     let callsign_upper = callsign.to_uppercase();
 
-    let progress = sqlx::query_as::<_, Progress>(
+    let progress = sqlx::query_as::<_, Progress>(&format!(
         r#"
-        SELECT id, challenge_id, callsign, completed_goals, current_value,
-               score, current_tier, last_qso_date, updated_at
+        SELECT {PROGRESS_COLUMNS}
         FROM progress
         WHERE challenge_id = $1 AND callsign = $2
-        "#,
-    )
+        "#
+    ))
     .bind(challenge_id)
     .bind(&callsign_upper)
     .fetch_optional(pool)
@@ -27,40 +61,232 @@ pub async fn get_progress(
     Ok(progress)
 }
 
-pub async fn upsert_progress(
+/// Completed goals of a progress row with their evidence, oldest first.
+pub async fn get_goal_completions<'e, E>(
+    executor: E,
+    progress_id: Uuid,
+) -> Result<Vec<GoalCompletion>, AppError>
+where
+    E: sqlx::PgExecutor<'e>,
+{
+    let rows = sqlx::query_as::<_, GoalCompletionRow>(
+        r#"
+        SELECT goal_id, completed_at, status, contacted_callsign, band, mode, qso_at,
+               confirmation_source
+        FROM goal_completions
+        WHERE progress_id = $1
+        ORDER BY completed_at, goal_id
+        "#,
+    )
+    .bind(progress_id)
+    .fetch_all(executor)
+    .await?;
+
+    Ok(rows.into_iter().map(GoalCompletion::from).collect())
+}
+
+/// Insert or update goal completions of a progress row.
+async fn upsert_goal_completions(
+    conn: &mut PgConnection,
+    progress_id: Uuid,
+    completions: &[GoalCompletion],
+) -> Result<(), AppError> {
+    let mut goal_ids = Vec::with_capacity(completions.len());
+    let mut completed_at = Vec::with_capacity(completions.len());
+    let mut statuses = Vec::with_capacity(completions.len());
+    let mut contacted_callsigns: Vec<Option<String>> = Vec::with_capacity(completions.len());
+    let mut bands: Vec<Option<String>> = Vec::with_capacity(completions.len());
+    let mut modes: Vec<Option<String>> = Vec::with_capacity(completions.len());
+    let mut qso_at: Vec<Option<DateTime<Utc>>> = Vec::with_capacity(completions.len());
+    let mut sources: Vec<Option<String>> = Vec::with_capacity(completions.len());
+
+    for completion in completions {
+        let evidence = completion.evidence.as_ref();
+        goal_ids.push(completion.goal_id.clone());
+        completed_at.push(completion.completed_at);
+        statuses.push(completion.status.as_str());
+        contacted_callsigns.push(evidence.map(|e| e.contacted_callsign.clone()));
+        bands.push(evidence.and_then(|e| e.band.clone()));
+        modes.push(evidence.and_then(|e| e.mode.clone()));
+        qso_at.push(evidence.and_then(|e| e.qso_at));
+        sources.push(evidence.and_then(|e| e.confirmation_source.clone()));
+    }
+
+    sqlx::query(
+        r#"
+        INSERT INTO goal_completions (progress_id, goal_id, completed_at, status,
+                                      contacted_callsign, band, mode, qso_at,
+                                      confirmation_source)
+        SELECT $1, * FROM UNNEST($2::text[], $3::timestamptz[], $4::text[], $5::text[],
+                                 $6::text[], $7::text[], $8::timestamptz[], $9::text[])
+        ON CONFLICT (progress_id, goal_id) DO UPDATE
+        SET completed_at = EXCLUDED.completed_at, status = EXCLUDED.status,
+            contacted_callsign = EXCLUDED.contacted_callsign,
+            band = EXCLUDED.band, mode = EXCLUDED.mode, qso_at = EXCLUDED.qso_at,
+            confirmation_source = EXCLUDED.confirmation_source
+        "#,
+    )
+    .bind(progress_id)
+    .bind(&goal_ids)
+    .bind(&completed_at)
+    .bind(&statuses)
+    .bind(&contacted_callsigns)
+    .bind(&bands)
+    .bind(&modes)
+    .bind(&qso_at)
+    .bind(&sources)
+    .execute(conn)
+    .await?;
+
+    Ok(())
+}
+
+/// Outcome of merging a progress report.
+pub struct ProgressMerge {
+    pub progress: Progress,
+    pub previous_score: i32,
+    pub conflicts: Vec<ProgressConflict>,
+}
+
+/// Merge a report into a participant's progress and rescore it.
+///
+/// The row is locked for the merge so reports from two devices arriving at
+/// once can't overwrite each other.
+pub async fn merge_progress(
     pool: &PgPool,
-    challenge_id: Uuid,
+    challenge: &Challenge,
     callsign: &str,
     req: &ReportProgressRequest,
-    score: i32,
-    current_tier: Option<&str>,
+) -> Result<ProgressMerge, AppError> {
+    let callsign_upper = callsign.to_uppercase();
+
+    let mut tx = pool.begin().await?;
+
+    // Make sure there is a row to lock, even on the first report
+    sqlx::query(
+        r#"
+        INSERT INTO progress (id, challenge_id, callsign, challenge_version)
+        VALUES ($1, $2, $3, $4)
+        ON CONFLICT (challenge_id, callsign) DO NOTHING
+        "#,
+    )
+    .bind(Uuid::new_v4())
+    .bind(challenge.id)
+    .bind(&callsign_upper)
+    .bind(challenge.version)
+    .execute(&mut *tx)
+    .await?;
+
+    let existing = sqlx::query_as::<_, Progress>(&format!(
+        r#"
+        SELECT {PROGRESS_COLUMNS}
+        FROM progress
+        WHERE challenge_id = $1 AND callsign = $2
+        FOR UPDATE
+        "#
+    ))
+    .bind(challenge.id)
+    .bind(&callsign_upper)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    let completions = get_goal_completions(&mut *tx, existing.id).await?;
+    let merged = req.merge_into(Some(&existing), &completions, Utc::now());
+    upsert_goal_completions(&mut tx, existing.id, &merged.completions).await?;
+
+    let config = &challenge.configuration;
+    let scores = calculate_scores(config, &merged.goals_with_status(), merged.current_value);
+    let current_tier = determine_tier(config, scores.score);
+
+    let progress = sqlx::query_as::<_, Progress>(&format!(
+        r#"
+        UPDATE progress
+        SET current_value = $2, score = $3, worked_score = $4, confirmed_score = $5,
+            current_tier = $6, last_qso_date = $7, challenge_version = $8, updated_at = now()
+        WHERE id = $1
+        RETURNING {PROGRESS_COLUMNS}
+        "#
+    ))
+    .bind(existing.id)
+    .bind(merged.current_value)
+    .bind(scores.score)
+    .bind(scores.worked_score)
+    .bind(scores.confirmed_score)
+    .bind(current_tier.as_deref())
+    .bind(merged.last_qso_date)
+    .bind(challenge.version)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+
+    Ok(ProgressMerge {
+        progress,
+        previous_score: existing.score,
+        conflicts: merged.conflicts,
+    })
+}
+
+/// Clear a participant's progress so they can start over, revoking the tier
+/// badges they no longer reach.
+pub async fn reset_progress(
+    pool: &PgPool,
+    challenge: &Challenge,
+    callsign: &str,
 ) -> Result<Progress, AppError> {
-    let id = Uuid::new_v4();
     let callsign_upper = callsign.to_uppercase();
-    let completed_goals = serde_json::to_value(&req.completed_goals)?;
+    let config = &challenge.configuration;
+    let scores = calculate_scores(config, &[], 0);
+    let current_tier = determine_tier(config, scores.score);
 
-    let progress = sqlx::query_as::<_, Progress>(
+    let mut tx = pool.begin().await?;
+
+    sqlx::query(
         r#"
-        INSERT INTO progress (id, challenge_id, callsign, completed_goals, current_value, score, current_tier, last_qso_date)
-        VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
-        ON CONFLICT (challenge_id, callsign) DO UPDATE
-        SET completed_goals = $4, current_value = $5, score = $6,
-            current_tier = $7, last_qso_date = $8, updated_at = now()
-        RETURNING id, challenge_id, callsign, completed_goals, current_value,
-                  score, current_tier, last_qso_date, updated_at
+        DELETE FROM goal_completions
+        WHERE progress_id = (
+            SELECT id FROM progress WHERE challenge_id = $1 AND callsign = $2
+        )
         "#,
     )
-    .bind(id)
-    .bind(challenge_id)
+    .bind(challenge.id)
+    .bind(&callsign_upper)
+    .execute(&mut *tx)
+    .await?;
+
+    let progress = sqlx::query_as::<_, Progress>(&format!(
+        r#"
+        INSERT INTO progress (id, challenge_id, callsign, score, worked_score, confirmed_score,
+                              current_tier, challenge_version)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+        ON CONFLICT (challenge_id, callsign) DO UPDATE
+        SET current_value = 0, score = $4, worked_score = $5, confirmed_score = $6,
+            current_tier = $7, last_qso_date = NULL, challenge_version = $8,
+            updated_at = now()
+        RETURNING {PROGRESS_COLUMNS}
+        "#
+    ))
+    .bind(Uuid::new_v4())
+    .bind(challenge.id)
     .bind(&callsign_upper)
-    .bind(&completed_goals)
-    .bind(req.current_value)
-    .bind(score)
-    .bind(current_tier)
-    .bind(req.last_qso_date)
-    .fetch_one(pool)
+    .bind(scores.score)
+    .bind(scores.worked_score)
+    .bind(scores.confirmed_score)
+    .bind(current_tier.as_deref())
+    .bind(challenge.version)
+    .fetch_one(&mut *tx)
     .await?;
 
+    revoke_tier_badges(
+        &mut *tx,
+        challenge.id,
+        &callsign_upper,
+        &reached_tiers(config, scores.score),
+    )
+    .await?;
+
+    tx.commit().await?;
+
     Ok(progress)
 }
 
@@ -96,21 +322,22 @@ pub async fn get_leaderboard(
 ) -> Result<(Vec<LeaderboardEntry>, i64), AppError> {
     let limit = query.limit.unwrap_or(100).min(100);
     let offset = query.offset.unwrap_or(0);
+    let score = leaderboard_column(query.rank_by);
 
-    let entries = sqlx::query_as::<_, LeaderboardEntry>(
+    let entries = sqlx::query_as::<_, LeaderboardEntry>(&format!(
         r#"
         SELECT
-            RANK() OVER (ORDER BY score DESC, updated_at ASC) as rank,
+            RANK() OVER (ORDER BY {score} DESC, updated_at ASC) as rank,
             callsign,
-            score,
+            {score} as score,
             current_tier,
-            CASE WHEN score > 0 THEN updated_at ELSE NULL END as completed_at
+            CASE WHEN {score} > 0 THEN updated_at ELSE NULL END as completed_at
         FROM progress
         WHERE challenge_id = $1
-        ORDER BY score DESC, updated_at ASC
+        ORDER BY {score} DESC, updated_at ASC
         LIMIT $2 OFFSET $3
-        "#,
-    )
+        "#
+    ))
     .bind(challenge_id)
     .bind(limit)
     .bind(offset)
@@ -130,18 +357,20 @@ pub async fn get_leaderboard_around(
     challenge_id: Uuid,
     callsign: &str,
     range: i64,
+    rank_by: Option<ScoreBasis>,
 ) -> Result<Vec<LeaderboardEntry>, AppError> {
     let callsign_upper = callsign.to_uppercase();
+    let score = leaderboard_column(rank_by);
 
-    let entries = sqlx::query_as::<_, LeaderboardEntry>(
+    let entries = sqlx::query_as::<_, LeaderboardEntry>(&format!(
         r#"
         WITH ranked AS (
             SELECT
-                RANK() OVER (ORDER BY score DESC, updated_at ASC) as rank,
+                RANK() OVER (ORDER BY {score} DESC, updated_at ASC) as rank,
                 callsign,
-                score,
+                {score} as score,
                 current_tier,
-                CASE WHEN score > 0 THEN updated_at ELSE NULL END as completed_at
+                CASE WHEN {score} > 0 THEN updated_at ELSE NULL END as completed_at
             FROM progress
             WHERE challenge_id = $1
         )
@@ -157,8 +386,8 @@ pub async fn get_leaderboard_around(
             AND
             (SELECT rank FROM ranked WHERE callsign = $2) + $3
         ORDER BY rank
-        "#,
-    )
+        "#
+    ))
     .bind(challenge_id)
     .bind(&callsign_upper)
     .bind(range)
@@ -168,6 +397,181 @@ pub async fn get_leaderboard_around(
     Ok(entries)
 }
 
+async fn get_ranks(
+    conn: &mut PgConnection,
+    challenge_id: Uuid,
+) -> Result<HashMap<String, i64>, AppError> {
+    let rows: Vec<(String, i64)> = sqlx::query_as(
+        r#"
+        SELECT callsign, RANK() OVER (ORDER BY score DESC, updated_at ASC) as rank
+        FROM progress
+        WHERE challenge_id = $1
+        "#,
+    )
+    .bind(challenge_id)
+    .fetch_all(conn)
+    .await?;
+
+    Ok(rows.into_iter().collect())
+}
+
+/// Number of progress rows recomputed per batch during a rescore.
+pub const RESCORE_BATCH_SIZE: i64 = 500;
+
+/// Recompute score, tier and earned tier badges for every participant of a
+/// challenge under its current configuration.
+///
+/// Rows are processed in batches of `batch_size` inside a single transaction
+/// so the leaderboard never shows a half-rescored state. `updated_at` is left
+/// untouched because it is the leaderboard tiebreaker. Also returns the
+/// newly awarded badges as `(callsign, badge_id)` pairs.
+///
+/// Without `awards`, only the stored scores and tiers are recomputed; no
+/// badges are awarded or revoked and no certificates issued.
+pub async fn rescore_challenge(
+    pool: &PgPool,
+    challenge: &Challenge,
+    batch_size: i64,
+    awards: bool,
+) -> Result<(RescoreSummary, Vec<(String, Uuid)>), AppError> {
+    let config = &challenge.configuration;
+    let mut summary = RescoreSummary {
+        challenge_id: challenge.id,
+        version: challenge.version,
+        ..Default::default()
+    };
+
+    let mut new_badges = Vec::new();
+
+    let mut tx = pool.begin().await?;
+
+    let ranks_before = get_ranks(&mut tx, challenge.id).await?;
+
+    let mut last_id = Uuid::nil();
+    loop {
+        let batch = sqlx::query_as::<_, Progress>(&format!(
+            r#"
+            SELECT {PROGRESS_COLUMNS}
+            FROM progress
+            WHERE challenge_id = $1 AND id > $2
+            ORDER BY id
+            LIMIT $3
+            FOR UPDATE
+            "#
+        ))
+        .bind(challenge.id)
+        .bind(last_id)
+        .bind(batch_size)
+        .fetch_all(&mut *tx)
+        .await?;
+
+        let Some(last) = batch.last() else {
+            break;
+        };
+        last_id = last.id;
+
+        for progress in &batch {
+            let scores = calculate_scores(
+                config,
+                &progress.goals_with_status(),
+                progress.current_value,
+            );
+            let score = scores.score;
+            let current_tier = determine_tier(config, score);
+
+            if score != progress.score
+                || scores.worked_score != progress.worked_score
+                || scores.confirmed_score != progress.confirmed_score
+                || current_tier != progress.current_tier
+                || progress.challenge_version != Some(challenge.version)
+            {
+                sqlx::query(
+                    r#"
+                    UPDATE progress
+                    SET score = $2, worked_score = $3, confirmed_score = $4, current_tier = $5,
+                        challenge_version = $6
+                    WHERE id = $1
+                    "#,
+                )
+                .bind(progress.id)
+                .bind(score)
+                .bind(scores.worked_score)
+                .bind(scores.confirmed_score)
+                .bind(current_tier.as_deref())
+                .bind(challenge.version)
+                .execute(&mut *tx)
+                .await?;
+            }
+
+            if score != progress.score {
+                summary.scores_changed += 1;
+            }
+            if current_tier != progress.current_tier {
+                summary.tiers_changed += 1;
+            }
+
+            summary.participants_rescored += 1;
+
+            if !awards {
+                continue;
+            }
+
+            let tiers = reached_tiers(config, score);
+            let awarded =
+                award_tier_badges(&mut *tx, challenge.id, &progress.callsign, &tiers).await?;
+            let revoked =
+                revoke_tier_badges(&mut *tx, challenge.id, &progress.callsign, &tiers).await?;
+
+            let earned = earned_certificates(
+                config,
+                &progress.goals_with_status(),
+                progress.current_value,
+                score,
+            );
+            let issued =
+                issue_certificates(&mut *tx, challenge, &progress.callsign, &earned).await?;
+
+            summary.badges_awarded += awarded.len() as i64;
+            summary.certificates_issued += issued.len() as i64;
+            summary.badges_revoked += revoked as i64;
+
+            new_badges.extend(
+                awarded
+                    .into_iter()
+                    .map(|badge_id| (progress.callsign.clone(), badge_id)),
+            );
+        }
+    }
+
+    let ranks_after = get_ranks(&mut tx, challenge.id).await?;
+    summary.ranks_changed = ranks_after
+        .iter()
+        .filter(|(callsign, rank)| ranks_before.get(*callsign) != Some(*rank))
+        .count() as i64;
+
+    tx.commit().await?;
+
+    Ok((summary, new_badges))
+}
+
+/// Challenges queued for a background rescore, oldest first.
+pub async fn list_pending_rescores(pool: &PgPool) -> Result<Vec<Uuid>, AppError> {
+    let ids = sqlx::query_scalar("SELECT challenge_id FROM pending_rescores ORDER BY queued_at")
+        .fetch_all(pool)
+        .await?;
+
+    Ok(ids)
+}
+
+pub async fn delete_pending_rescore(pool: &PgPool, challenge_id: Uuid) -> Result<(), AppError> {
+    sqlx::query("DELETE FROM pending_rescores WHERE challenge_id = $1")
+        .bind(challenge_id)
+        .execute(pool)
+        .await?;
+
+    Ok(())
+}
+
 impl From<serde_json::Error> for AppError {
     fn from(e: serde_json::Error) -> Self {
         AppError::Internal(e.to_string())
@@ -0,0 +1,212 @@
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::error::AppError;
+use crate::models::activity::Activity;
+use crate::models::{CommentRow, ReactionKind, ReactionRow};
+
+/// An activity that `user_id` may react to and comment on: their own, or a
+/// friend's public or friends-only activity.
+pub async fn get_activity_for_interaction(
+    pool: &PgPool,
+    activity_id: Uuid,
+    user_id: Uuid,
+) -> Result<Option<Activity>, AppError> {
+    let activity = sqlx::query_as::<_, Activity>(
+        r#"
+        SELECT a.id, a.user_id, a.callsign, a.activity_type, a.timestamp, a.details,
+               a.audience, a.created_at
+        FROM activities a
+        WHERE a.id = $1
+          AND (
+              a.user_id = $2
+              OR (a.audience IN ('public', 'friends') AND EXISTS (
+                  SELECT 1 FROM friendships f
+                  WHERE f.user_id = $2 AND f.friend_id = a.user_id
+              ))
+          )
+        "#,
+    )
+    .bind(activity_id)
+    .bind(user_id)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(activity)
+}
+
+/// Add a reaction. Adding the same reaction twice is a no-op.
+pub async fn add_reaction(
+    pool: &PgPool,
+    activity_id: Uuid,
+    user_id: Uuid,
+    kind: ReactionKind,
+) -> Result<(), AppError> {
+    sqlx::query(
+        r#"
+        INSERT INTO activity_reactions (activity_id, user_id, kind)
+        VALUES ($1, $2, $3)
+        ON CONFLICT DO NOTHING
+        "#,
+    )
+    .bind(activity_id)
+    .bind(user_id)
+    .bind(kind.as_str())
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// Remove a reaction. Returns false if there was none.
+pub async fn remove_reaction(
+    pool: &PgPool,
+    activity_id: Uuid,
+    user_id: Uuid,
+    kind: ReactionKind,
+) -> Result<bool, AppError> {
+    let result = sqlx::query(
+        "DELETE FROM activity_reactions WHERE activity_id = $1 AND user_id = $2 AND kind = $3",
+    )
+    .bind(activity_id)
+    .bind(user_id)
+    .bind(kind.as_str())
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// Reactions on an activity, oldest first, skipping users blocked either way
+/// by `viewer_id`.
+pub async fn list_reactions(
+    pool: &PgPool,
+    activity_id: Uuid,
+    viewer_id: Uuid,
+) -> Result<Vec<ReactionRow>, AppError> {
+    let reactions = sqlx::query_as::<_, ReactionRow>(
+        r#"
+        SELECT u.id as user_id, u.callsign, p.display_name, r.kind, r.created_at
+        FROM activity_reactions r
+        JOIN users u ON u.id = r.user_id
+        LEFT JOIN user_profiles p ON p.user_id = u.id
+        WHERE r.activity_id = $1
+          AND NOT EXISTS (
+              SELECT 1 FROM user_blocks b
+              WHERE (b.blocker_id = $2 AND b.blocked_id = r.user_id)
+                 OR (b.blocker_id = r.user_id AND b.blocked_id = $2)
+          )
+        ORDER BY r.created_at, u.callsign
+        "#,
+    )
+    .bind(activity_id)
+    .bind(viewer_id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(reactions)
+}
+
+/// Add a comment, optionally as a reply, and return it with author info.
+pub async fn insert_comment(
+    pool: &PgPool,
+    activity_id: Uuid,
+    user_id: Uuid,
+    parent_id: Option<Uuid>,
+    body: &str,
+) -> Result<CommentRow, AppError> {
+    let comment = sqlx::query_as::<_, CommentRow>(
+        r#"
+        WITH inserted AS (
+            INSERT INTO activity_comments (activity_id, user_id, parent_id, body)
+            VALUES ($1, $2, $3, $4)
+            RETURNING id, activity_id, user_id, parent_id, body, created_at, deleted_at
+        )
+        SELECT c.id, c.activity_id, c.user_id, u.callsign, p.display_name, c.parent_id,
+               c.body, c.created_at, c.deleted_at
+        FROM inserted c
+        JOIN users u ON u.id = c.user_id
+        LEFT JOIN user_profiles p ON p.user_id = u.id
+        "#,
+    )
+    .bind(activity_id)
+    .bind(user_id)
+    .bind(parent_id)
+    .bind(body)
+    .fetch_one(pool)
+    .await?;
+
+    Ok(comment)
+}
+
+/// Get a comment by ID, including deleted ones.
+pub async fn get_comment(pool: &PgPool, comment_id: Uuid) -> Result<Option<CommentRow>, AppError> {
+    let comment = sqlx::query_as::<_, CommentRow>(
+        r#"
+        SELECT c.id, c.activity_id, c.user_id, u.callsign, p.display_name, c.parent_id,
+               c.body, c.created_at, c.deleted_at
+        FROM activity_comments c
+        JOIN users u ON u.id = c.user_id
+        LEFT JOIN user_profiles p ON p.user_id = u.id
+        WHERE c.id = $1
+        "#,
+    )
+    .bind(comment_id)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(comment)
+}
+
+/// Comments on an activity, oldest first, skipping authors blocked either
+/// way by `viewer_id`. Deleted comments are kept so replies stay threaded.
+pub async fn list_comments(
+    pool: &PgPool,
+    activity_id: Uuid,
+    viewer_id: Uuid,
+) -> Result<Vec<CommentRow>, AppError> {
+    let comments = sqlx::query_as::<_, CommentRow>(
+        r#"
+        SELECT c.id, c.activity_id, c.user_id, u.callsign, p.display_name, c.parent_id,
+               c.body, c.created_at, c.deleted_at
+        FROM activity_comments c
+        JOIN users u ON u.id = c.user_id
+        LEFT JOIN user_profiles p ON p.user_id = u.id
+        WHERE c.activity_id = $1
+          AND NOT EXISTS (
+              SELECT 1 FROM user_blocks b
+              WHERE (b.blocker_id = $2 AND b.blocked_id = c.user_id)
+                 OR (b.blocker_id = c.user_id AND b.blocked_id = $2)
+          )
+        ORDER BY c.created_at, c.id
+        "#,
+    )
+    .bind(activity_id)
+    .bind(viewer_id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(comments)
+}
+
+/// Soft-delete a comment. `deleted_by` is `None` when an admin removes it.
+/// Returns false if the comment doesn't exist or is already deleted.
+pub async fn delete_comment(
+    pool: &PgPool,
+    comment_id: Uuid,
+    deleted_by: Option<Uuid>,
+) -> Result<bool, AppError> {
+    let result = sqlx::query(
+        r#"
+        UPDATE activity_comments
+        SET deleted_at = now(), deleted_by = $2
+        WHERE id = $1 AND deleted_at IS NULL
+        "#,
+    )
+    .bind(comment_id)
+    .bind(deleted_by)
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
@@ -0,0 +1,222 @@
+use chrono::{DateTime, Utc};
+use sqlx::{PgConnection, PgPool};
+use uuid::Uuid;
+
+use crate::error::AppError;
+use crate::models::{
+    Challenge, ChallengeStatus, ChallengeTemplate, CreateChallengeTemplateRequest,
+};
+
+use super::challenges::insert_challenge_copy;
+
+const TEMPLATE_COLUMNS: &str =
+    "id, name_template, source_challenge_id, last_challenge_id, recurrence, \
+     first_period_start, next_period_start, created_at, updated_at";
+
+/// Create a template, instantiating its first period right away if it has
+/// already started. Both happen in one transaction, so a failed
+/// instantiation leaves no template behind.
+pub async fn create_template(
+    pool: &PgPool,
+    req: &CreateChallengeTemplateRequest,
+) -> Result<ChallengeTemplate, AppError> {
+    let mut tx = pool.begin().await?;
+
+    let template = sqlx::query_as::<_, ChallengeTemplate>(&format!(
+        r#"
+        INSERT INTO challenge_templates
+            (name_template, source_challenge_id, recurrence, first_period_start, next_period_start)
+        VALUES ($1, $2, $3, $4, $4)
+        RETURNING {TEMPLATE_COLUMNS}
+        "#
+    ))
+    .bind(&req.name_template)
+    .bind(req.source_challenge_id)
+    .bind(req.recurrence.as_str())
+    .bind(req.first_period_start)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    let template = match instantiate_template(&mut tx, &template, Utc::now()).await? {
+        Some(advanced) => advanced,
+        None => template,
+    };
+    tx.commit().await?;
+
+    Ok(template)
+}
+
+pub async fn list_templates(pool: &PgPool) -> Result<Vec<ChallengeTemplate>, AppError> {
+    let templates = sqlx::query_as::<_, ChallengeTemplate>(&format!(
+        r#"
+        SELECT {TEMPLATE_COLUMNS}
+        FROM challenge_templates
+        ORDER BY created_at DESC
+        "#
+    ))
+    .fetch_all(pool)
+    .await?;
+
+    Ok(templates)
+}
+
+pub async fn delete_template(pool: &PgPool, id: Uuid) -> Result<bool, AppError> {
+    let result = sqlx::query("DELETE FROM challenge_templates WHERE id = $1")
+        .bind(id)
+        .execute(pool)
+        .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// Instantiate every template whose next period has started. Each run
+/// creates a published challenge for the current period with calendar
+/// `timeConstraints`, archives the template's previous instance and
+/// advances the template. Templates are locked with `SKIP LOCKED`, so
+/// concurrent servers never create the same period twice. A template that
+/// fails is logged and skipped until the next run, so it can't hold up the
+/// others.
+pub async fn instantiate_due_templates(pool: &PgPool) -> Result<u64, AppError> {
+    let mut created = 0;
+    let mut failed: Vec<Uuid> = Vec::new();
+
+    loop {
+        let now = Utc::now();
+        let mut tx = pool.begin().await?;
+
+        let template = sqlx::query_as::<_, ChallengeTemplate>(&format!(
+            r#"
+            SELECT {TEMPLATE_COLUMNS}
+            FROM challenge_templates
+            WHERE next_period_start <= $1 AND id <> ALL($2)
+            ORDER BY next_period_start
+            LIMIT 1
+            FOR UPDATE SKIP LOCKED
+            "#
+        ))
+        .bind(now)
+        .bind(&failed)
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        let Some(template) = template else {
+            break;
+        };
+
+        match instantiate_template(&mut tx, &template, now).await {
+            Ok(_) => {
+                tx.commit().await?;
+                created += 1;
+            }
+            Err(e) => {
+                tracing::warn!(
+                    template_id = %template.id,
+                    error = ?e,
+                    "Failed to instantiate challenge template"
+                );
+                failed.push(template.id);
+            }
+        }
+    }
+
+    Ok(created)
+}
+
+/// Create the challenge for the template's current period. Returns the
+/// advanced template, or `None` if no period has started yet.
+async fn instantiate_template(
+    conn: &mut PgConnection,
+    template: &ChallengeTemplate,
+    now: DateTime<Utc>,
+) -> Result<Option<ChallengeTemplate>, AppError> {
+    let Some((start, end)) = template.due_period(now)? else {
+        return Ok(None);
+    };
+
+    // Prefer the latest instance so edits made to it carry forward.
+    let source = match template.last_challenge_id {
+        Some(id) => get_challenge_in(conn, id, false).await?,
+        None => None,
+    };
+    let source = match source {
+        Some(source) => source,
+        None => get_challenge_in(conn, template.source_challenge_id, true)
+            .await?
+            .ok_or(AppError::ChallengeNotFound {
+                challenge_id: template.source_challenge_id,
+            })?,
+    };
+
+    let mut configuration = source.configuration.clone();
+    if let Some(obj) = configuration.as_object_mut() {
+        obj.insert(
+            "timeConstraints".to_string(),
+            serde_json::json!({
+                "type": "calendar",
+                "startDate": start,
+                "endDate": end - chrono::Duration::seconds(1),
+                "timezone": "UTC",
+            }),
+        );
+    }
+
+    let challenge = insert_challenge_copy(
+        conn,
+        &source,
+        &template.instance_name(start),
+        &configuration,
+        ChallengeStatus::Published,
+    )
+    .await?;
+
+    if let Some(previous) = template.last_challenge_id {
+        sqlx::query(
+            r#"
+            UPDATE challenges
+            SET status = 'archived', updated_at = now()
+            WHERE id = $1 AND status <> 'archived' AND deleted_at IS NULL
+            "#,
+        )
+        .bind(previous)
+        .execute(&mut *conn)
+        .await?;
+    }
+
+    let template = sqlx::query_as::<_, ChallengeTemplate>(&format!(
+        r#"
+        UPDATE challenge_templates
+        SET last_challenge_id = $2, next_period_start = $3, updated_at = now()
+        WHERE id = $1
+        RETURNING {TEMPLATE_COLUMNS}
+        "#
+    ))
+    .bind(template.id)
+    .bind(challenge.id)
+    .bind(end)
+    .fetch_one(&mut *conn)
+    .await?;
+
+    Ok(Some(template))
+}
+
+async fn get_challenge_in(
+    conn: &mut PgConnection,
+    id: Uuid,
+    include_deleted: bool,
+) -> Result<Option<Challenge>, AppError> {
+    let challenge = sqlx::query_as::<_, Challenge>(
+        r#"
+        SELECT id, version, name, description, author, category, challenge_type,
+               configuration, invite_config, hamalert_config,
+               created_at, updated_at, status, publish_at, deleted_at
+        FROM challenges
+        WHERE id = $1 AND ($2 OR deleted_at IS NULL)
+        "#,
+    )
+    .bind(id)
+    .bind(include_deleted)
+    .fetch_optional(conn)
+    .await?;
+
+    Ok(challenge)
+}
@@ -2,7 +2,7 @@ use sqlx::PgPool;
 use uuid::Uuid;
 
 use crate::error::AppError;
-use crate::models::User;
+use crate::models::{User, UserSummary};
 
 pub async fn get_user_by_callsign(pool: &PgPool, callsign: &str) -> Result<Option<User>, AppError> {
     let user = sqlx::query_as::<_, User>(
@@ -34,18 +34,34 @@ pub async fn get_user_by_id(pool: &PgPool, user_id: Uuid) -> Result<Option<User>
     Ok(user)
 }
 
-pub async fn search_users(pool: &PgPool, query: &str, limit: i64) -> Result<Vec<User>, AppError> {
+/// Search users by callsign. When `viewer_id` is set, users blocked by or
+/// blocking the viewer are excluded.
+pub async fn search_users(
+    pool: &PgPool,
+    query: &str,
+    viewer_id: Option<Uuid>,
+    limit: i64,
+) -> Result<Vec<UserSummary>, AppError> {
     let pattern = format!("%{}%", query.to_uppercase());
-    let users = sqlx::query_as::<_, User>(
+    let users = sqlx::query_as::<_, UserSummary>(
         r#"
-        SELECT id, callsign, created_at
-        FROM users
-        WHERE UPPER(callsign) LIKE $1
-        ORDER BY callsign
-        LIMIT $2
+        SELECT u.id, u.callsign, p.display_name
+        FROM users u
+        LEFT JOIN user_profiles p ON p.user_id = u.id
+        LEFT JOIN user_privacy_settings s ON s.user_id = u.id
+        WHERE UPPER(u.callsign) LIKE $1
+          AND NOT COALESCE(s.hide_from_search, false)
+          AND NOT EXISTS (
+              SELECT 1 FROM user_blocks b
+              WHERE (b.blocker_id = $2 AND b.blocked_id = u.id)
+                 OR (b.blocker_id = u.id AND b.blocked_id = $2)
+          )
+        ORDER BY u.callsign
+        LIMIT $3
         "#,
     )
     .bind(&pattern)
+    .bind(viewer_id)
     .bind(limit)
     .fetch_all(pool)
     .await?;
@@ -4,6 +4,7 @@ use axum::{
     response::{IntoResponse, Response},
     Json,
 };
+use chrono::{DateTime, Utc};
 use serde::Serialize;
 use uuid::Uuid;
 
@@ -12,6 +13,12 @@ pub enum AppError {
     #[error("Challenge not found")]
     ChallengeNotFound { challenge_id: Uuid },
 
+    #[error("Challenge version not found")]
+    ChallengeVersionNotFound { challenge_id: Uuid, version: i32 },
+
+    #[error("Challenge template not found")]
+    TemplateNotFound { template_id: Uuid },
+
     #[error("Badge not found")]
     BadgeNotFound { badge_id: Uuid },
 
@@ -33,15 +40,30 @@ pub enum AppError {
     #[error("Friendship not found")]
     FriendshipNotFound { friendship_id: Uuid },
 
+    #[error("Activity not found")]
+    ActivityNotFound { activity_id: Uuid },
+
+    #[error("Comment not found")]
+    CommentNotFound { comment_id: Uuid },
+
+    #[error("Certificate not found")]
+    CertificateNotFound { code: String },
+
     #[error("Already friends with this user")]
     AlreadyFriends,
 
     #[error("Friend request already exists")]
     FriendRequestExists,
 
+    #[error("Friend request was declined recently")]
+    FriendRequestDeclined { retry_after: DateTime<Utc> },
+
     #[error("Cannot send friend request to yourself")]
     CannotFriendSelf,
 
+    #[error("User is blocked")]
+    UserBlocked,
+
     #[error("Already joined this challenge")]
     AlreadyJoined,
 
@@ -63,6 +85,21 @@ pub enum AppError {
     #[error("Challenge has ended")]
     ChallengeEnded,
 
+    #[error("Challenge is archived and read-only")]
+    ChallengeArchived,
+
+    #[error("Challenge not completed")]
+    ChallengeNotCompleted,
+
+    #[error("Cannot change challenge status from {from} to {to}")]
+    InvalidStatusTransition { from: String, to: String },
+
+    #[error("Idempotency key was used for a different request")]
+    IdempotencyKeyMismatch,
+
+    #[error("A request with this idempotency key is still in progress")]
+    IdempotencyKeyInUse,
+
     #[error("Invalid or revoked token")]
     InvalidToken,
 
@@ -103,6 +140,19 @@ impl IntoResponse for AppError {
                 "CHALLENGE_NOT_FOUND",
                 Some(serde_json::json!({ "challengeId": challenge_id })),
             ),
+            Self::ChallengeVersionNotFound {
+                challenge_id,
+                version,
+            } => (
+                StatusCode::NOT_FOUND,
+                "CHALLENGE_VERSION_NOT_FOUND",
+                Some(serde_json::json!({ "challengeId": challenge_id, "version": version })),
+            ),
+            Self::TemplateNotFound { template_id } => (
+                StatusCode::NOT_FOUND,
+                "TEMPLATE_NOT_FOUND",
+                Some(serde_json::json!({ "templateId": template_id })),
+            ),
             Self::BadgeNotFound { badge_id } => (
                 StatusCode::NOT_FOUND,
                 "BADGE_NOT_FOUND",
@@ -138,13 +188,34 @@ impl IntoResponse for AppError {
                 "FRIENDSHIP_NOT_FOUND",
                 Some(serde_json::json!({ "friendshipId": friendship_id })),
             ),
+            Self::ActivityNotFound { activity_id } => (
+                StatusCode::NOT_FOUND,
+                "ACTIVITY_NOT_FOUND",
+                Some(serde_json::json!({ "activityId": activity_id })),
+            ),
+            Self::CommentNotFound { comment_id } => (
+                StatusCode::NOT_FOUND,
+                "COMMENT_NOT_FOUND",
+                Some(serde_json::json!({ "commentId": comment_id })),
+            ),
+            Self::CertificateNotFound { code } => (
+                StatusCode::NOT_FOUND,
+                "CERTIFICATE_NOT_FOUND",
+                Some(serde_json::json!({ "code": code })),
+            ),
             Self::AlreadyFriends => (StatusCode::CONFLICT, "ALREADY_FRIENDS", None),
             Self::FriendRequestExists => (StatusCode::CONFLICT, "FRIEND_REQUEST_EXISTS", None),
+            Self::FriendRequestDeclined { retry_after } => (
+                StatusCode::CONFLICT,
+                "FRIEND_REQUEST_DECLINED",
+                Some(serde_json::json!({ "retryAfter": retry_after })),
+            ),
             Self::CannotFriendSelf => (
                 StatusCode::UNPROCESSABLE_ENTITY,
                 "CANNOT_FRIEND_SELF",
                 None,
             ),
+            Self::UserBlocked => (StatusCode::FORBIDDEN, "USER_BLOCKED", None),
             Self::AlreadyJoined => (StatusCode::CONFLICT, "ALREADY_JOINED", None),
             Self::NotParticipating => (StatusCode::FORBIDDEN, "NOT_PARTICIPATING", None),
             Self::InviteRequired => (StatusCode::FORBIDDEN, "INVITE_REQUIRED", None),
@@ -152,6 +223,19 @@ impl IntoResponse for AppError {
             Self::InviteExhausted => (StatusCode::FORBIDDEN, "INVITE_EXHAUSTED", None),
             Self::MaxParticipants => (StatusCode::FORBIDDEN, "MAX_PARTICIPANTS", None),
             Self::ChallengeEnded => (StatusCode::BAD_REQUEST, "CHALLENGE_ENDED", None),
+            Self::ChallengeArchived => (StatusCode::CONFLICT, "CHALLENGE_ARCHIVED", None),
+            Self::ChallengeNotCompleted => (StatusCode::CONFLICT, "CHALLENGE_NOT_COMPLETED", None),
+            Self::InvalidStatusTransition { from, to } => (
+                StatusCode::CONFLICT,
+                "INVALID_STATUS_TRANSITION",
+                Some(serde_json::json!({ "from": from, "to": to })),
+            ),
+            Self::IdempotencyKeyMismatch => (
+                StatusCode::UNPROCESSABLE_ENTITY,
+                "IDEMPOTENCY_KEY_MISMATCH",
+                None,
+            ),
+            Self::IdempotencyKeyInUse => (StatusCode::CONFLICT, "IDEMPOTENCY_KEY_IN_USE", None),
             Self::InvalidToken => (StatusCode::UNAUTHORIZED, "INVALID_TOKEN", None),
             Self::Forbidden => (StatusCode::FORBIDDEN, "FORBIDDEN", None),
             Self::RateLimited => (StatusCode::TOO_MANY_REQUESTS, "RATE_LIMITED", None),
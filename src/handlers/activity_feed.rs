@@ -3,44 +3,154 @@ use axum::{
     http::StatusCode,
 };
 
-use crate::extractors::Json;
+use crate::extractors::{Json, Path};
 use sqlx::PgPool;
+use uuid::Uuid;
 
 use crate::auth::AuthContext;
+use crate::config::Config;
 use crate::db;
 use crate::error::AppError;
-use crate::models::activity::{ActivityResponse, FeedItemResponse, ReportActivityRequest};
+use crate::milestones;
+use crate::models::activity::{
+    ActivityResponse, FeedCursor, FeedFilter, FeedItemResponse, FeedItemRow, FeedScope,
+    ReportActivityRequest,
+};
+use crate::models::{normalize_activity, ActivityTypeInfo, ACTIVITY_TYPES};
 
 use super::DataResponse;
 
 /// POST /v1/activities
-/// Report a notable activity.
+/// Report a notable activity. Details are validated against the activity
+/// type registry; unknown types are stored as reported. Reporting a
+/// milestone that was already posted returns the existing activity with
+/// `200 OK`.
 pub async fn report_activity(
     State(pool): State<PgPool>,
     Extension(auth): Extension<AuthContext>,
-    Json(body): Json<ReportActivityRequest>,
+    Extension(config): Extension<Config>,
+    Json(mut body): Json<ReportActivityRequest>,
 ) -> Result<(StatusCode, Json<DataResponse<ActivityResponse>>), AppError> {
+    let (activity_type, details) = normalize_activity(
+        &body.activity_type,
+        &body.details,
+        config.custom_activity_max_bytes,
+    )
+    .map_err(|message| AppError::Validation { message })?;
+    body.activity_type = activity_type;
+    body.details = details;
+
     let user = db::get_or_create_user(&pool, &auth.callsign).await?;
 
-    let activity = db::insert_activity(
+    let audience = match body.audience {
+        Some(audience) => audience,
+        None => db::get_privacy_settings(&pool, user.id)
+            .await?
+            .default_activity_audience(),
+    };
+
+    // Milestones the server may already have posted are deduplicated
+    let dedupe_key = milestones::dedupe_key(&body.activity_type, &body.details);
+
+    let inserted = db::insert_activity(
         &pool,
         user.id,
         &auth.callsign,
-        &body.activity_type,
-        body.timestamp,
-        &body.details,
+        &body,
+        audience,
+        dedupe_key.as_deref(),
     )
     .await?;
 
+    let (status, activity) = match inserted {
+        Some(activity) => (StatusCode::CREATED, activity),
+        // Only a dedupe_key conflict skips the insert
+        None => {
+            let existing = match dedupe_key {
+                Some(key) => db::get_activity_by_dedupe_key(&pool, user.id, &key).await?,
+                None => None,
+            };
+            let existing = existing
+                .ok_or_else(|| AppError::Internal("Deduplicated activity not found".to_string()))?;
+            (StatusCode::OK, existing)
+        }
+    };
+
     let response: ActivityResponse = activity.into();
-    Ok((StatusCode::CREATED, Json(DataResponse { data: response })))
+    Ok((status, Json(DataResponse { data: response })))
 }
 
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ActivityTypesResponse {
+    pub types: &'static [ActivityTypeInfo],
+    /// Whether unknown types are accepted.
+    pub custom_accepted: bool,
+    /// Size cap for the details of an unknown type, in bytes.
+    pub custom_max_bytes: usize,
+}
+
+/// GET /v1/activities/types
+/// Describe the known activity types and their details fields.
+pub async fn list_activity_types(
+    Extension(config): Extension<Config>,
+) -> Json<DataResponse<ActivityTypesResponse>> {
+    Json(DataResponse {
+        data: ActivityTypesResponse {
+            types: ACTIVITY_TYPES,
+            custom_accepted: config.custom_activity_max_bytes > 0,
+            custom_max_bytes: config.custom_activity_max_bytes,
+        },
+    })
+}
+
+/// Most activity types accepted in one `filter`.
+const MAX_FILTER_TYPES: usize = 20;
+
 #[derive(serde::Deserialize)]
 pub struct FeedQuery {
     pub limit: Option<i64>,
+    /// Comma-separated activity types to include.
     pub filter: Option<String>,
+    /// Opaque cursor from `pagination.nextCursor`.
     pub before: Option<String>,
+    /// Feed only: friends (default), mine or all.
+    pub scope: Option<FeedScope>,
+}
+
+impl FeedQuery {
+    fn to_filter(&self) -> Result<FeedFilter, AppError> {
+        let activity_types = self.filter.as_deref().map(|f| {
+            f.split(',')
+                .map(str::trim)
+                .filter(|t| !t.is_empty())
+                .map(String::from)
+                .collect::<Vec<_>>()
+        });
+        let activity_types = activity_types.filter(|types| !types.is_empty());
+        if activity_types
+            .as_ref()
+            .is_some_and(|types| types.len() > MAX_FILTER_TYPES)
+        {
+            return Err(AppError::Validation {
+                message: format!("filter accepts at most {} activity types", MAX_FILTER_TYPES),
+            });
+        }
+
+        let before = match self.before.as_deref() {
+            Some(s) => Some(FeedCursor::decode(s).ok_or_else(|| AppError::Validation {
+                message: "Invalid cursor".to_string(),
+            })?),
+            None => None,
+        };
+
+        Ok(FeedFilter {
+            activity_types,
+            before,
+            // Fetch one extra to determine hasMore
+            limit: self.limit.unwrap_or(50).clamp(1, 100) + 1,
+        })
+    }
 }
 
 #[derive(serde::Serialize)]
@@ -57,46 +167,83 @@ pub struct FeedPagination {
     pub next_cursor: Option<String>,
 }
 
+impl FeedResponse {
+    /// Build a page from rows fetched with one extra to detect more.
+    fn from_rows(mut rows: Vec<FeedItemRow>, filter: &FeedFilter) -> Self {
+        let page_size = (filter.limit - 1) as usize;
+        let has_more = rows.len() > page_size;
+        rows.truncate(page_size);
+
+        let next_cursor = if has_more {
+            rows.last().map(|row| FeedCursor::from(row).encode())
+        } else {
+            None
+        };
+
+        Self {
+            items: rows.into_iter().map(Into::into).collect(),
+            pagination: FeedPagination {
+                has_more,
+                next_cursor,
+            },
+        }
+    }
+}
+
 /// GET /v1/feed
-/// Get activity feed from friends, with cursor-based pagination.
+/// Get the activity feed with cursor-based pagination: friends' activities,
+/// the caller's own (`scope=mine`) or both (`scope=all`), optionally
+/// filtered by activity type.
 pub async fn get_feed(
     State(pool): State<PgPool>,
     Extension(auth): Extension<AuthContext>,
     Query(params): Query<FeedQuery>,
 ) -> Result<Json<DataResponse<FeedResponse>>, AppError> {
     let user = db::get_or_create_user(&pool, &auth.callsign).await?;
+    let filter = params.to_filter()?;
 
-    let limit = params.limit.unwrap_or(50).min(100).max(1);
+    let rows =
+        db::get_feed_for_user(&pool, user.id, params.scope.unwrap_or_default(), &filter).await?;
 
-    // Parse cursor (ISO 8601 timestamp)
-    let before = params.before.as_deref().and_then(|s| {
-        chrono::DateTime::parse_from_rfc3339(s)
-            .ok()
-            .map(|dt| dt.with_timezone(&chrono::Utc))
-    });
+    Ok(Json(DataResponse {
+        data: FeedResponse::from_rows(rows, &filter),
+    }))
+}
 
-    // Fetch one extra to determine hasMore
-    let rows = db::get_feed_for_user(&pool, user.id, limit + 1, before).await?;
+/// GET /v1/users/:id/activities
+/// One user's activity timeline, showing only what the caller may see:
+/// public activities to everyone, friends-only ones to friends and
+/// everything to the user themself.
+pub async fn get_user_activities(
+    State(pool): State<PgPool>,
+    Path(user_id): Path<Uuid>,
+    auth: Option<Extension<AuthContext>>,
+    Query(params): Query<FeedQuery>,
+) -> Result<Json<DataResponse<FeedResponse>>, AppError> {
+    let filter = params.to_filter()?;
 
-    let has_more = rows.len() as i64 > limit;
-    let truncated: Vec<_> = rows.into_iter().take(limit as usize).collect();
+    db::get_user_by_id(&pool, user_id)
+        .await?
+        .ok_or(AppError::UserNotFound { user_id })?;
 
-    let next_cursor = if has_more {
-        truncated.last().map(|row| row.created_at.to_rfc3339())
-    } else {
-        None
+    let viewer_id = match auth {
+        Some(Extension(auth)) => db::get_user_by_callsign(&pool, &auth.callsign)
+            .await?
+            .map(|u| u.id),
+        None => None,
     };
 
-    let items: Vec<FeedItemResponse> = truncated.into_iter().map(Into::into).collect();
+    // Blocked users can't see each other's timelines
+    if let Some(viewer_id) = viewer_id {
+        if db::is_blocked_between(&pool, viewer_id, user_id).await? {
+            return Err(AppError::UserNotFound { user_id });
+        }
+    }
+
+    let rows = db::get_user_timeline(&pool, user_id, viewer_id, &filter).await?;
 
     Ok(Json(DataResponse {
-        data: FeedResponse {
-            items,
-            pagination: FeedPagination {
-                has_more,
-                next_cursor,
-            },
-        },
+        data: FeedResponse::from_rows(rows, &filter),
     }))
 }
 
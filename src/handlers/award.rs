@@ -0,0 +1,92 @@
+use axum::{
+    extract::{Extension, Query, State},
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+};
+use chrono::Utc;
+
+use crate::extractors::Path;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::auth::AuthContext;
+use crate::award::{application_rows, to_csv, ApplicationFormat, ApplicationQuery};
+use crate::db;
+use crate::error::AppError;
+use crate::models::ScoreBasis;
+use crate::scoring::{calculate_percentage, get_total_goals, goals_on_basis, score_basis};
+use crate::templates::{self, AwardApplicationPage};
+
+use super::challenges::get_public_challenge;
+
+/// GET /v1/challenges/:id/application
+/// Award application for a collection challenge the caller has completed:
+/// each goal with its supporting contact, as CSV or, with `?format=html`,
+/// a printable page.
+pub async fn get_award_application(
+    State(pool): State<PgPool>,
+    Path(challenge_id): Path<Uuid>,
+    Extension(auth): Extension<AuthContext>,
+    Query(query): Query<ApplicationQuery>,
+) -> Result<Response, AppError> {
+    let challenge = get_public_challenge(&pool, challenge_id).await?;
+    let config = &challenge.configuration;
+
+    if get_total_goals(config) == 0 {
+        return Err(AppError::Validation {
+            message: "Award applications are only available for collection challenges".to_string(),
+        });
+    }
+
+    let progress = db::get_progress(&pool, challenge_id, &auth.callsign)
+        .await?
+        .ok_or(AppError::NotParticipating)?;
+
+    let goals = progress.goals_with_status();
+    let counted = goals_on_basis(config, &goals, score_basis(config));
+    if calculate_percentage(config, &counted, progress.current_value) < 100.0 {
+        return Err(AppError::ChallengeNotCompleted);
+    }
+
+    let completions = db::get_goal_completions(&pool, progress.id).await?;
+    let rows = application_rows(config, &completions);
+
+    match query.format {
+        ApplicationFormat::Csv => {
+            let filename: String = progress
+                .callsign
+                .chars()
+                .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+                .collect();
+            let disposition = format!(
+                "attachment; filename=\"award-application-{}-{}.csv\"",
+                challenge_id, filename
+            );
+            Ok((
+                [
+                    (header::CONTENT_TYPE, "text/csv; charset=utf-8".to_string()),
+                    (header::CONTENT_DISPOSITION, disposition),
+                ],
+                to_csv(&rows),
+            )
+                .into_response())
+        }
+        ApplicationFormat::Html => {
+            let confirmed = goals_on_basis(config, &goals, ScoreBasis::Confirmed).len();
+            let page = AwardApplicationPage {
+                challenge_name: challenge.name,
+                author: challenge.author,
+                callsign: progress.callsign,
+                summary: format!(
+                    "{} of {} goals, {} confirmed",
+                    goals.len(),
+                    get_total_goals(config),
+                    confirmed
+                ),
+                generated_on: Utc::now().format("%Y-%m-%d").to_string(),
+                rows,
+            };
+            Ok(templates::render(StatusCode::OK, &page))
+        }
+    }
+}
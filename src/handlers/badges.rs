@@ -16,8 +16,8 @@ use crate::models::BadgeResponse;
 
 use super::challenges::DataResponse;
 
-const MAX_BADGE_SIZE: usize = 1024 * 1024; // 1MB
-const ALLOWED_CONTENT_TYPES: &[&str] = &["image/png", "image/svg+xml", "image/jpeg"];
+pub(crate) const MAX_BADGE_SIZE: usize = 1024 * 1024; // 1MB
+pub(crate) const ALLOWED_CONTENT_TYPES: &[&str] = &["image/png", "image/svg+xml", "image/jpeg"];
 
 #[derive(Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -0,0 +1,123 @@
+use axum::{
+    extract::{Extension, State},
+    http::StatusCode,
+};
+use uuid::Uuid;
+
+use crate::extractors::{Json, Path};
+use sqlx::PgPool;
+
+use crate::auth::AuthContext;
+use crate::db;
+use crate::error::AppError;
+use crate::models::{BlockedUserResponse, MutedUserResponse, UserTargetRequest};
+
+use super::DataResponse;
+
+/// POST /v1/blocks
+/// Block a user. Removes any friendship and pending friend requests between
+/// the two users and hides each from the other.
+pub async fn block_user(
+    State(pool): State<PgPool>,
+    Extension(auth): Extension<AuthContext>,
+    Json(body): Json<UserTargetRequest>,
+) -> Result<StatusCode, AppError> {
+    let user = db::get_or_create_user(&pool, &auth.callsign).await?;
+
+    let target = db::get_user_by_id(&pool, body.user_id)
+        .await?
+        .ok_or(AppError::UserNotFound {
+            user_id: body.user_id,
+        })?;
+
+    if target.id == user.id {
+        return Err(AppError::Validation {
+            message: "Cannot block yourself".to_string(),
+        });
+    }
+
+    db::block_user(&pool, user.id, target.id).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// DELETE /v1/blocks/:user_id
+/// Unblock a user. Friendships removed by the block are not restored.
+pub async fn unblock_user(
+    State(pool): State<PgPool>,
+    Path(user_id): Path<Uuid>,
+    Extension(auth): Extension<AuthContext>,
+) -> Result<StatusCode, AppError> {
+    let user = db::get_or_create_user(&pool, &auth.callsign).await?;
+
+    if !db::unblock_user(&pool, user.id, user_id).await? {
+        return Err(AppError::UserNotFound { user_id });
+    }
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// GET /v1/blocks
+/// List users blocked by the authenticated user
+pub async fn list_blocks(
+    State(pool): State<PgPool>,
+    Extension(auth): Extension<AuthContext>,
+) -> Result<Json<DataResponse<Vec<BlockedUserResponse>>>, AppError> {
+    let user = db::get_or_create_user(&pool, &auth.callsign).await?;
+    let blocks = db::list_blocks(&pool, user.id).await?;
+
+    Ok(Json(DataResponse {
+        data: blocks.into_iter().map(Into::into).collect(),
+    }))
+}
+
+/// POST /v1/mutes
+/// Mute a friend: their activities are hidden from the feed without
+/// unfriending them.
+pub async fn mute_user(
+    State(pool): State<PgPool>,
+    Extension(auth): Extension<AuthContext>,
+    Json(body): Json<UserTargetRequest>,
+) -> Result<StatusCode, AppError> {
+    let user = db::get_or_create_user(&pool, &auth.callsign).await?;
+
+    if !db::are_friends(&pool, user.id, body.user_id).await? {
+        return Err(AppError::Validation {
+            message: "Only friends can be muted".to_string(),
+        });
+    }
+
+    db::mute_user(&pool, user.id, body.user_id).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// DELETE /v1/mutes/:user_id
+/// Unmute a user
+pub async fn unmute_user(
+    State(pool): State<PgPool>,
+    Path(user_id): Path<Uuid>,
+    Extension(auth): Extension<AuthContext>,
+) -> Result<StatusCode, AppError> {
+    let user = db::get_or_create_user(&pool, &auth.callsign).await?;
+
+    if !db::unmute_user(&pool, user.id, user_id).await? {
+        return Err(AppError::UserNotFound { user_id });
+    }
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// GET /v1/mutes
+/// List users muted by the authenticated user
+pub async fn list_mutes(
+    State(pool): State<PgPool>,
+    Extension(auth): Extension<AuthContext>,
+) -> Result<Json<DataResponse<Vec<MutedUserResponse>>>, AppError> {
+    let user = db::get_or_create_user(&pool, &auth.callsign).await?;
+    let mutes = db::list_mutes(&pool, user.id).await?;
+
+    Ok(Json(DataResponse {
+        data: mutes.into_iter().map(Into::into).collect(),
+    }))
+}
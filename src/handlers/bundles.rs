@@ -0,0 +1,170 @@
+use std::collections::HashSet;
+
+use axum::{
+    extract::State,
+    http::{header, StatusCode},
+    response::IntoResponse,
+};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use chrono::Utc;
+
+use crate::extractors::{Json, Path};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::db;
+use crate::error::AppError;
+use crate::models::{
+    BundleBadge, BundleChallenge, ChallengeBundle, DecodedBadge, ImportBundleResponse,
+    CHALLENGE_BUNDLE_FORMAT_VERSION,
+};
+use crate::scoring::validate_scoring;
+
+use super::badges::{ALLOWED_CONTENT_TYPES, MAX_BADGE_SIZE};
+use super::challenges::DataResponse;
+
+/// Request body limit for bundle imports: several badges at the maximum
+/// size, plus base64 overhead.
+pub const MAX_BUNDLE_SIZE: usize = 32 * 1024 * 1024;
+
+const CATEGORIES: &[&str] = &["award", "event", "club", "personal", "other"];
+const CHALLENGE_TYPES: &[&str] = &["collection", "cumulative", "timeBounded"];
+
+/// GET /v1/admin/challenges/:id/export
+/// Download a challenge and its badge images as a portable bundle.
+pub async fn export_challenge(
+    State(pool): State<PgPool>,
+    Path(id): Path<Uuid>,
+) -> Result<impl IntoResponse, AppError> {
+    let challenge = db::get_challenge(&pool, id)
+        .await?
+        .ok_or(AppError::ChallengeNotFound { challenge_id: id })?;
+    let badges = db::list_badges_with_images(&pool, id).await?;
+
+    let bundle = ChallengeBundle {
+        format_version: CHALLENGE_BUNDLE_FORMAT_VERSION,
+        exported_at: Utc::now(),
+        challenge: BundleChallenge {
+            name: challenge.name,
+            description: challenge.description,
+            author: challenge.author,
+            category: challenge.category,
+            challenge_type: challenge.challenge_type,
+            configuration: challenge.configuration,
+            invite_config: challenge.invite_config,
+            hamalert_config: challenge.hamalert_config,
+        },
+        badges: badges
+            .into_iter()
+            .map(|b| BundleBadge {
+                id: b.id,
+                name: b.name,
+                tier_id: b.tier_id,
+                content_type: b.content_type,
+                image_data: BASE64.encode(&b.image_data),
+            })
+            .collect(),
+    };
+
+    let disposition = format!("attachment; filename=\"challenge-{}.json\"", id);
+
+    Ok(([(header::CONTENT_DISPOSITION, disposition)], Json(bundle)))
+}
+
+/// POST /v1/admin/challenges/import
+/// Create a draft challenge from a bundle. Challenge and badge IDs are
+/// reassigned and tier badge references rewritten to match.
+pub async fn import_challenge(
+    State(pool): State<PgPool>,
+    Json(bundle): Json<ChallengeBundle>,
+) -> Result<(StatusCode, Json<DataResponse<ImportBundleResponse>>), AppError> {
+    let badges = validate_bundle(&bundle)?;
+
+    let (challenge, badges, badge_ids) =
+        db::import_challenge(&pool, &bundle.challenge, &badges).await?;
+    let base_url = "";
+
+    Ok((
+        StatusCode::CREATED,
+        Json(DataResponse {
+            data: ImportBundleResponse {
+                challenge: challenge.into(),
+                badges: badges
+                    .into_iter()
+                    .map(|b| b.into_response(base_url))
+                    .collect(),
+                badge_ids,
+            },
+        }),
+    ))
+}
+
+fn validate_bundle(bundle: &ChallengeBundle) -> Result<Vec<DecodedBadge>, AppError> {
+    let invalid = |message: String| AppError::Validation { message };
+
+    if bundle.format_version == 0 || bundle.format_version > CHALLENGE_BUNDLE_FORMAT_VERSION {
+        return Err(invalid(format!(
+            "Unsupported bundle formatVersion {} (this server reads up to {})",
+            bundle.format_version, CHALLENGE_BUNDLE_FORMAT_VERSION
+        )));
+    }
+
+    let challenge = &bundle.challenge;
+    if challenge.name.trim().is_empty() {
+        return Err(invalid("Challenge name must not be empty".to_string()));
+    }
+    if !CATEGORIES.contains(&challenge.category.as_str()) {
+        return Err(invalid(format!(
+            "Invalid category '{}'",
+            challenge.category
+        )));
+    }
+    if !CHALLENGE_TYPES.contains(&challenge.challenge_type.as_str()) {
+        return Err(invalid(format!(
+            "Invalid challenge type '{}'",
+            challenge.challenge_type
+        )));
+    }
+    if !challenge.configuration.is_object() {
+        return Err(invalid("configuration must be an object".to_string()));
+    }
+    validate_scoring(&challenge.configuration).map_err(invalid)?;
+
+    let mut seen = HashSet::new();
+    bundle
+        .badges
+        .iter()
+        .map(|badge| {
+            if !seen.insert(badge.id) {
+                return Err(invalid(format!("Duplicate badge id {}", badge.id)));
+            }
+            if !ALLOWED_CONTENT_TYPES.contains(&badge.content_type.as_str()) {
+                return Err(invalid(format!(
+                    "Badge '{}' has unsupported content type '{}'",
+                    badge.name, badge.content_type
+                )));
+            }
+
+            let image_data = BASE64.decode(&badge.image_data).map_err(|e| {
+                invalid(format!(
+                    "Badge '{}' image is not valid base64: {}",
+                    badge.name, e
+                ))
+            })?;
+            if image_data.is_empty() || image_data.len() > MAX_BADGE_SIZE {
+                return Err(invalid(format!(
+                    "Badge '{}' image must be between 1 and {} bytes",
+                    badge.name, MAX_BADGE_SIZE
+                )));
+            }
+
+            Ok(DecodedBadge {
+                source_id: badge.id,
+                name: badge.name.clone(),
+                tier_id: badge.tier_id.clone(),
+                content_type: badge.content_type.clone(),
+                image_data,
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,53 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::Response,
+};
+use sqlx::PgPool;
+
+use crate::db;
+use crate::models::CertificateKind;
+use crate::templates::{render, MessagePage};
+
+/// GET /certificates/:code
+/// Renders an HTML page confirming a certificate is genuine, for the
+/// verification link printed on it.
+pub async fn certificate_page(State(pool): State<PgPool>, Path(code): Path<String>) -> Response {
+    match db::get_certificate_by_code(&pool, &code).await {
+        Ok(Some(certificate)) => {
+            let achieved = match certificate.kind() {
+                CertificateKind::TopTier => format!(
+                    "reached {} in {}",
+                    certificate.achievement(),
+                    certificate.challenge_name
+                ),
+                CertificateKind::Completion => format!("completed {}", certificate.challenge_name),
+            };
+            let summary = format!(
+                "{} {} on {}. Verification code {}.",
+                certificate.callsign,
+                achieved,
+                certificate.achieved_at.format("%-d %B %Y"),
+                certificate.verification_code
+            );
+            render(
+                StatusCode::OK,
+                &MessagePage::new("Certificate verified", &summary),
+            )
+        }
+        Ok(None) => render(
+            StatusCode::NOT_FOUND,
+            &MessagePage::new(
+                "Certificate not found",
+                "No certificate has this verification code. Check that it was typed correctly.",
+            ),
+        ),
+        Err(e) => {
+            tracing::error!(error = ?e, "Failed to look up certificate");
+            render(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                &MessagePage::new("Something went wrong", "Please try again later."),
+            )
+        }
+    }
+}
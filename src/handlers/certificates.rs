@@ -0,0 +1,234 @@
+use axum::{
+    body::Body,
+    extract::{Extension, State},
+    http::{header, Response, StatusCode},
+};
+use chrono::Utc;
+
+use crate::extractors::{Json, Path};
+use serde::Serialize;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::auth::AuthContext;
+use crate::certificate::{self, top_tier, validate_template, CERTIFICATE_CSP, DEFAULT_TEMPLATE};
+use crate::config::Config;
+use crate::db;
+use crate::error::AppError;
+use crate::models::{
+    Certificate, CertificateKind, CertificateResponse, CertificateTemplate,
+    CertificateTemplateRequest, CertificateTemplateResponse, ChallengeStatus,
+};
+
+use super::challenges::get_public_challenge;
+use super::DataResponse;
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CertificateListResponse {
+    pub certificates: Vec<CertificateResponse>,
+}
+
+/// GET /v1/challenges/:id/certificates
+/// The caller's certificates for a challenge.
+pub async fn list_certificates(
+    State(pool): State<PgPool>,
+    Extension(config): Extension<Config>,
+    Path(challenge_id): Path<Uuid>,
+    Extension(auth): Extension<AuthContext>,
+) -> Result<Json<DataResponse<CertificateListResponse>>, AppError> {
+    get_public_challenge(&pool, challenge_id).await?;
+
+    let certificates = db::list_certificates(&pool, challenge_id, &auth.callsign).await?;
+
+    Ok(Json(DataResponse {
+        data: CertificateListResponse {
+            certificates: certificates
+                .into_iter()
+                .map(|c| c.into_response(&config.invite_base_url))
+                .collect(),
+        },
+    }))
+}
+
+/// GET /v1/certificates/:code
+/// Public verification: the certificate's details if the code is genuine.
+pub async fn verify_certificate(
+    State(pool): State<PgPool>,
+    Extension(config): Extension<Config>,
+    Path(code): Path<String>,
+) -> Result<Json<DataResponse<CertificateResponse>>, AppError> {
+    let certificate = db::get_certificate_by_code(&pool, &code)
+        .await?
+        .ok_or(AppError::CertificateNotFound { code })?;
+
+    Ok(Json(DataResponse {
+        data: certificate.into_response(&config.invite_base_url),
+    }))
+}
+
+/// GET /v1/certificates/:code/image
+/// The certificate as SVG, drawn with the challenge's template.
+pub async fn get_certificate_image(
+    State(pool): State<PgPool>,
+    Extension(config): Extension<Config>,
+    Path(code): Path<String>,
+) -> Result<Response<Body>, AppError> {
+    let certificate = db::get_certificate_by_code(&pool, &code)
+        .await?
+        .ok_or(AppError::CertificateNotFound { code })?;
+
+    let template = db::get_certificate_template(&pool, certificate.challenge_id).await?;
+    let template = template
+        .as_ref()
+        .map_or(DEFAULT_TEMPLATE, |t| t.svg.as_str());
+    let verify_url = format!(
+        "{}/certificates/{}",
+        config.invite_base_url, certificate.verification_code
+    );
+    let filename = format!("certificate-{}.svg", certificate.verification_code);
+
+    svg_response(
+        certificate::render(template, &certificate, &verify_url),
+        &filename,
+    )
+}
+
+/// GET /v1/admin/challenges/:id/certificate-template
+/// The challenge's certificate template, or the built-in one.
+pub async fn get_certificate_template(
+    State(pool): State<PgPool>,
+    Path(challenge_id): Path<Uuid>,
+) -> Result<Json<DataResponse<CertificateTemplateResponse>>, AppError> {
+    db::get_challenge(&pool, challenge_id)
+        .await?
+        .ok_or(AppError::ChallengeNotFound { challenge_id })?;
+
+    let template = db::get_certificate_template(&pool, challenge_id).await?;
+
+    Ok(Json(DataResponse {
+        data: template_response(challenge_id, template),
+    }))
+}
+
+/// PUT /v1/admin/challenges/:id/certificate-template
+/// Replace the challenge's certificate template. Existing certificates are
+/// drawn with the new template from then on.
+pub async fn update_certificate_template(
+    State(pool): State<PgPool>,
+    Path(challenge_id): Path<Uuid>,
+    Json(req): Json<CertificateTemplateRequest>,
+) -> Result<Json<DataResponse<CertificateTemplateResponse>>, AppError> {
+    let challenge = db::get_challenge(&pool, challenge_id)
+        .await?
+        .ok_or(AppError::ChallengeNotFound { challenge_id })?;
+
+    if challenge.effective_status() == ChallengeStatus::Archived {
+        return Err(AppError::ChallengeArchived);
+    }
+
+    validate_template(&req.svg).map_err(|message| AppError::Validation { message })?;
+
+    let template = db::upsert_certificate_template(&pool, challenge_id, &req.svg).await?;
+
+    Ok(Json(DataResponse {
+        data: template_response(challenge_id, Some(template)),
+    }))
+}
+
+/// DELETE /v1/admin/challenges/:id/certificate-template
+/// Go back to the built-in template.
+pub async fn delete_certificate_template(
+    State(pool): State<PgPool>,
+    Path(challenge_id): Path<Uuid>,
+) -> Result<StatusCode, AppError> {
+    let challenge = db::get_challenge(&pool, challenge_id)
+        .await?
+        .ok_or(AppError::ChallengeNotFound { challenge_id })?;
+
+    if challenge.effective_status() == ChallengeStatus::Archived {
+        return Err(AppError::ChallengeArchived);
+    }
+
+    db::delete_certificate_template(&pool, challenge_id).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// GET /v1/admin/challenges/:id/certificate-template/preview
+/// The challenge's template filled in with sample values.
+pub async fn preview_certificate_template(
+    State(pool): State<PgPool>,
+    Extension(config): Extension<Config>,
+    Path(challenge_id): Path<Uuid>,
+) -> Result<Response<Body>, AppError> {
+    let challenge = db::get_challenge(&pool, challenge_id)
+        .await?
+        .ok_or(AppError::ChallengeNotFound { challenge_id })?;
+
+    let (tier_id, tier_name) = top_tier(&challenge.configuration).unzip();
+    let sample = Certificate {
+        challenge_id,
+        callsign: "N0CALL".to_string(),
+        kind: match tier_id {
+            Some(_) => CertificateKind::TopTier,
+            None => CertificateKind::Completion,
+        }
+        .as_str()
+        .to_string(),
+        challenge_name: challenge.name,
+        tier_id,
+        tier_name: tier_name.flatten(),
+        verification_code: "CW-SAMP-LE00-0000".to_string(),
+        achieved_at: Utc::now(),
+    };
+
+    let template = db::get_certificate_template(&pool, challenge_id).await?;
+    let template = template
+        .as_ref()
+        .map_or(DEFAULT_TEMPLATE, |t| t.svg.as_str());
+    let verify_url = format!(
+        "{}/certificates/{}",
+        config.invite_base_url, sample.verification_code
+    );
+
+    svg_response(
+        certificate::render(template, &sample, &verify_url),
+        "certificate-preview.svg",
+    )
+}
+
+fn template_response(
+    challenge_id: Uuid,
+    template: Option<CertificateTemplate>,
+) -> CertificateTemplateResponse {
+    match template {
+        Some(t) => CertificateTemplateResponse {
+            challenge_id: t.challenge_id,
+            svg: t.svg,
+            custom: true,
+            updated_at: Some(t.updated_at),
+        },
+        None => CertificateTemplateResponse {
+            challenge_id,
+            svg: DEFAULT_TEMPLATE.to_string(),
+            custom: false,
+            updated_at: None,
+        },
+    }
+}
+
+fn svg_response(svg: String, filename: &str) -> Result<Response<Body>, AppError> {
+    Response::builder()
+        .status(StatusCode::OK)
+        .header(header::CONTENT_TYPE, "image/svg+xml")
+        .header(header::CONTENT_SECURITY_POLICY, CERTIFICATE_CSP)
+        .header(header::X_CONTENT_TYPE_OPTIONS, "nosniff")
+        .header(
+            header::CONTENT_DISPOSITION,
+            format!("inline; filename=\"{}\"", filename),
+        )
+        .header(header::CACHE_CONTROL, "public, max-age=3600")
+        .body(Body::from(svg))
+        .map_err(|e| AppError::Internal(e.to_string()))
+}
@@ -0,0 +1,148 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::Response,
+};
+use chrono::Utc;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::db;
+use crate::error::AppError;
+use crate::models::{Challenge, ChallengeStatus, LeaderboardQuery};
+use crate::templates::{render, summarize, ChallengePage, MessagePage, PageBadge};
+
+use super::challenges::get_public_challenge;
+
+/// Leaderboard entries shown on challenge pages.
+const PAGE_LEADERBOARD_SIZE: i64 = 5;
+
+/// GET /c/:id
+/// Renders an HTML landing page for a shared challenge link.
+pub async fn challenge_page(State(pool): State<PgPool>, Path(id): Path<String>) -> Response {
+    let Ok(challenge_id) = id.parse::<Uuid>() else {
+        return challenge_not_found();
+    };
+
+    let challenge = match get_public_challenge(&pool, challenge_id).await {
+        Ok(challenge) => challenge,
+        Err(AppError::ChallengeNotFound { .. }) => return challenge_not_found(),
+        Err(e) => return page_error(e),
+    };
+
+    let deep_link = format!("carrierwave://challenge/{}", challenge.id);
+    match build_challenge_page(&pool, challenge, None, deep_link, None).await {
+        Ok(page) => render(StatusCode::OK, &page),
+        Err(e) => page_error(e),
+    }
+}
+
+/// GET /join/:token
+/// Renders an HTML landing page for a challenge invite link, noting when
+/// the invite can no longer be used.
+pub async fn join_page(State(pool): State<PgPool>, Path(token): Path<String>) -> Response {
+    let invite = match db::get_invite(&pool, &token).await {
+        Ok(Some(invite)) => invite,
+        Ok(None) => {
+            return render(
+                StatusCode::NOT_FOUND,
+                &MessagePage::new(
+                    "Invite not found",
+                    "This challenge invite doesn't exist or has been revoked.",
+                ),
+            )
+        }
+        Err(e) => return page_error(e),
+    };
+
+    let challenge = match get_public_challenge(&pool, invite.challenge_id).await {
+        Ok(challenge) => challenge,
+        Err(AppError::ChallengeNotFound { .. }) => return challenge_not_found(),
+        Err(e) => return page_error(e),
+    };
+
+    let valid = invite.is_valid(Utc::now());
+    let notice = (!valid).then(|| "This invite has expired or reached its limit.".to_string());
+    let qr_url = valid.then(|| format!("/v1/invites/{}/qr?size=400", invite.token));
+    let deep_link = format!(
+        "carrierwave://challenge/{}?invite={}",
+        challenge.id, invite.token
+    );
+
+    match build_challenge_page(&pool, challenge, notice, deep_link, qr_url).await {
+        Ok(page) => render(StatusCode::OK, &page),
+        Err(e) => page_error(e),
+    }
+}
+
+async fn build_challenge_page(
+    pool: &PgPool,
+    challenge: Challenge,
+    notice: Option<String>,
+    deep_link: String,
+    qr_url: Option<String>,
+) -> Result<ChallengePage, AppError> {
+    let participant_count = db::count_participants(pool, challenge.id).await?;
+
+    let query = LeaderboardQuery {
+        limit: Some(PAGE_LEADERBOARD_SIZE),
+        ..Default::default()
+    };
+    let (mut leaderboard, _) = db::get_leaderboard(pool, challenge.id, &query).await?;
+
+    // Pages are public, so apply everyone's leaderboard privacy
+    let callsigns: Vec<String> = leaderboard.iter().map(|e| e.callsign.clone()).collect();
+    let hidden = db::get_hidden_leaderboard_callsigns(pool, &callsigns, None).await?;
+    for entry in &mut leaderboard {
+        if let Some(visibility) = hidden.get(&entry.callsign) {
+            entry.callsign = visibility.display_callsign(&entry.callsign);
+        }
+    }
+
+    let badges = db::list_badges(pool, challenge.id)
+        .await?
+        .into_iter()
+        .map(|b| PageBadge {
+            image_url: format!("/v1/badges/{}/image", b.id),
+            name: b.name,
+        })
+        .collect();
+
+    let notice = notice.or_else(|| match challenge.effective_status() {
+        ChallengeStatus::Ended | ChallengeStatus::Archived => {
+            Some("This challenge has ended.".to_string())
+        }
+        _ => None,
+    });
+
+    Ok(ChallengePage {
+        title: format!("{} on Carrier Wave", challenge.name),
+        summary: summarize(&challenge.description),
+        name: challenge.name,
+        description: challenge.description,
+        notice,
+        participant_count,
+        leaderboard,
+        badges,
+        deep_link: Some(deep_link),
+        qr_url,
+    })
+}
+
+fn challenge_not_found() -> Response {
+    render(
+        StatusCode::NOT_FOUND,
+        &MessagePage::new(
+            "Challenge not found",
+            "This challenge doesn't exist or isn't public yet.",
+        ),
+    )
+}
+
+fn page_error(e: AppError) -> Response {
+    tracing::error!(error = ?e, "Failed to build page");
+    render(
+        StatusCode::INTERNAL_SERVER_ERROR,
+        &MessagePage::new("Something went wrong", "Please try again later."),
+    )
+}
@@ -1,18 +1,24 @@
 use axum::{
-    extract::{Query, State},
+    extract::{Extension, Query, State},
     http::{header, HeaderMap, StatusCode},
 };
 
 use crate::extractors::{Json, Path};
-use serde::Serialize;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
 use sqlx::PgPool;
 use uuid::Uuid;
 
+use crate::config::Config;
 use crate::db;
 use crate::error::AppError;
+use crate::milestones::{self, Milestone};
 use crate::models::{
-    ChallengeListItem, ChallengeResponse, CreateChallengeRequest, ListChallengesQuery,
+    Challenge, ChallengeListItem, ChallengeResponse, ChallengeStatus, ChallengeVersionListItem,
+    ChallengeVersionResponse, CloneChallengeRequest, CreateChallengeRequest, ListChallengesQuery,
+    PurgeChallengeQuery, RescoreSummary, UpdateChallengeStatusRequest,
 };
+use crate::scoring::validate_scoring;
 
 #[derive(Serialize)]
 pub struct DataResponse<T> {
@@ -28,6 +34,19 @@ pub struct ListChallengesResponse {
     pub offset: i64,
 }
 
+/// Statuses listed on the public route when no `status` filter is given.
+/// Archived challenges stay reachable by ID and by explicit filter.
+const DEFAULT_PUBLIC_STATUSES: &[&str] = &["published", "ended"];
+
+/// Fetch a challenge for a public route. Drafts and challenges that are
+/// scheduled but not yet due are reported as not found.
+pub(crate) async fn get_public_challenge(pool: &PgPool, id: Uuid) -> Result<Challenge, AppError> {
+    db::get_challenge(pool, id)
+        .await?
+        .filter(|c| c.effective_status().is_public())
+        .ok_or(AppError::ChallengeNotFound { challenge_id: id })
+}
+
 pub async fn list_challenges(
     State(pool): State<PgPool>,
     Query(query): Query<ListChallengesQuery>,
@@ -35,7 +54,13 @@ pub async fn list_challenges(
     let limit = query.limit.unwrap_or(50).min(100);
     let offset = query.offset.unwrap_or(0);
 
-    let (challenges, total) = db::list_challenges(&pool, &query).await?;
+    let statuses: Vec<&str> = match query.status {
+        Some(status) if status.is_public() => vec![status.as_str()],
+        Some(_) => vec![],
+        None => DEFAULT_PUBLIC_STATUSES.to_vec(),
+    };
+
+    let (challenges, total) = db::list_challenges(&pool, &query, &statuses, false).await?;
 
     Ok(Json(DataResponse {
         data: ListChallengesResponse {
@@ -51,9 +76,7 @@ pub async fn get_challenge(
     State(pool): State<PgPool>,
     Path(id): Path<Uuid>,
 ) -> Result<(HeaderMap, Json<DataResponse<ChallengeResponse>>), AppError> {
-    let challenge = db::get_challenge(&pool, id)
-        .await?
-        .ok_or(AppError::ChallengeNotFound { challenge_id: id })?;
+    let challenge = get_public_challenge(&pool, id).await?;
 
     let mut headers = HeaderMap::new();
     headers.insert(
@@ -76,11 +99,100 @@ pub async fn get_challenge(
     ))
 }
 
+/// GET /v1/challenges/:id/versions
+/// List every configuration version the challenge has had, newest first.
+pub async fn list_challenge_versions(
+    State(pool): State<PgPool>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<DataResponse<Vec<ChallengeVersionListItem>>>, AppError> {
+    let challenge = get_public_challenge(&pool, id).await?;
+
+    let versions = db::list_challenge_versions(&pool, challenge.id).await?;
+
+    Ok(Json(DataResponse { data: versions }))
+}
+
+/// GET /v1/challenges/:id/versions/:version
+/// Get the challenge definition as it was at a specific version.
+pub async fn get_challenge_version(
+    State(pool): State<PgPool>,
+    Path((id, version)): Path<(Uuid, i32)>,
+) -> Result<Json<DataResponse<ChallengeVersionResponse>>, AppError> {
+    let challenge = get_public_challenge(&pool, id).await?;
+
+    let snapshot = db::get_challenge_version(&pool, challenge.id, version)
+        .await?
+        .ok_or(AppError::ChallengeVersionNotFound {
+            challenge_id: id,
+            version,
+        })?;
+
+    Ok(Json(DataResponse {
+        data: snapshot.into(),
+    }))
+}
+
+/// GET /v1/admin/challenges
+/// List challenges in every lifecycle status, including drafts.
+pub async fn admin_list_challenges(
+    State(pool): State<PgPool>,
+    Query(query): Query<ListChallengesQuery>,
+) -> Result<Json<DataResponse<ListChallengesResponse>>, AppError> {
+    let limit = query.limit.unwrap_or(50).min(100);
+    let offset = query.offset.unwrap_or(0);
+
+    let statuses: Vec<&str> = match query.status {
+        Some(status) => vec![status.as_str()],
+        None => vec!["draft", "scheduled", "published", "ended", "archived"],
+    };
+    let deleted = query.deleted.unwrap_or(false);
+
+    let (challenges, total) = db::list_challenges(&pool, &query, &statuses, deleted).await?;
+
+    Ok(Json(DataResponse {
+        data: ListChallengesResponse {
+            challenges,
+            total,
+            limit,
+            offset,
+        },
+    }))
+}
+
+/// GET /v1/admin/challenges/:id
+/// Get a challenge in any lifecycle status, including drafts and
+/// soft-deleted challenges.
+pub async fn admin_get_challenge(
+    State(pool): State<PgPool>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<DataResponse<ChallengeResponse>>, AppError> {
+    let challenge = db::get_challenge_including_deleted(&pool, id)
+        .await?
+        .ok_or(AppError::ChallengeNotFound { challenge_id: id })?;
+
+    Ok(Json(DataResponse {
+        data: challenge.into(),
+    }))
+}
+
 pub async fn create_challenge(
     State(pool): State<PgPool>,
     Json(req): Json<CreateChallengeRequest>,
 ) -> Result<(StatusCode, Json<DataResponse<ChallengeResponse>>), AppError> {
-    let challenge = db::create_challenge(&pool, &req).await?;
+    validate_scoring(&req.configuration).map_err(|message| AppError::Validation { message })?;
+
+    let status = req.status.unwrap_or(ChallengeStatus::Draft);
+    match status {
+        ChallengeStatus::Draft | ChallengeStatus::Published => {}
+        ChallengeStatus::Scheduled => validate_publish_at(req.publish_at)?,
+        _ => {
+            return Err(AppError::Validation {
+                message: "New challenges must be draft, scheduled or published".to_string(),
+            });
+        }
+    }
+
+    let challenge = db::create_challenge(&pool, &req, status).await?;
 
     Ok((
         StatusCode::CREATED,
@@ -90,20 +202,90 @@ pub async fn create_challenge(
     ))
 }
 
+/// POST /v1/admin/challenges/:id/clone
+/// Copy a challenge, including its badges, as a new draft.
+pub async fn clone_challenge(
+    State(pool): State<PgPool>,
+    Path(id): Path<Uuid>,
+    body: Option<Json<CloneChallengeRequest>>,
+) -> Result<(StatusCode, Json<DataResponse<ChallengeResponse>>), AppError> {
+    let source = db::get_challenge(&pool, id)
+        .await?
+        .ok_or(AppError::ChallengeNotFound { challenge_id: id })?;
+
+    let req = body.map(|Json(req)| req).unwrap_or_default();
+    let name = req
+        .name
+        .unwrap_or_else(|| format!("{} (copy)", source.name));
+
+    let challenge = db::clone_challenge(&pool, &source, &name).await?;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(DataResponse {
+            data: challenge.into(),
+        }),
+    ))
+}
+
+#[derive(Debug, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateChallengeQuery {
+    /// Recompute all participants' progress under the new configuration.
+    pub rescore: Option<bool>,
+}
+
 pub async fn update_challenge(
     State(pool): State<PgPool>,
     Path(id): Path<Uuid>,
+    Query(query): Query<UpdateChallengeQuery>,
     Json(req): Json<CreateChallengeRequest>,
 ) -> Result<Json<DataResponse<ChallengeResponse>>, AppError> {
+    let existing = db::get_challenge(&pool, id)
+        .await?
+        .ok_or(AppError::ChallengeNotFound { challenge_id: id })?;
+
+    if existing.effective_status() == ChallengeStatus::Archived {
+        return Err(AppError::ChallengeArchived);
+    }
+
+    validate_scoring(&req.configuration).map_err(|message| AppError::Validation { message })?;
+
     let challenge = db::update_challenge(&pool, id, &req)
         .await?
         .ok_or(AppError::ChallengeNotFound { challenge_id: id })?;
 
+    // A raised or removed cap frees places for waitlisted participants.
+    let promoted = db::fill_from_waitlist(&pool, id, challenge.capacity()).await?;
+    if !promoted.is_empty() {
+        tracing::info!(challenge_id = %id, ?promoted, "Promoted from waitlist after update");
+    }
+    for callsign in &promoted {
+        let milestone = Milestone::challenge_joined(&challenge);
+        milestones::emit(&pool, callsign, milestone).await;
+    }
+
+    if query.rescore.unwrap_or(false) {
+        let (summary, new_badges) =
+            db::rescore_challenge(&pool, &challenge, db::RESCORE_BATCH_SIZE, true).await?;
+        milestones::emit_badges_earned(&pool, &challenge, &new_badges).await;
+        tracing::info!(
+            challenge_id = %id,
+            version = summary.version,
+            participants = summary.participants_rescored,
+            ranks_changed = summary.ranks_changed,
+            "Rescored challenge after update"
+        );
+    }
+
     Ok(Json(DataResponse {
         data: challenge.into(),
     }))
 }
 
+/// DELETE /v1/admin/challenges/:id
+/// Soft-delete a challenge. It can be restored until the retention window
+/// passes, after which it is purged.
 pub async fn delete_challenge(
     State(pool): State<PgPool>,
     Path(id): Path<Uuid>,
@@ -116,3 +298,119 @@ pub async fn delete_challenge(
         Err(AppError::ChallengeNotFound { challenge_id: id })
     }
 }
+
+/// POST /v1/admin/challenges/:id/rescore
+/// Recompute score, tier and earned badges for every participant under the
+/// challenge's current configuration.
+pub async fn rescore_challenge(
+    State(pool): State<PgPool>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<DataResponse<RescoreSummary>>, AppError> {
+    let challenge = db::get_challenge(&pool, id)
+        .await?
+        .ok_or(AppError::ChallengeNotFound { challenge_id: id })?;
+
+    if challenge.effective_status() == ChallengeStatus::Archived {
+        return Err(AppError::ChallengeArchived);
+    }
+
+    let (summary, new_badges) =
+        db::rescore_challenge(&pool, &challenge, db::RESCORE_BATCH_SIZE, true).await?;
+    milestones::emit_badges_earned(&pool, &challenge, &new_badges).await;
+
+    Ok(Json(DataResponse { data: summary }))
+}
+
+fn validate_publish_at(publish_at: Option<DateTime<Utc>>) -> Result<(), AppError> {
+    match publish_at {
+        Some(at) if at > Utc::now() => Ok(()),
+        Some(_) => Err(AppError::Validation {
+            message: "publishAt must be in the future".to_string(),
+        }),
+        None => Err(AppError::Validation {
+            message: "publishAt is required for scheduled challenges".to_string(),
+        }),
+    }
+}
+
+/// POST /v1/admin/challenges/:id/status
+/// Move a challenge through its lifecycle (draft, scheduled, published,
+/// ended, archived).
+pub async fn update_challenge_status(
+    State(pool): State<PgPool>,
+    Path(id): Path<Uuid>,
+    Json(req): Json<UpdateChallengeStatusRequest>,
+) -> Result<Json<DataResponse<ChallengeResponse>>, AppError> {
+    let challenge = db::get_challenge(&pool, id)
+        .await?
+        .ok_or(AppError::ChallengeNotFound { challenge_id: id })?;
+
+    let current = challenge.effective_status();
+    if !current.can_transition_to(req.status) {
+        return Err(AppError::InvalidStatusTransition {
+            from: current.as_str().to_string(),
+            to: req.status.as_str().to_string(),
+        });
+    }
+
+    let publish_at = match req.status {
+        ChallengeStatus::Scheduled => {
+            validate_publish_at(req.publish_at)?;
+            req.publish_at
+        }
+        ChallengeStatus::Published => Some(req.publish_at.unwrap_or_else(Utc::now)),
+        _ => challenge.publish_at,
+    };
+
+    let challenge = db::set_challenge_status(&pool, id, req.status, publish_at)
+        .await?
+        .ok_or(AppError::ChallengeNotFound { challenge_id: id })?;
+
+    Ok(Json(DataResponse {
+        data: challenge.into(),
+    }))
+}
+
+/// POST /v1/admin/challenges/:id/restore
+/// Restore a soft-deleted challenge within the retention window.
+pub async fn restore_challenge(
+    State(pool): State<PgPool>,
+    Extension(config): Extension<Config>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<DataResponse<ChallengeResponse>>, AppError> {
+    let challenge = db::restore_challenge(&pool, id, config.challenge_retention_days)
+        .await?
+        .ok_or(AppError::ChallengeNotFound { challenge_id: id })?;
+
+    Ok(Json(DataResponse {
+        data: challenge.into(),
+    }))
+}
+
+/// DELETE /v1/admin/challenges/:id/purge?confirm=:id
+/// Permanently delete a soft-deleted challenge and everything attached to it.
+pub async fn purge_challenge(
+    State(pool): State<PgPool>,
+    Path(id): Path<Uuid>,
+    Query(query): Query<PurgeChallengeQuery>,
+) -> Result<StatusCode, AppError> {
+    if query.confirm.as_deref() != Some(id.to_string().as_str()) {
+        return Err(AppError::Validation {
+            message: "Purging is permanent; pass ?confirm=<challenge id> to proceed".to_string(),
+        });
+    }
+
+    let challenge = db::get_challenge_including_deleted(&pool, id)
+        .await?
+        .ok_or(AppError::ChallengeNotFound { challenge_id: id })?;
+
+    if challenge.deleted_at.is_none() {
+        return Err(AppError::Validation {
+            message: "Challenge must be deleted before it can be purged".to_string(),
+        });
+    }
+
+    db::purge_challenge(&pool, id).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -2,6 +2,8 @@ use axum::{
     extract::{Extension, State},
     http::StatusCode,
 };
+use chrono::{Duration, Utc};
+use std::collections::HashMap;
 
 use crate::extractors::{Json, Path};
 use sqlx::PgPool;
@@ -10,7 +12,11 @@ use crate::auth::AuthContext;
 use crate::config::Config;
 use crate::db;
 use crate::error::AppError;
-use crate::models::{CreateFriendRequestBody, FriendInviteResponse, FriendRequestResponse};
+use crate::milestones::{self, Milestone};
+use crate::models::{
+    friend_request_retry_after, CreateFriendInviteRequest, CreateFriendRequestBody,
+    FriendInviteDetailResponse, FriendInviteResponse, FriendInviteStatus, FriendRequestResponse,
+};
 
 use super::DataResponse;
 
@@ -24,45 +30,153 @@ pub async fn get_invite_link(
     // Get or create user record for this callsign
     let user = db::get_or_create_user(&pool, &auth.callsign).await?;
 
-    // Create a new invite
-    let invite = db::create_friend_invite(&pool, user.id, config.invite_expiry_days).await?;
+    // Create a new single-use invite
+    let expires_at = Utc::now() + Duration::days(config.invite_expiry_days);
+    let invite = db::create_friend_invite(&pool, user.id, expires_at, 1).await?;
 
     Ok(Json(DataResponse {
         data: invite.into_response(&config.invite_base_url),
     }))
 }
 
+/// Upper bound on uses for one friend invite link.
+const MAX_FRIEND_INVITE_USES: i32 = 500;
+
+/// Longest expiry that can be requested for a friend invite link.
+const MAX_FRIEND_INVITE_EXPIRY_DAYS: i64 = 365;
+
+/// POST /v1/friends/invites
+/// Create a friend invite link, optionally usable by several people and with
+/// a custom expiry
+pub async fn create_friend_invite(
+    State(pool): State<PgPool>,
+    Extension(config): Extension<Config>,
+    Extension(auth): Extension<AuthContext>,
+    body: Option<Json<CreateFriendInviteRequest>>,
+) -> Result<(StatusCode, Json<DataResponse<FriendInviteDetailResponse>>), AppError> {
+    let body = body.map(|Json(b)| b).unwrap_or_default();
+    let now = Utc::now();
+
+    let max_uses = body.max_uses.unwrap_or(1);
+    if !(1..=MAX_FRIEND_INVITE_USES).contains(&max_uses) {
+        return Err(AppError::Validation {
+            message: format!("maxUses must be between 1 and {}", MAX_FRIEND_INVITE_USES),
+        });
+    }
+
+    let expires_at = body
+        .expires_at
+        .unwrap_or_else(|| now + Duration::days(config.invite_expiry_days));
+    if expires_at <= now || expires_at > now + Duration::days(MAX_FRIEND_INVITE_EXPIRY_DAYS) {
+        return Err(AppError::Validation {
+            message: format!(
+                "expiresAt must be in the future and within {} days",
+                MAX_FRIEND_INVITE_EXPIRY_DAYS
+            ),
+        });
+    }
+
+    let user = db::get_or_create_user(&pool, &auth.callsign).await?;
+    let invite = db::create_friend_invite(&pool, user.id, expires_at, max_uses).await?;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(DataResponse {
+            data: invite.into_detail_response(&config.invite_base_url, vec![]),
+        }),
+    ))
+}
+
+/// GET /v1/friends/invites
+/// List the authenticated user's invite links with who redeemed each
+pub async fn list_friend_invites(
+    State(pool): State<PgPool>,
+    Extension(config): Extension<Config>,
+    Extension(auth): Extension<AuthContext>,
+) -> Result<Json<DataResponse<Vec<FriendInviteDetailResponse>>>, AppError> {
+    let user = db::get_or_create_user(&pool, &auth.callsign).await?;
+    let invites = db::list_friend_invites(&pool, user.id).await?;
+    let redemptions = db::list_friend_invite_redemptions(&pool, user.id).await?;
+
+    let mut by_invite: HashMap<uuid::Uuid, Vec<_>> = HashMap::new();
+    for redemption in redemptions {
+        by_invite
+            .entry(redemption.invite_id)
+            .or_default()
+            .push(redemption);
+    }
+
+    let data = invites
+        .into_iter()
+        .map(|invite| {
+            let redemptions = by_invite.remove(&invite.id).unwrap_or_default();
+            invite.into_detail_response(&config.invite_base_url, redemptions)
+        })
+        .collect();
+
+    Ok(Json(DataResponse { data }))
+}
+
+/// DELETE /v1/friends/invites/:token
+/// Revoke one of the authenticated user's invite links
+pub async fn revoke_friend_invite(
+    State(pool): State<PgPool>,
+    Path(token): Path<String>,
+    Extension(auth): Extension<AuthContext>,
+) -> Result<StatusCode, AppError> {
+    let user = db::get_or_create_user(&pool, &auth.callsign).await?;
+
+    db::revoke_friend_invite(&pool, &token, user.id)
+        .await?
+        .ok_or(AppError::FriendInviteNotFound { token })?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
 /// POST /v1/friends/requests
 /// Create a friend request either by user ID or invite token
 pub async fn create_friend_request(
     State(pool): State<PgPool>,
+    Extension(config): Extension<Config>,
     Extension(auth): Extension<AuthContext>,
     Json(body): Json<CreateFriendRequestBody>,
 ) -> Result<(StatusCode, Json<DataResponse<FriendRequestResponse>>), AppError> {
     // Get or create user record for the sender
     let sender = db::get_or_create_user(&pool, &auth.callsign).await?;
 
-    // Determine the target user
-    let target_user_id = match (&body.to_user_id, &body.invite_token) {
+    // Determine the target user. An invite link is an explicit invitation
+    // from the target, so it bypasses the re-request cooldown.
+    let (target_user_id, invite) = match (&body.to_user_id, &body.invite_token) {
         (Some(user_id), None) => {
             // Direct user ID specified
             let target = db::get_user_by_id(&pool, *user_id)
                 .await?
                 .ok_or(AppError::UserNotFound { user_id: *user_id })?;
-            target.id
+            (target.id, None)
         }
         (None, Some(token)) => {
             // Invite token specified
-            let invite = db::get_valid_friend_invite(&pool, token)
-                .await?
-                .ok_or_else(|| AppError::FriendInviteNotFound {
+            let invite = db::get_friend_invite(&pool, token).await?.ok_or_else(|| {
+                AppError::FriendInviteNotFound {
                     token: token.clone(),
-                })?;
-
-            // Mark the invite as used
-            db::mark_invite_used(&pool, token, sender.id).await?;
-
-            invite.user_id
+                }
+            })?;
+
+            match invite.status(Utc::now()) {
+                FriendInviteStatus::Active => {}
+                FriendInviteStatus::Used => {
+                    return Err(AppError::FriendInviteUsed {
+                        token: token.clone(),
+                    })
+                }
+                FriendInviteStatus::Expired | FriendInviteStatus::Revoked => {
+                    return Err(AppError::FriendInviteNotFound {
+                        token: token.clone(),
+                    })
+                }
+            }
+
+            (invite.user_id, Some(invite))
         }
         (Some(_), Some(_)) => {
             return Err(AppError::Validation {
@@ -81,6 +195,10 @@ pub async fn create_friend_request(
         return Err(AppError::CannotFriendSelf);
     }
 
+    if db::is_blocked_between(&pool, sender.id, target_user_id).await? {
+        return Err(AppError::UserBlocked);
+    }
+
     // Check if already friends
     if db::are_friends(&pool, sender.id, target_user_id).await? {
         return Err(AppError::AlreadyFriends);
@@ -94,8 +212,23 @@ pub async fn create_friend_request(
         return Err(AppError::FriendRequestExists);
     }
 
-    // Create the friend request
-    let request = db::create_friend_request(&pool, sender.id, target_user_id).await?;
+    // After a decline, the sender must wait out the cooldown before asking again
+    if invite.is_none() && config.friend_request_cooldown_days > 0 {
+        let declined_at = db::get_last_declined_at(&pool, sender.id, target_user_id).await?;
+        let retry_after = declined_at.and_then(|declined_at| {
+            friend_request_retry_after(declined_at, config.friend_request_cooldown_days, Utc::now())
+        });
+        if let Some(retry_after) = retry_after {
+            return Err(AppError::FriendRequestDeclined { retry_after });
+        }
+    }
+
+    // Create the friend request, consuming a use of the invite only now that
+    // every check has passed
+    let request = match invite {
+        Some(invite) => db::redeem_friend_invite(&pool, &invite, sender.id).await?,
+        None => db::create_friend_request(&pool, sender.id, target_user_id).await?,
+    };
 
     Ok((
         StatusCode::CREATED,
@@ -131,6 +264,7 @@ pub async fn get_friend_suggestions(
         .map(|u| crate::models::FriendSuggestionResponse {
             user_id: u.id,
             callsign: u.callsign,
+            display_name: u.display_name,
         })
         .collect();
 
@@ -153,6 +287,7 @@ pub async fn list_friends(
             friendship_id: f.friendship_id,
             callsign: f.callsign,
             user_id: f.user_id,
+            display_name: f.display_name,
             accepted_at: f.created_at,
         })
         .collect();
@@ -208,6 +343,19 @@ pub async fn accept_friend_request(
         .await?
         .ok_or(AppError::FriendRequestNotFound { request_id })?;
 
+    milestones::emit(
+        &pool,
+        &accepted.from_callsign,
+        Milestone::friend_added(accepted.to_user_id, &accepted.to_callsign),
+    )
+    .await;
+    milestones::emit(
+        &pool,
+        &accepted.to_callsign,
+        Milestone::friend_added(accepted.from_user_id, &accepted.from_callsign),
+    )
+    .await;
+
     Ok((
         StatusCode::OK,
         Json(DataResponse {
@@ -241,6 +389,31 @@ pub async fn decline_friend_request(
     Ok(StatusCode::NO_CONTENT)
 }
 
+/// DELETE /v1/friends/requests/:id
+/// Cancel a pending friend request sent by the authenticated user
+pub async fn cancel_friend_request(
+    State(pool): State<PgPool>,
+    Path(request_id): Path<uuid::Uuid>,
+    Extension(auth): Extension<AuthContext>,
+) -> Result<StatusCode, AppError> {
+    let user = db::get_or_create_user(&pool, &auth.callsign).await?;
+
+    // Verify the request exists and was sent by this user
+    let request = db::get_friend_request(&pool, request_id)
+        .await?
+        .ok_or(AppError::FriendRequestNotFound { request_id })?;
+
+    if request.from_user_id != user.id {
+        return Err(AppError::Forbidden);
+    }
+
+    db::cancel_friend_request(&pool, request_id)
+        .await?
+        .ok_or(AppError::FriendRequestNotFound { request_id })?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
 /// DELETE /v1/friends/:id
 /// Remove an existing friend
 pub async fn remove_friend(
@@ -10,21 +10,21 @@ use uuid::Uuid;
 use crate::auth::AuthContext;
 use crate::db;
 use crate::error::AppError;
-use crate::models::{JoinChallengeRequest, JoinChallengeResponse};
+use crate::milestones::{self, Milestone};
+use crate::models::{ChallengeStatus, JoinChallengeRequest, JoinChallengeResponse};
 
+use super::challenges::get_public_challenge;
 use super::DataResponse;
 
 pub async fn join_challenge(
     State(pool): State<PgPool>,
     Path(challenge_id): Path<Uuid>,
-    _auth: Option<Extension<AuthContext>>,
+    auth: Option<Extension<AuthContext>>,
     Json(req): Json<JoinChallengeRequest>,
 ) -> Result<(StatusCode, Json<DataResponse<JoinChallengeResponse>>), AppError> {
-    let challenge = db::get_challenge(&pool, challenge_id)
-        .await?
-        .ok_or(AppError::ChallengeNotFound { challenge_id })?;
+    let challenge = get_public_challenge(&pool, challenge_id).await?;
 
-    if !challenge.is_active {
+    if challenge.effective_status() != ChallengeStatus::Published {
         return Err(AppError::ChallengeEnded);
     }
 
@@ -52,9 +52,18 @@ pub async fn join_challenge(
         challenge_id,
         &req.callsign,
         req.invite_token.as_deref(),
+        challenge.capacity(),
     )
     .await?;
 
+    // The callsign comes from the body, so only post for its owner
+    let is_caller =
+        auth.is_some_and(|Extension(auth)| auth.callsign.eq_ignore_ascii_case(&req.callsign));
+    if participation.status == "active" && is_caller {
+        let milestone = Milestone::challenge_joined(&challenge);
+        milestones::emit(&pool, &req.callsign, milestone).await;
+    }
+
     let historical_allowed = challenge
         .configuration
         .get("historicalQsosAllowed")
@@ -88,11 +97,19 @@ pub async fn leave_challenge(
         .execute(&pool)
         .await?;
 
-    let left = db::leave_challenge(&pool, challenge_id, &callsign_upper).await?;
+    let challenge = db::get_challenge(&pool, challenge_id).await?;
+    let capacity = challenge.as_ref().map(|c| c.capacity()).unwrap_or_default();
 
-    if left {
-        Ok(StatusCode::NO_CONTENT)
-    } else {
-        Err(AppError::NotParticipating)
+    let promoted = db::leave_challenge(&pool, challenge_id, &callsign_upper, capacity)
+        .await?
+        .ok_or(AppError::NotParticipating)?;
+
+    if let Some(challenge) = &challenge {
+        for callsign in &promoted {
+            let milestone = Milestone::challenge_joined(challenge);
+            milestones::emit(&pool, callsign, milestone).await;
+        }
     }
+
+    Ok(StatusCode::NO_CONTENT)
 }
@@ -1,27 +1,46 @@
-use axum::extract::{Query, State};
+use axum::extract::{Extension, Query, State};
 
 use crate::extractors::{Json, Path};
 use chrono::Utc;
 use sqlx::PgPool;
 use uuid::Uuid;
 
+use crate::auth::AuthContext;
 use crate::db;
 use crate::error::AppError;
 use crate::models::{LeaderboardQuery, LeaderboardResponse};
+use crate::scoring::score_basis;
 
+use super::challenges::get_public_challenge;
 use super::DataResponse;
 
 pub async fn get_leaderboard(
     State(pool): State<PgPool>,
     Path(challenge_id): Path<Uuid>,
-    Query(query): Query<LeaderboardQuery>,
+    auth: Option<Extension<AuthContext>>,
+    Query(mut query): Query<LeaderboardQuery>,
 ) -> Result<Json<DataResponse<LeaderboardResponse>>, AppError> {
-    let _challenge = db::get_challenge(&pool, challenge_id)
-        .await?
-        .ok_or(AppError::ChallengeNotFound { challenge_id })?;
+    let challenge = get_public_challenge(&pool, challenge_id).await?;
 
-    let (leaderboard, total) = if let Some(ref around) = query.around {
-        let entries = db::get_leaderboard_around(&pool, challenge_id, around, 5).await?;
+    let viewer = auth.map(|Extension(a)| a.callsign.to_uppercase());
+
+    // Don't let `around` reveal where a hidden participant is ranked.
+    if let Some(around) = query.around.take() {
+        let around = around.to_uppercase();
+        let hidden = db::get_hidden_leaderboard_callsigns(
+            &pool,
+            std::slice::from_ref(&around),
+            viewer.as_deref(),
+        )
+        .await?;
+        if hidden.is_empty() {
+            query.around = Some(around);
+        }
+    }
+
+    let (mut leaderboard, total) = if let Some(ref around) = query.around {
+        let entries =
+            db::get_leaderboard_around(&pool, challenge_id, around, 5, query.rank_by).await?;
         let total: (i64,) = sqlx::query_as(
             r#"SELECT COUNT(*) FROM progress WHERE challenge_id = $1"#,
         )
@@ -33,6 +52,14 @@ pub async fn get_leaderboard(
         db::get_leaderboard(&pool, challenge_id, &query).await?
     };
 
+    let callsigns: Vec<String> = leaderboard.iter().map(|e| e.callsign.clone()).collect();
+    let hidden = db::get_hidden_leaderboard_callsigns(&pool, &callsigns, viewer.as_deref()).await?;
+    for entry in &mut leaderboard {
+        if let Some(visibility) = hidden.get(&entry.callsign) {
+            entry.callsign = visibility.display_callsign(&entry.callsign);
+        }
+    }
+
     let user_position = if let Some(ref around) = query.around {
         leaderboard.iter().find(|e| e.callsign == *around).cloned()
     } else {
@@ -42,6 +69,9 @@ pub async fn get_leaderboard(
     Ok(Json(DataResponse {
         data: LeaderboardResponse {
             leaderboard,
+            rank_by: query
+                .rank_by
+                .unwrap_or_else(|| score_basis(&challenge.configuration)),
             total,
             user_position,
             last_updated: Utc::now(),
@@ -1,4 +1,10 @@
+pub mod award;
 pub mod badges;
+pub mod blocks;
+pub mod bundles;
+pub mod certificate_page;
+pub mod certificates;
+pub mod challenge_page;
 pub mod challenges;
 pub mod friends;
 pub mod health;
@@ -7,10 +13,21 @@ pub mod invites;
 pub mod join;
 pub mod leaderboard;
 pub mod participants;
+pub mod privacy;
+pub mod profiles;
 pub mod progress;
+pub mod qr;
+pub mod reactions;
+pub mod templates;
 pub mod users;
 
+pub use award::*;
 pub use badges::*;
+pub use blocks::*;
+pub use bundles::*;
+pub use certificate_page::*;
+pub use certificates::*;
+pub use challenge_page::*;
 pub use challenges::*;
 pub mod activity_feed;
 pub use activity_feed::*;
@@ -21,5 +38,10 @@ pub use invites::*;
 pub use join::*;
 pub use leaderboard::*;
 pub use participants::*;
+pub use privacy::*;
+pub use profiles::*;
 pub use progress::*;
+pub use qr::*;
+pub use reactions::*;
+pub use templates::*;
 pub use users::*;
@@ -0,0 +1,55 @@
+use axum::extract::{Extension, State};
+
+use crate::extractors::Json;
+use sqlx::PgPool;
+
+use crate::auth::AuthContext;
+use crate::db;
+use crate::error::AppError;
+use crate::models::{PrivacySettingsResponse, UpdatePrivacySettingsRequest};
+
+use super::DataResponse;
+
+/// GET /v1/settings/privacy
+/// Get the authenticated user's privacy settings.
+pub async fn get_privacy_settings(
+    State(pool): State<PgPool>,
+    Extension(auth): Extension<AuthContext>,
+) -> Result<Json<DataResponse<PrivacySettingsResponse>>, AppError> {
+    let user = db::get_or_create_user(&pool, &auth.callsign).await?;
+    let settings = db::get_privacy_settings(&pool, user.id).await?;
+
+    Ok(Json(DataResponse {
+        data: settings.into(),
+    }))
+}
+
+/// PUT /v1/settings/privacy
+/// Update privacy settings. Omitted fields are left unchanged.
+pub async fn update_privacy_settings(
+    State(pool): State<PgPool>,
+    Extension(auth): Extension<AuthContext>,
+    Json(req): Json<UpdatePrivacySettingsRequest>,
+) -> Result<Json<DataResponse<PrivacySettingsResponse>>, AppError> {
+    let user = db::get_or_create_user(&pool, &auth.callsign).await?;
+    let mut settings = db::get_privacy_settings(&pool, user.id).await?;
+
+    if let Some(hide) = req.hide_from_search {
+        settings.hide_from_search = hide;
+    }
+    if let Some(visibility) = req.leaderboard_visibility {
+        settings.leaderboard_visibility = visibility.as_str().to_string();
+    }
+    if let Some(audience) = req.default_activity_audience {
+        settings.default_activity_audience = audience.as_str().to_string();
+    }
+    if let Some(share) = req.share_milestones {
+        settings.share_milestones = share;
+    }
+
+    let settings = db::upsert_privacy_settings(&pool, user.id, &settings).await?;
+
+    Ok(Json(DataResponse {
+        data: settings.into(),
+    }))
+}
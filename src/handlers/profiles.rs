@@ -0,0 +1,242 @@
+use axum::{
+    body::Body,
+    extract::{Extension, State},
+    http::{header, Response, StatusCode},
+};
+use axum_extra::extract::Multipart;
+
+use crate::extractors::{Json, Path};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::auth::AuthContext;
+use crate::db;
+use crate::error::AppError;
+use crate::models::{
+    is_valid_grid, pair_goal_statuses, ProfileChallenge, ProfileResponse, ProfileStats,
+    PublicProfileResponse, UpdateProfileRequest, MAX_BIO_LENGTH, MAX_DISPLAY_NAME_LENGTH,
+    MAX_QTH_LENGTH,
+};
+use crate::scoring::{calculate_percentage, goals_on_basis, score_basis};
+
+use super::DataResponse;
+
+const MAX_AVATAR_SIZE: usize = 512 * 1024; // 512KB
+
+// No SVG: avatars are user-supplied and served from our origin.
+const ALLOWED_AVATAR_TYPES: &[&str] = &["image/png", "image/jpeg"];
+
+/// GET /v1/users/:id/profile
+/// Public profile with earned badges and challenge history. Users with an
+/// anonymous or masked leaderboard visibility get the same treatment here
+/// for viewers other than themselves and their friends.
+pub async fn get_user_profile(
+    State(pool): State<PgPool>,
+    Path(user_id): Path<Uuid>,
+    auth: Option<Extension<AuthContext>>,
+) -> Result<Json<DataResponse<PublicProfileResponse>>, AppError> {
+    let profile = db::get_user_profile(&pool, user_id)
+        .await?
+        .ok_or(AppError::UserNotFound { user_id })?;
+
+    let viewer = auth.map(|Extension(a)| a.callsign.to_uppercase());
+    let callsign = profile.callsign.to_uppercase();
+    let hidden = db::get_hidden_leaderboard_callsigns(
+        &pool,
+        std::slice::from_ref(&callsign),
+        viewer.as_deref(),
+    )
+    .await?;
+    if let Some(&visibility) = hidden.get(&callsign) {
+        return Ok(Json(DataResponse {
+            data: PublicProfileResponse::hidden(profile, visibility),
+        }));
+    }
+
+    let badges = db::get_earned_badges(&pool, &profile.callsign).await?;
+    let rows = db::get_profile_challenges(&pool, &profile.callsign).await?;
+
+    let challenges: Vec<ProfileChallenge> = rows
+        .into_iter()
+        .map(|row| {
+            let goals = pair_goal_statuses(&row.completed_goals, &row.goal_statuses);
+            let basis = score_basis(&row.configuration);
+            let percentage = calculate_percentage(
+                &row.configuration,
+                &goals_on_basis(&row.configuration, &goals, basis),
+                row.current_value,
+            );
+            ProfileChallenge {
+                challenge_id: row.challenge_id,
+                challenge_name: row.challenge_name,
+                joined_at: row.joined_at,
+                score: row.score,
+                percentage,
+                current_tier: row.current_tier,
+                completed: percentage >= 100.0,
+            }
+        })
+        .collect();
+
+    let stats = ProfileStats {
+        challenges_joined: challenges.len(),
+        challenges_completed: challenges.iter().filter(|c| c.completed).count(),
+        badges_earned: badges.len(),
+        tiers_reached: challenges
+            .iter()
+            .filter(|c| c.current_tier.is_some())
+            .count(),
+    };
+    let base_url = "";
+
+    Ok(Json(DataResponse {
+        data: PublicProfileResponse {
+            profile: profile.into_response(base_url),
+            stats,
+            badges,
+            challenges,
+        },
+    }))
+}
+
+/// PUT /v1/profile
+/// Replace the authenticated user's display name, QTH, grid and bio.
+pub async fn update_profile(
+    State(pool): State<PgPool>,
+    Extension(auth): Extension<AuthContext>,
+    Json(req): Json<UpdateProfileRequest>,
+) -> Result<Json<DataResponse<ProfileResponse>>, AppError> {
+    let req = normalize_profile(req)?;
+    let user = db::get_or_create_user(&pool, &auth.callsign).await?;
+
+    db::upsert_user_profile(&pool, user.id, &req).await?;
+
+    let profile = db::get_user_profile(&pool, user.id)
+        .await?
+        .ok_or(AppError::UserNotFound { user_id: user.id })?;
+    let base_url = "";
+
+    Ok(Json(DataResponse {
+        data: profile.into_response(base_url),
+    }))
+}
+
+/// POST /v1/profile/avatar
+/// Upload an avatar image (multipart field `image`, PNG or JPEG).
+pub async fn upload_avatar(
+    State(pool): State<PgPool>,
+    Extension(auth): Extension<AuthContext>,
+    mut multipart: Multipart,
+) -> Result<Json<DataResponse<ProfileResponse>>, AppError> {
+    let mut image: Option<(Vec<u8>, String)> = None;
+
+    while let Some(field) = multipart
+        .next_field()
+        .await
+        .map_err(|e| AppError::Validation {
+            message: format!("Failed to read multipart field: {}", e),
+        })?
+    {
+        if field.name() != Some("image") {
+            continue;
+        }
+
+        let ct = field
+            .content_type()
+            .map(|s| s.to_string())
+            .unwrap_or_default();
+
+        if !ALLOWED_AVATAR_TYPES.contains(&ct.as_str()) {
+            return Err(AppError::Validation {
+                message: format!("Invalid content type '{}'. Allowed: PNG, JPEG", ct),
+            });
+        }
+
+        let data = field.bytes().await.map_err(|e| AppError::Validation {
+            message: format!("Failed to read image data: {}", e),
+        })?;
+
+        if data.len() > MAX_AVATAR_SIZE {
+            return Err(AppError::Validation {
+                message: format!("Image too large. Maximum size is {} bytes", MAX_AVATAR_SIZE),
+            });
+        }
+
+        image = Some((data.to_vec(), ct));
+    }
+
+    let (data, content_type) = image.ok_or(AppError::Validation {
+        message: "Missing required field: image".to_string(),
+    })?;
+
+    let user = db::get_or_create_user(&pool, &auth.callsign).await?;
+    db::set_user_avatar(&pool, user.id, Some((&data, &content_type))).await?;
+
+    let profile = db::get_user_profile(&pool, user.id)
+        .await?
+        .ok_or(AppError::UserNotFound { user_id: user.id })?;
+    let base_url = "";
+
+    Ok(Json(DataResponse {
+        data: profile.into_response(base_url),
+    }))
+}
+
+/// DELETE /v1/profile/avatar
+pub async fn delete_avatar(
+    State(pool): State<PgPool>,
+    Extension(auth): Extension<AuthContext>,
+) -> Result<StatusCode, AppError> {
+    let user = db::get_or_create_user(&pool, &auth.callsign).await?;
+    db::set_user_avatar(&pool, user.id, None).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// GET /v1/users/:id/avatar
+pub async fn get_user_avatar(
+    State(pool): State<PgPool>,
+    Path(user_id): Path<Uuid>,
+) -> Result<Response<Body>, AppError> {
+    let avatar = db::get_user_avatar(&pool, user_id)
+        .await?
+        .ok_or(AppError::UserNotFound { user_id })?;
+
+    let response = Response::builder()
+        .status(StatusCode::OK)
+        .header(header::CONTENT_TYPE, avatar.avatar_content_type)
+        .header(header::CACHE_CONTROL, "public, max-age=3600")
+        .body(Body::from(avatar.avatar_data))
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+
+    Ok(response)
+}
+
+/// Trim fields, turn empty strings into `None` and check lengths and grid.
+fn normalize_profile(req: UpdateProfileRequest) -> Result<UpdateProfileRequest, AppError> {
+    fn field(value: Option<String>, name: &str, max: usize) -> Result<Option<String>, AppError> {
+        let value = value
+            .map(|v| v.trim().to_string())
+            .filter(|v| !v.is_empty());
+        if value.as_ref().is_some_and(|v| v.chars().count() > max) {
+            return Err(AppError::Validation {
+                message: format!("{} must be at most {} characters", name, max),
+            });
+        }
+        Ok(value)
+    }
+
+    let grid = field(req.grid, "grid", 8)?.map(|g| g.to_uppercase());
+    if grid.as_deref().is_some_and(|g| !is_valid_grid(g)) {
+        return Err(AppError::Validation {
+            message: "grid must be a 4, 6 or 8 character Maidenhead locator".to_string(),
+        });
+    }
+
+    Ok(UpdateProfileRequest {
+        display_name: field(req.display_name, "displayName", MAX_DISPLAY_NAME_LENGTH)?,
+        qth: field(req.qth, "qth", MAX_QTH_LENGTH)?,
+        grid,
+        bio: field(req.bio, "bio", MAX_BIO_LENGTH)?,
+    })
+}
@@ -1,71 +1,165 @@
-use axum::extract::{Extension, State};
+use axum::extract::{Extension, Query, State};
 
 use crate::extractors::{Json, Path};
 use sqlx::PgPool;
 use uuid::Uuid;
 
 use crate::auth::AuthContext;
+use crate::certificate::earned_certificates;
+use crate::config::Config;
 use crate::db;
 use crate::error::AppError;
-use crate::models::{Progress, ProgressResponse, ReportProgressRequest, ReportProgressResponse};
-
+use crate::milestones::{self, Milestone};
+use crate::models::{
+    Challenge, ChallengeStatus, GoalCompletion, Progress, ProgressQuery, ProgressResponse,
+    ReportProgressRequest, ReportProgressResponse, ScoreBasis,
+};
+use crate::scoring::{
+    calculate_percentage, goals_on_basis, is_value_based, reached_tiers, score_basis,
+};
+
+use super::challenges::get_public_challenge;
 use super::DataResponse;
 
 pub async fn report_progress(
     State(pool): State<PgPool>,
+    Extension(app_config): Extension<Config>,
     Path(challenge_id): Path<Uuid>,
     Extension(auth): Extension<AuthContext>,
-    Json(req): Json<ReportProgressRequest>,
+    Json(mut req): Json<ReportProgressRequest>,
 ) -> Result<Json<DataResponse<ReportProgressResponse>>, AppError> {
-    let challenge = db::get_challenge(&pool, challenge_id)
-        .await?
-        .ok_or(AppError::ChallengeNotFound { challenge_id })?;
+    let challenge = get_public_challenge(&pool, challenge_id).await?;
 
-    let _participation = db::get_participation(&pool, challenge_id, &auth.callsign)
+    if challenge.effective_status() != ChallengeStatus::Published {
+        return Err(AppError::ChallengeEnded);
+    }
+
+    db::get_participation(&pool, challenge_id, &auth.callsign)
         .await?
+        .filter(|p| p.is_participating())
         .ok_or(AppError::NotParticipating)?;
 
-    let score = calculate_score(&challenge.configuration, &req);
-    let current_tier = determine_tier(&challenge.configuration, score);
-
-    let _progress = db::upsert_progress(
+    req.goal_evidence = std::mem::take(&mut req.goal_evidence)
+        .into_iter()
+        .map(|(goal_id, evidence)| {
+            if !req.completed_goals.contains(&goal_id) {
+                return Err(format!("goalEvidence.{}: not in completedGoals", goal_id));
+            }
+            evidence
+                .normalize()
+                .map(|evidence| (goal_id.clone(), evidence))
+                .map_err(|message| format!("goalEvidence.{}: {}", goal_id, message))
+        })
+        .collect::<Result<_, _>>()
+        .map_err(|message| AppError::Validation { message })?;
+
+    let merge = db::merge_progress(&pool, &challenge, &auth.callsign, &req).await?;
+    let config = &challenge.configuration;
+    let score = merge.progress.score;
+    let previous_score = merge.previous_score;
+
+    let new_badges = db::award_tier_badges(
         &pool,
         challenge_id,
         &auth.callsign,
-        &req,
-        score,
-        current_tier.as_deref(),
+        &reached_tiers(config, score),
     )
     .await?;
 
+    // Post the highest newly reached tier, not every tier skipped past
+    let previous_tiers = reached_tiers(config, previous_score);
+    if let Some(tier_id) = reached_tiers(config, score)
+        .into_iter()
+        .rfind(|t| !previous_tiers.contains(t))
+    {
+        let milestone = Milestone::tier_unlocked(&challenge, &tier_id);
+        milestones::emit(&pool, &auth.callsign, milestone).await;
+    }
+
+    if !new_badges.is_empty() {
+        for badge in db::list_badges(&pool, challenge_id).await? {
+            if new_badges.contains(&badge.id) {
+                let milestone = Milestone::badge_earned(&challenge, badge.id, &badge.name);
+                milestones::emit(&pool, &auth.callsign, milestone).await;
+            }
+        }
+    }
+
+    let earned = earned_certificates(
+        config,
+        &merge.progress.goals_with_status(),
+        merge.progress.current_value,
+        score,
+    );
+    let new_certificates =
+        db::issue_certificates(&pool, &challenge, &auth.callsign, &earned).await?;
+
     let rank = db::get_rank(&pool, challenge_id, &auth.callsign)
         .await?
         .unwrap_or(0);
 
-    let percentage = calculate_percentage(&challenge.configuration, &req);
-    let new_badges = vec![];
+    let completions = db::get_goal_completions(&pool, merge.progress.id).await?;
 
     Ok(Json(DataResponse {
         data: ReportProgressResponse {
             accepted: true,
-            server_progress: ProgressResponse {
-                completed_goals: req.completed_goals,
-                current_value: req.current_value,
-                percentage,
-                score,
+            server_progress: progress_response(
+                &challenge,
+                merge.progress,
+                completions,
                 rank,
-                current_tier,
-            },
+                false,
+            ),
             new_badges,
+            new_certificates: new_certificates
+                .into_iter()
+                .map(|c| c.into_response(&app_config.invite_base_url))
+                .collect(),
+            conflicts: merge.conflicts,
         },
     }))
 }
 
+/// Clear the caller's progress so they can start over. Reports only ever
+/// add goals and raise the current value, so this is the way back to zero.
+pub async fn reset_progress(
+    State(pool): State<PgPool>,
+    Path(challenge_id): Path<Uuid>,
+    Extension(auth): Extension<AuthContext>,
+) -> Result<Json<DataResponse<ProgressResponse>>, AppError> {
+    let challenge = get_public_challenge(&pool, challenge_id).await?;
+
+    if challenge.effective_status() != ChallengeStatus::Published {
+        return Err(AppError::ChallengeEnded);
+    }
+
+    db::get_participation(&pool, challenge_id, &auth.callsign)
+        .await?
+        .filter(|p| p.is_participating())
+        .ok_or(AppError::NotParticipating)?;
+
+    let progress = db::reset_progress(&pool, &challenge, &auth.callsign).await?;
+
+    let rank = db::get_rank(&pool, challenge_id, &auth.callsign)
+        .await?
+        .unwrap_or(0);
+
+    Ok(Json(DataResponse {
+        data: progress_response(&challenge, progress, Vec::new(), rank, false),
+    }))
+}
+
+/// Own progress; `?include=evidence` adds the qualifying contact per goal.
 pub async fn get_progress(
     State(pool): State<PgPool>,
     Path(challenge_id): Path<Uuid>,
     Extension(auth): Extension<AuthContext>,
+    Query(query): Query<ProgressQuery>,
 ) -> Result<Json<DataResponse<ProgressResponse>>, AppError> {
+    let include_evidence = query
+        .include_evidence()
+        .map_err(|message| AppError::Validation { message })?;
+
     let challenge = db::get_challenge(&pool, challenge_id)
         .await?
         .ok_or(AppError::ChallengeNotFound { challenge_id })?;
@@ -78,131 +172,68 @@ pub async fn get_progress(
         .await?
         .unwrap_or(0);
 
-    let completed_goals: Vec<String> = serde_json::from_value(progress.completed_goals.clone())
-        .unwrap_or_default();
-
-    let percentage = calculate_percentage_from_progress(&challenge.configuration, &progress);
+    let completions = db::get_goal_completions(&pool, progress.id).await?;
 
     Ok(Json(DataResponse {
-        data: ProgressResponse {
-            completed_goals,
-            current_value: progress.current_value,
-            percentage,
-            score: progress.score,
-            rank,
-            current_tier: progress.current_tier,
-        },
+        data: progress_response(&challenge, progress, completions, rank, include_evidence),
     }))
 }
 
-fn calculate_score(config: &serde_json::Value, req: &ReportProgressRequest) -> i32 {
-    let scoring = config.get("scoring");
-    let method = scoring
-        .and_then(|s| s.get("method"))
-        .and_then(|m| m.as_str())
-        .unwrap_or("count");
-
-    match method {
-        "percentage" => {
-            let total = get_total_goals(config);
-            if total > 0 {
-                (req.completed_goals.len() as f64 / total as f64 * 100.0) as i32
-            } else {
-                0
-            }
-        }
-        "count" => req.completed_goals.len() as i32,
-        "points" => req.current_value,
-        _ => req.completed_goals.len() as i32,
-    }
-}
-
-fn calculate_percentage(config: &serde_json::Value, req: &ReportProgressRequest) -> f64 {
-    let goals = config.get("goals");
-    let goal_type = goals
-        .and_then(|g| g.get("type"))
-        .and_then(|t| t.as_str())
-        .unwrap_or("collection");
-
-    match goal_type {
-        "collection" => {
-            let total = get_total_goals(config);
-            if total > 0 {
-                req.completed_goals.len() as f64 / total as f64 * 100.0
-            } else {
-                0.0
-            }
-        }
-        "cumulative" => {
-            let target = goals
-                .and_then(|g| g.get("targetValue"))
-                .and_then(|t| t.as_i64())
-                .unwrap_or(100) as f64;
-            if target > 0.0 {
-                req.current_value as f64 / target * 100.0
-            } else {
-                0.0
-            }
-        }
-        _ => 0.0,
+fn progress_response(
+    challenge: &Challenge,
+    progress: Progress,
+    completions: Vec<GoalCompletion>,
+    rank: i64,
+    include_evidence: bool,
+) -> ProgressResponse {
+    let config = &challenge.configuration;
+    let goals = progress.goals_with_status();
+    let percentage_on = |basis| {
+        let goals = goals_on_basis(config, &goals, basis);
+        calculate_percentage(config, &goals, progress.current_value)
+    };
+    let worked_percentage = percentage_on(ScoreBasis::Worked);
+    let confirmed_percentage = if is_value_based(config) {
+        0.0
+    } else {
+        percentage_on(ScoreBasis::Confirmed)
+    };
+    let score_basis = score_basis(config);
+    let percentage = match score_basis {
+        ScoreBasis::Worked => worked_percentage,
+        ScoreBasis::Confirmed => confirmed_percentage,
+    };
+
+    let goal_completed_at = completions
+        .iter()
+        .map(|c| (c.goal_id.clone(), c.completed_at))
+        .collect();
+    let goal_status = completions
+        .iter()
+        .map(|c| (c.goal_id.clone(), c.status))
+        .collect();
+    let goal_evidence = include_evidence.then(|| {
+        completions
+            .into_iter()
+            .filter_map(|c| Some((c.goal_id, c.evidence?)))
+            .collect()
+    });
+
+    ProgressResponse {
+        completed_goals: progress.completed_goals,
+        goal_completed_at,
+        goal_status,
+        goal_evidence,
+        current_value: progress.current_value,
+        percentage,
+        worked_percentage,
+        confirmed_percentage,
+        score_basis,
+        score: progress.score,
+        worked_score: progress.worked_score,
+        confirmed_score: progress.confirmed_score,
+        rank,
+        current_tier: progress.current_tier,
+        challenge_version: progress.challenge_version,
     }
 }
-
-fn calculate_percentage_from_progress(config: &serde_json::Value, progress: &Progress) -> f64 {
-    let goals = config.get("goals");
-    let goal_type = goals
-        .and_then(|g| g.get("type"))
-        .and_then(|t| t.as_str())
-        .unwrap_or("collection");
-
-    match goal_type {
-        "collection" => {
-            let total = get_total_goals(config);
-            let completed: Vec<String> = serde_json::from_value(progress.completed_goals.clone())
-                .unwrap_or_default();
-            if total > 0 {
-                completed.len() as f64 / total as f64 * 100.0
-            } else {
-                0.0
-            }
-        }
-        "cumulative" => {
-            let target = goals
-                .and_then(|g| g.get("targetValue"))
-                .and_then(|t| t.as_i64())
-                .unwrap_or(100) as f64;
-            if target > 0.0 {
-                progress.current_value as f64 / target * 100.0
-            } else {
-                0.0
-            }
-        }
-        _ => 0.0,
-    }
-}
-
-fn get_total_goals(config: &serde_json::Value) -> usize {
-    config
-        .get("goals")
-        .and_then(|g| g.get("items"))
-        .and_then(|i| i.as_array())
-        .map(|a| a.len())
-        .unwrap_or(0)
-}
-
-fn determine_tier(config: &serde_json::Value, score: i32) -> Option<String> {
-    let tiers = config.get("tiers")?.as_array()?;
-    let mut current_tier: Option<&serde_json::Value> = None;
-
-    for tier in tiers {
-        let threshold = tier.get("threshold")?.as_i64()? as i32;
-        if score >= threshold {
-            current_tier = Some(tier);
-        }
-    }
-
-    current_tier
-        .and_then(|t| t.get("id"))
-        .and_then(|id| id.as_str())
-        .map(String::from)
-}
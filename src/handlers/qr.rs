@@ -0,0 +1,78 @@
+use axum::{
+    body::Body,
+    extract::{Extension, Query, State},
+    http::{header, Response, StatusCode},
+};
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+
+use crate::config::Config;
+use crate::db;
+use crate::error::AppError;
+use crate::extractors::Path;
+use crate::models::FriendInviteStatus;
+use crate::qr::{self, QrQuery};
+
+/// GET /v1/friends/invites/:token/qr?format=svg|png&size=256&ecc=M
+/// QR code for an active friend invite link
+pub async fn friend_invite_qr(
+    State(pool): State<PgPool>,
+    Extension(config): Extension<Config>,
+    Path(token): Path<String>,
+    Query(query): Query<QrQuery>,
+) -> Result<Response<Body>, AppError> {
+    let invite = db::get_friend_invite(&pool, &token).await?.ok_or_else(|| {
+        AppError::FriendInviteNotFound {
+            token: token.clone(),
+        }
+    })?;
+
+    match invite.status(Utc::now()) {
+        FriendInviteStatus::Active => {}
+        FriendInviteStatus::Used => return Err(AppError::FriendInviteUsed { token }),
+        FriendInviteStatus::Expired | FriendInviteStatus::Revoked => {
+            return Err(AppError::FriendInviteNotFound { token })
+        }
+    }
+
+    let expires_at = invite.expires_at;
+    let url = invite.into_response(&config.invite_base_url).url;
+    qr_response(&url, &query, Some(expires_at))
+}
+
+/// GET /v1/invites/:token/qr?format=svg|png&size=256&ecc=M
+/// QR code for a challenge invite link that is still valid
+pub async fn challenge_invite_qr(
+    State(pool): State<PgPool>,
+    Extension(config): Extension<Config>,
+    Path(token): Path<String>,
+    Query(query): Query<QrQuery>,
+) -> Result<Response<Body>, AppError> {
+    let invite = db::get_invite(&pool, &token)
+        .await?
+        .filter(|invite| invite.is_valid(Utc::now()))
+        .ok_or(AppError::InviteNotFound { token })?;
+
+    let expires_at = invite.expires_at;
+    let url = invite.into_response(&config.invite_base_url).url;
+    qr_response(&url, &query, expires_at)
+}
+
+fn qr_response(
+    url: &str,
+    query: &QrQuery,
+    expires_at: Option<DateTime<Utc>>,
+) -> Result<Response<Body>, AppError> {
+    let (content_type, image) = qr::render(url, query)?;
+    let max_age = qr::cache_max_age(expires_at, Utc::now());
+
+    Response::builder()
+        .status(StatusCode::OK)
+        .header(header::CONTENT_TYPE, content_type)
+        .header(
+            header::CACHE_CONTROL,
+            format!("public, max-age={}", max_age),
+        )
+        .body(Body::from(image))
+        .map_err(|e| AppError::Internal(e.to_string()))
+}
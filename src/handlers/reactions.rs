@@ -0,0 +1,179 @@
+use axum::{
+    extract::{Extension, State},
+    http::StatusCode,
+};
+use uuid::Uuid;
+
+use crate::extractors::{Json, Path};
+use sqlx::PgPool;
+
+use crate::auth::AuthContext;
+use crate::db;
+use crate::error::AppError;
+use crate::models::activity::Activity;
+use crate::models::{
+    CommentResponse, CreateCommentRequest, ReactionKind, ReactionResponse, MAX_COMMENT_LENGTH,
+};
+
+use super::DataResponse;
+
+/// The activity if the caller may interact with it, otherwise 404 so
+/// private and non-friend activities aren't revealed.
+async fn interactable_activity(
+    pool: &PgPool,
+    activity_id: Uuid,
+    user_id: Uuid,
+) -> Result<Activity, AppError> {
+    db::get_activity_for_interaction(pool, activity_id, user_id)
+        .await?
+        .ok_or(AppError::ActivityNotFound { activity_id })
+}
+
+/// PUT /v1/activities/:id/reactions/:kind
+/// React to your own or a friend's activity. Reacting twice is a no-op.
+pub async fn add_reaction(
+    State(pool): State<PgPool>,
+    Path((activity_id, kind)): Path<(Uuid, ReactionKind)>,
+    Extension(auth): Extension<AuthContext>,
+) -> Result<StatusCode, AppError> {
+    let user = db::get_or_create_user(&pool, &auth.callsign).await?;
+    interactable_activity(&pool, activity_id, user.id).await?;
+
+    db::add_reaction(&pool, activity_id, user.id, kind).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// DELETE /v1/activities/:id/reactions/:kind
+/// Remove your reaction
+pub async fn remove_reaction(
+    State(pool): State<PgPool>,
+    Path((activity_id, kind)): Path<(Uuid, ReactionKind)>,
+    Extension(auth): Extension<AuthContext>,
+) -> Result<StatusCode, AppError> {
+    let user = db::get_or_create_user(&pool, &auth.callsign).await?;
+
+    if !db::remove_reaction(&pool, activity_id, user.id, kind).await? {
+        return Err(AppError::ActivityNotFound { activity_id });
+    }
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// GET /v1/activities/:id/reactions
+/// List who reacted to an activity
+pub async fn list_reactions(
+    State(pool): State<PgPool>,
+    Path(activity_id): Path<Uuid>,
+    Extension(auth): Extension<AuthContext>,
+) -> Result<Json<DataResponse<Vec<ReactionResponse>>>, AppError> {
+    let user = db::get_or_create_user(&pool, &auth.callsign).await?;
+    interactable_activity(&pool, activity_id, user.id).await?;
+
+    let reactions = db::list_reactions(&pool, activity_id, user.id).await?;
+
+    Ok(Json(DataResponse {
+        data: reactions.into_iter().map(Into::into).collect(),
+    }))
+}
+
+/// GET /v1/activities/:id/comments
+/// List comments on an activity, oldest first. Replies carry `parentId`.
+pub async fn list_comments(
+    State(pool): State<PgPool>,
+    Path(activity_id): Path<Uuid>,
+    Extension(auth): Extension<AuthContext>,
+) -> Result<Json<DataResponse<Vec<CommentResponse>>>, AppError> {
+    let user = db::get_or_create_user(&pool, &auth.callsign).await?;
+    interactable_activity(&pool, activity_id, user.id).await?;
+
+    let comments = db::list_comments(&pool, activity_id, user.id).await?;
+
+    Ok(Json(DataResponse {
+        data: comments.into_iter().map(Into::into).collect(),
+    }))
+}
+
+/// POST /v1/activities/:id/comments
+/// Comment on your own or a friend's activity, or reply to a comment
+pub async fn create_comment(
+    State(pool): State<PgPool>,
+    Path(activity_id): Path<Uuid>,
+    Extension(auth): Extension<AuthContext>,
+    Json(body): Json<CreateCommentRequest>,
+) -> Result<(StatusCode, Json<DataResponse<CommentResponse>>), AppError> {
+    let text = body.body.trim();
+    if text.is_empty() {
+        return Err(AppError::Validation {
+            message: "Comment body is required".to_string(),
+        });
+    }
+    if text.chars().count() > MAX_COMMENT_LENGTH {
+        return Err(AppError::Validation {
+            message: format!("Comment must be at most {} characters", MAX_COMMENT_LENGTH),
+        });
+    }
+
+    let user = db::get_or_create_user(&pool, &auth.callsign).await?;
+    interactable_activity(&pool, activity_id, user.id).await?;
+
+    if let Some(parent_id) = body.parent_id {
+        let parent = db::get_comment(&pool, parent_id).await?;
+        if !parent.is_some_and(|p| p.activity_id == activity_id && p.deleted_at.is_none()) {
+            return Err(AppError::CommentNotFound {
+                comment_id: parent_id,
+            });
+        }
+    }
+
+    let comment = db::insert_comment(&pool, activity_id, user.id, body.parent_id, text).await?;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(DataResponse {
+            data: comment.into(),
+        }),
+    ))
+}
+
+/// DELETE /v1/activities/:id/comments/:comment_id
+/// Delete a comment. Authors can delete their own comments and activity
+/// owners can delete any comment on their activity.
+pub async fn delete_comment(
+    State(pool): State<PgPool>,
+    Path((activity_id, comment_id)): Path<(Uuid, Uuid)>,
+    Extension(auth): Extension<AuthContext>,
+) -> Result<StatusCode, AppError> {
+    let user = db::get_or_create_user(&pool, &auth.callsign).await?;
+
+    let comment = db::get_comment(&pool, comment_id)
+        .await?
+        .filter(|c| c.activity_id == activity_id && c.deleted_at.is_none())
+        .ok_or(AppError::CommentNotFound { comment_id })?;
+
+    if comment.user_id != user.id {
+        let activity = interactable_activity(&pool, activity_id, user.id).await?;
+        if activity.user_id != user.id {
+            return Err(AppError::Forbidden);
+        }
+    }
+
+    if !db::delete_comment(&pool, comment_id, Some(user.id)).await? {
+        return Err(AppError::CommentNotFound { comment_id });
+    }
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// DELETE /v1/admin/comments/:id
+/// Remove any comment (moderation)
+pub async fn admin_delete_comment(
+    State(pool): State<PgPool>,
+    Path(comment_id): Path<Uuid>,
+) -> Result<StatusCode, AppError> {
+    if db::delete_comment(&pool, comment_id, None).await? {
+        Ok(StatusCode::NO_CONTENT)
+    } else {
+        Err(AppError::CommentNotFound { comment_id })
+    }
+}
@@ -0,0 +1,75 @@
+use axum::{extract::State, http::StatusCode};
+
+use crate::extractors::{Json, Path};
+use serde::Serialize;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::db;
+use crate::error::AppError;
+use crate::models::{ChallengeTemplateResponse, CreateChallengeTemplateRequest};
+
+use super::challenges::DataResponse;
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TemplateListResponse {
+    pub templates: Vec<ChallengeTemplateResponse>,
+}
+
+/// POST /v1/admin/templates
+/// Define a recurring challenge. The first instance is created right away
+/// if `firstPeriodStart` has passed, otherwise once it does.
+pub async fn create_template(
+    State(pool): State<PgPool>,
+    Json(req): Json<CreateChallengeTemplateRequest>,
+) -> Result<(StatusCode, Json<DataResponse<ChallengeTemplateResponse>>), AppError> {
+    if req.name_template.trim().is_empty() {
+        return Err(AppError::Validation {
+            message: "nameTemplate must not be empty".to_string(),
+        });
+    }
+
+    db::get_challenge(&pool, req.source_challenge_id)
+        .await?
+        .ok_or(AppError::ChallengeNotFound {
+            challenge_id: req.source_challenge_id,
+        })?;
+
+    let template = db::create_template(&pool, &req).await?;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(DataResponse {
+            data: template.into(),
+        }),
+    ))
+}
+
+/// GET /v1/admin/templates
+pub async fn list_templates(
+    State(pool): State<PgPool>,
+) -> Result<Json<DataResponse<TemplateListResponse>>, AppError> {
+    let templates = db::list_templates(&pool).await?;
+
+    Ok(Json(DataResponse {
+        data: TemplateListResponse {
+            templates: templates.into_iter().map(Into::into).collect(),
+        },
+    }))
+}
+
+/// DELETE /v1/admin/templates/:id
+/// Stop a recurrence. Challenges already created are kept.
+pub async fn delete_template(
+    State(pool): State<PgPool>,
+    Path(id): Path<Uuid>,
+) -> Result<StatusCode, AppError> {
+    let deleted = db::delete_template(&pool, id).await?;
+
+    if deleted {
+        Ok(StatusCode::NO_CONTENT)
+    } else {
+        Err(AppError::TemplateNotFound { template_id: id })
+    }
+}
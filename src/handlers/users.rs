@@ -18,13 +18,22 @@ pub struct SearchUsersQuery {
 /// Search for users by callsign (public, no auth required)
 pub async fn search_users(
     State(pool): State<PgPool>,
+    auth: Option<Extension<AuthContext>>,
     Query(query): Query<SearchUsersQuery>,
 ) -> Result<Json<DataResponse<Vec<UserSearchResponse>>>, AppError> {
     if query.q.len() < 2 {
         return Ok(Json(DataResponse { data: vec![] }));
     }
 
-    let users = db::search_users(&pool, &query.q, 20).await?;
+    // Authenticated callers don't see users they have blocked or who blocked them
+    let viewer_id = match auth {
+        Some(Extension(auth)) => db::get_user_by_callsign(&pool, &auth.callsign)
+            .await?
+            .map(|u| u.id),
+        None => None,
+    };
+
+    let users = db::search_users(&pool, &query.q, viewer_id, 20).await?;
 
     let results: Vec<UserSearchResponse> = users.into_iter().map(|u| u.into()).collect();
 
@@ -0,0 +1,291 @@
+//! `Idempotency-Key` support for mutating authenticated routes. The first
+//! request with a key stores its response; retries with the same key and
+//! body within the TTL get that response replayed instead of running again.
+//! Only routes behind `require_auth` use it; admin routes don't.
+
+use axum::{
+    body::{to_bytes, Body},
+    extract::{Request, State},
+    http::{header, HeaderValue, Method, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use sha2::{Digest, Sha256};
+use sqlx::PgPool;
+
+use crate::auth::AuthContext;
+use crate::db;
+use crate::error::AppError;
+
+pub const IDEMPOTENCY_KEY_HEADER: &str = "idempotency-key";
+
+/// Set on replayed responses.
+pub const IDEMPOTENT_REPLAYED_HEADER: &str = "idempotent-replayed";
+
+/// Longest key accepted.
+const MAX_KEY_LENGTH: usize = 255;
+
+/// Largest request or response body buffered for an idempotent request.
+const MAX_BODY_SIZE: usize = 2 * 1024 * 1024;
+
+/// How long an in-flight request holds its key. A retry after this runs the
+/// request again, in case the original never finished.
+const LEASE_SECONDS: i64 = 60;
+
+/// Whether `key` is 1-255 visible ASCII characters.
+fn is_valid_key(key: &str) -> bool {
+    !key.is_empty() && key.len() <= MAX_KEY_LENGTH && key.bytes().all(|b| b.is_ascii_graphic())
+}
+
+/// Fingerprint of a request, so a key reused for a different request is
+/// detected. Multipart bodies are left out: a retry encodes them with a new
+/// boundary, so only the method, path and media type are compared.
+fn request_hash(method: &Method, path: &str, content_type: Option<&str>, body: &[u8]) -> String {
+    let media_type = content_type
+        .and_then(|ct| ct.split(';').next())
+        .map(|mt| mt.trim().to_ascii_lowercase());
+    let multipart = media_type
+        .as_deref()
+        .is_some_and(|mt| mt.starts_with("multipart/"));
+
+    let mut hasher = Sha256::new();
+    hasher.update(method.as_str());
+    hasher.update(b"\n");
+    hasher.update(path);
+    hasher.update(b"\n");
+    if multipart {
+        hasher.update(media_type.unwrap_or_default());
+    } else {
+        hasher.update(body);
+    }
+    URL_SAFE_NO_PAD.encode(hasher.finalize())
+}
+
+/// A claimed key. Dropping it before `complete` releases the key, so a
+/// handler that errors, panics or is cancelled by a dropped connection
+/// doesn't leave it claimed.
+struct Claim {
+    pool: PgPool,
+    callsign: String,
+    key: String,
+    done: bool,
+}
+
+impl Claim {
+    /// Store the response so retries replay it.
+    async fn complete(
+        mut self,
+        status: StatusCode,
+        content_type: Option<&str>,
+        body: &[u8],
+    ) -> Result<(), AppError> {
+        db::complete_idempotency_key(
+            &self.pool,
+            &self.callsign,
+            &self.key,
+            status.as_u16(),
+            content_type,
+            body,
+        )
+        .await?;
+        self.done = true;
+        Ok(())
+    }
+
+    /// Release the key before responding, so an immediate retry can run.
+    async fn release(mut self) -> Result<(), AppError> {
+        self.done = true;
+        db::release_idempotency_key(&self.pool, &self.callsign, &self.key).await
+    }
+}
+
+impl Drop for Claim {
+    fn drop(&mut self) {
+        if self.done {
+            return;
+        }
+        let pool = self.pool.clone();
+        let callsign = std::mem::take(&mut self.callsign);
+        let key = std::mem::take(&mut self.key);
+        tokio::spawn(async move {
+            if let Err(e) = db::release_idempotency_key(&pool, &callsign, &key).await {
+                tracing::warn!(error = ?e, "Failed to release idempotency key");
+            }
+        });
+    }
+}
+
+/// Middleware honoring `Idempotency-Key` on POST, PUT, PATCH and DELETE.
+/// Must run after `require_auth`: keys are scoped to the caller's callsign.
+/// State is the pool and the key TTL in hours.
+pub async fn idempotency(
+    State((pool, ttl_hours)): State<(PgPool, i64)>,
+    req: Request,
+    next: Next,
+) -> Result<Response, AppError> {
+    let mutating = matches!(
+        *req.method(),
+        Method::POST | Method::PUT | Method::PATCH | Method::DELETE
+    );
+    let Some(key) = req
+        .headers()
+        .get(IDEMPOTENCY_KEY_HEADER)
+        .filter(|_| mutating)
+    else {
+        return Ok(next.run(req).await);
+    };
+
+    let key = key
+        .to_str()
+        .ok()
+        .filter(|k| is_valid_key(k))
+        .ok_or_else(|| AppError::Validation {
+            message: format!(
+                "Idempotency-Key must be 1-{} visible ASCII characters",
+                MAX_KEY_LENGTH
+            ),
+        })?
+        .to_string();
+
+    let callsign = req
+        .extensions()
+        .get::<AuthContext>()
+        .map(|auth| auth.callsign.to_uppercase())
+        .ok_or(AppError::InvalidToken)?;
+
+    let (parts, body) = req.into_parts();
+    let body = to_bytes(body, MAX_BODY_SIZE)
+        .await
+        .map_err(|_| AppError::Validation {
+            message: "Request body too large for an idempotent request".to_string(),
+        })?;
+
+    let path = parts
+        .uri
+        .path_and_query()
+        .map(|p| p.as_str())
+        .unwrap_or_else(|| parts.uri.path());
+    let content_type = parts
+        .headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok());
+    let hash = request_hash(&parts.method, path, content_type, &body);
+
+    if let Some(existing) =
+        db::claim_idempotency_key(&pool, &callsign, &key, &hash, ttl_hours, LEASE_SECONDS).await?
+    {
+        if existing.request_hash != hash {
+            return Err(AppError::IdempotencyKeyMismatch);
+        }
+        let Some(status) = existing.status_code else {
+            return Err(AppError::IdempotencyKeyInUse);
+        };
+        return Ok(replay(
+            status,
+            existing.content_type.as_deref(),
+            existing.response_body.unwrap_or_default(),
+        ));
+    }
+
+    let claim = Claim {
+        pool,
+        callsign,
+        key,
+        done: false,
+    };
+
+    let response = next.run(Request::from_parts(parts, Body::from(body))).await;
+
+    // Server errors aren't stored so the client can retry with the same key
+    if response.status().is_server_error() {
+        claim.release().await?;
+        return Ok(response);
+    }
+
+    let (parts, body) = response.into_parts();
+    let body = to_bytes(body, MAX_BODY_SIZE)
+        .await
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+
+    let content_type = parts
+        .headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok());
+    claim.complete(parts.status, content_type, &body).await?;
+
+    Ok(Response::from_parts(parts, Body::from(body)))
+}
+
+/// Rebuild a stored response.
+fn replay(status: i16, content_type: Option<&str>, body: Vec<u8>) -> Response {
+    let status = u16::try_from(status)
+        .ok()
+        .and_then(|s| StatusCode::from_u16(s).ok())
+        .unwrap_or(StatusCode::OK);
+
+    let mut response = (status, body).into_response();
+    let headers = response.headers_mut();
+    headers.remove(header::CONTENT_TYPE);
+    if let Some(value) = content_type.and_then(|ct| HeaderValue::from_str(ct).ok()) {
+        headers.insert(header::CONTENT_TYPE, value);
+    }
+    headers.insert(IDEMPOTENT_REPLAYED_HEADER, HeaderValue::from_static("true"));
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_valid_key() {
+        assert!(is_valid_key("3f2b9c1e-8d4a-4c6b-9f1e-2a7d5b8c0e4f"));
+        assert!(!is_valid_key(""));
+        assert!(!is_valid_key("has space"));
+        assert!(!is_valid_key(&"a".repeat(256)));
+    }
+
+    #[test]
+    fn test_request_hash() {
+        let json = Some("application/json");
+        let hash = request_hash(&Method::POST, "/v1/activities", json, b"{}");
+        assert_eq!(
+            hash,
+            request_hash(&Method::POST, "/v1/activities", json, b"{}")
+        );
+        assert_ne!(
+            hash,
+            request_hash(&Method::POST, "/v1/activities", json, b"{\"a\":1}")
+        );
+        assert_ne!(
+            hash,
+            request_hash(&Method::PUT, "/v1/activities", json, b"{}")
+        );
+        assert_ne!(hash, request_hash(&Method::POST, "/v1/feed", json, b"{}"));
+    }
+
+    #[test]
+    fn test_request_hash_multipart_retry() {
+        let upload = |boundary: &str| {
+            let content_type = format!("multipart/form-data; boundary={}", boundary);
+            let body = format!(
+                "--{b}\r\nContent-Disposition: form-data; name=\"avatar\"\r\n\r\npng\r\n--{b}--\r\n",
+                b = boundary
+            );
+            request_hash(
+                &Method::POST,
+                "/v1/profile/avatar",
+                Some(&content_type),
+                body.as_bytes(),
+            )
+        };
+
+        // A retry re-encodes the body with a new boundary
+        assert_eq!(upload("----a1"), upload("----b2"));
+        assert_ne!(
+            upload("----a1"),
+            request_hash(&Method::POST, "/v1/profile/avatar", None, b"png")
+        );
+    }
+}
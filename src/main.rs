@@ -1,14 +1,23 @@
 mod auth;
+mod award;
+mod certificate;
 mod config;
 mod db;
 mod error;
 mod extractors;
 mod handlers;
+mod idempotency;
+mod milestones;
 mod models;
+mod qr;
+mod scoring;
+mod tasks;
+mod templates;
 
 use std::net::SocketAddr;
 
 use axum::{
+    extract::DefaultBodyLimit,
     http::StatusCode,
     middleware,
     response::IntoResponse,
@@ -54,6 +63,8 @@ async fn main() {
 
     tracing::info!("Database connected and migrations complete");
 
+    tasks::spawn_maintenance(pool.clone(), config.clone());
+
     // Build router
     let app = create_router(pool.clone(), config.clone());
 
@@ -75,6 +86,14 @@ fn create_router(pool: sqlx::PgPool, config: Config) -> Router {
     let public_routes = Router::new()
         .route("/challenges", get(handlers::list_challenges))
         .route("/challenges/:id", get(handlers::get_challenge))
+        .route(
+            "/challenges/:id/versions",
+            get(handlers::list_challenge_versions),
+        )
+        .route(
+            "/challenges/:id/versions/:version",
+            get(handlers::get_challenge_version),
+        )
         .route("/challenges/:id/join", post(handlers::join_challenge))
         .route(
             "/challenges/:id/leaderboard",
@@ -83,7 +102,22 @@ fn create_router(pool: sqlx::PgPool, config: Config) -> Router {
         .route("/badges/:id/image", get(handlers::get_badge_image))
         .route("/health", get(handlers::health_check))
         .route("/users/search", get(handlers::search_users))
+        .route("/users/:id/profile", get(handlers::get_user_profile))
+        .route("/users/:id/avatar", get(handlers::get_user_avatar))
+        .route("/users/:id/activities", get(handlers::get_user_activities))
+        .route("/activities/types", get(handlers::list_activity_types))
+        .route(
+            "/friends/invites/:token/qr",
+            get(handlers::friend_invite_qr),
+        )
+        .route("/invites/:token/qr", get(handlers::challenge_invite_qr))
+        .route("/certificates/:code", get(handlers::verify_certificate))
+        .route(
+            "/certificates/:code/image",
+            get(handlers::get_certificate_image),
+        )
         .route("/register", post(handlers::register))
+        .layer(Extension(config.clone()))
         .layer(middleware::from_fn_with_state(
             pool.clone(),
             auth::optional_auth,
@@ -93,6 +127,18 @@ fn create_router(pool: sqlx::PgPool, config: Config) -> Router {
     let auth_routes = Router::new()
         .route("/challenges/:id/progress", post(handlers::report_progress))
         .route("/challenges/:id/progress", get(handlers::get_progress))
+        .route(
+            "/challenges/:id/progress/reset",
+            post(handlers::reset_progress),
+        )
+        .route(
+            "/challenges/:id/application",
+            get(handlers::get_award_application),
+        )
+        .route(
+            "/challenges/:id/certificates",
+            get(handlers::list_certificates),
+        )
         .route("/challenges/:id/leave", delete(handlers::leave_challenge))
         .route(
             "/challenges/:id/participants/:callsign",
@@ -103,19 +149,67 @@ fn create_router(pool: sqlx::PgPool, config: Config) -> Router {
             get(handlers::list_challenges_for_callsign),
         )
         .route("/friends/invite-link", get(handlers::get_invite_link))
+        .route(
+            "/friends/invites",
+            post(handlers::create_friend_invite).get(handlers::list_friend_invites),
+        )
+        .route(
+            "/friends/invites/:token",
+            delete(handlers::revoke_friend_invite),
+        )
         .route("/friends/requests", post(handlers::create_friend_request))
         .route("/friends/suggestions", post(handlers::get_friend_suggestions))
         .route("/friends", get(handlers::list_friends))
         .route("/friends/requests/pending", get(handlers::list_pending_requests))
         .route("/friends/requests/:id/accept", post(handlers::accept_friend_request))
         .route("/friends/requests/:id/decline", post(handlers::decline_friend_request))
+        .route("/friends/requests/:id", delete(handlers::cancel_friend_request))
         .route("/friends/:id", delete(handlers::remove_friend))
+        .route(
+            "/blocks",
+            post(handlers::block_user).get(handlers::list_blocks),
+        )
+        .route("/blocks/:user_id", delete(handlers::unblock_user))
+        .route(
+            "/mutes",
+            post(handlers::mute_user).get(handlers::list_mutes),
+        )
+        .route("/mutes/:user_id", delete(handlers::unmute_user))
         .route("/activities", post(handlers::report_activity))
+        .route(
+            "/activities/:id/reactions",
+            get(handlers::list_reactions),
+        )
+        .route(
+            "/activities/:id/reactions/:kind",
+            put(handlers::add_reaction).delete(handlers::remove_reaction),
+        )
+        .route(
+            "/activities/:id/comments",
+            get(handlers::list_comments).post(handlers::create_comment),
+        )
+        .route(
+            "/activities/:id/comments/:comment_id",
+            delete(handlers::delete_comment),
+        )
         .route("/feed", get(handlers::get_feed))
         .route("/clubs", get(handlers::get_clubs))
         .route("/clubs/:id", get(handlers::get_club_details))
         .route("/account", delete(handlers::delete_account))
+        .route("/profile", put(handlers::update_profile))
+        .route(
+            "/settings/privacy",
+            get(handlers::get_privacy_settings).put(handlers::update_privacy_settings),
+        )
+        .route(
+            "/profile/avatar",
+            post(handlers::upload_avatar).delete(handlers::delete_avatar),
+        )
         .layer(Extension(config.clone()))
+        .layer(middleware::from_fn_with_state(
+            (pool.clone(), config.idempotency_key_ttl_hours),
+            idempotency::idempotency,
+        ))
         .layer(middleware::from_fn_with_state(
             pool.clone(),
             auth::require_auth,
@@ -123,19 +217,74 @@ fn create_router(pool: sqlx::PgPool, config: Config) -> Router {
 
     // Admin routes
     let admin_routes = Router::new()
-        .route("/admin/challenges", post(handlers::create_challenge))
-        .route("/admin/challenges/:id", put(handlers::update_challenge))
+        .route(
+            "/admin/challenges",
+            post(handlers::create_challenge).get(handlers::admin_list_challenges),
+        )
+        .route(
+            "/admin/challenges/:id",
+            get(handlers::admin_get_challenge).put(handlers::update_challenge),
+        )
         .route("/admin/challenges/:id", delete(handlers::delete_challenge))
+        .route(
+            "/admin/challenges/:id/clone",
+            post(handlers::clone_challenge),
+        )
+        .route(
+            "/admin/challenges/:id/export",
+            get(handlers::export_challenge),
+        )
+        .route(
+            "/admin/challenges/import",
+            post(handlers::import_challenge)
+                .layer(DefaultBodyLimit::max(handlers::MAX_BUNDLE_SIZE)),
+        )
+        .route(
+            "/admin/challenges/:id/restore",
+            post(handlers::restore_challenge),
+        )
+        .route(
+            "/admin/challenges/:id/purge",
+            delete(handlers::purge_challenge),
+        )
+        .route(
+            "/admin/challenges/:id/status",
+            post(handlers::update_challenge_status),
+        )
+        .route(
+            "/admin/challenges/:id/rescore",
+            post(handlers::rescore_challenge),
+        )
         .route(
             "/admin/challenges/:id/badges",
             post(handlers::upload_badge).get(handlers::list_badges),
         )
         .route("/admin/badges/:id", delete(handlers::delete_badge))
+        .route(
+            "/admin/challenges/:id/certificate-template",
+            get(handlers::get_certificate_template)
+                .put(handlers::update_certificate_template)
+                .delete(handlers::delete_certificate_template),
+        )
+        .route(
+            "/admin/challenges/:id/certificate-template/preview",
+            get(handlers::preview_certificate_template),
+        )
         .route(
             "/admin/challenges/:id/invites",
             post(handlers::generate_invite).get(handlers::list_invites),
         )
         .route("/admin/invites/:token", delete(handlers::revoke_invite))
+        .route(
+            "/admin/comments/:id",
+            delete(handlers::admin_delete_comment),
+        )
+        .route(
+            "/admin/templates",
+            post(handlers::create_template).get(handlers::list_templates),
+        )
+        .route("/admin/templates/:id", delete(handlers::delete_template))
+        .layer(Extension(config.clone()))
         .layer(middleware::from_fn_with_state(
             config.admin_token,
             auth::require_admin,
@@ -147,16 +296,19 @@ fn create_router(pool: sqlx::PgPool, config: Config) -> Router {
         .merge(admin_routes)
         .fallback(api_not_found);
 
-    // Friend invite page (server-rendered HTML for links opened in browsers)
-    let invite_route = Router::new()
-        .route("/invite/:token", get(handlers::invite_page));
+    // Server-rendered HTML pages for links opened in browsers
+    let page_routes = Router::new()
+        .route("/invite/:token", get(handlers::invite_page))
+        .route("/c/:id", get(handlers::challenge_page))
+        .route("/join/:token", get(handlers::join_page))
+        .route("/certificates/:code", get(handlers::certificate_page));
 
     // Static file serving for SPA (fallback to index.html for client-side routing)
     let serve_dir = ServeDir::new("web/dist").fallback(ServeFile::new("web/dist/index.html"));
 
     Router::new()
         .nest("/v1", v1_routes)
-        .merge(invite_route)
+        .merge(page_routes)
         .fallback_service(serve_dir)
         .layer(TraceLayer::new_for_http())
         .layer(cors)
@@ -0,0 +1,195 @@
+//! Activities the server posts on a user's behalf when they reach a
+//! milestone, so friends see them even if the client never reports one.
+
+use serde_json::json;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::db;
+use crate::error::AppError;
+use crate::models::Challenge;
+
+/// Activity types the server posts itself, with the `details` fields that
+/// identify one occurrence. Client reports of these types are deduplicated
+/// against server-generated ones using the same fields.
+const MILESTONE_KEYS: &[(&str, &[&str])] = &[
+    ("challengeJoined", &["challengeId"]),
+    ("challengeTierUnlocked", &["challengeId", "tierId"]),
+    ("badgeEarned", &["badgeId"]),
+    ("friendAdded", &["friendUserId"]),
+];
+
+/// Deduplication key for a milestone activity, e.g.
+/// `challengeTierUnlocked:<challengeId>:<tierId>`. `None` for other types or
+/// when an identifying field is missing.
+pub fn dedupe_key(activity_type: &str, details: &serde_json::Value) -> Option<String> {
+    let (_, fields) = MILESTONE_KEYS.iter().find(|(t, _)| *t == activity_type)?;
+
+    let mut key = activity_type.to_string();
+    for field in *fields {
+        let value = details.get(*field)?.as_str()?;
+        key.push(':');
+        key.push_str(value);
+    }
+    Some(key)
+}
+
+/// An activity generated by the server on a user's behalf.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Milestone {
+    pub activity_type: &'static str,
+    pub details: serde_json::Value,
+}
+
+impl Milestone {
+    pub fn challenge_joined(challenge: &Challenge) -> Self {
+        Self {
+            activity_type: "challengeJoined",
+            details: json!({
+                "challengeId": challenge.id.to_string(),
+                "challengeName": challenge.name,
+            }),
+        }
+    }
+
+    pub fn tier_unlocked(challenge: &Challenge, tier_id: &str) -> Self {
+        let tier_name = challenge
+            .configuration
+            .get("tiers")
+            .and_then(|t| t.as_array())
+            .and_then(|tiers| {
+                tiers
+                    .iter()
+                    .find(|tier| tier.get("id").and_then(|id| id.as_str()) == Some(tier_id))
+            })
+            .and_then(|tier| tier.get("name"))
+            .and_then(|n| n.as_str())
+            .unwrap_or(tier_id);
+
+        Self {
+            activity_type: "challengeTierUnlocked",
+            details: json!({
+                "challengeId": challenge.id.to_string(),
+                "challengeName": challenge.name,
+                "tierId": tier_id,
+                "tierName": tier_name,
+            }),
+        }
+    }
+
+    pub fn badge_earned(challenge: &Challenge, badge_id: Uuid, badge_name: &str) -> Self {
+        Self {
+            activity_type: "badgeEarned",
+            details: json!({
+                "challengeId": challenge.id.to_string(),
+                "challengeName": challenge.name,
+                "badgeId": badge_id.to_string(),
+                "badgeName": badge_name,
+            }),
+        }
+    }
+
+    pub fn friend_added(friend_user_id: Uuid, friend_callsign: &str) -> Self {
+        Self {
+            activity_type: "friendAdded",
+            details: json!({
+                "friendUserId": friend_user_id.to_string(),
+                "friendCallsign": friend_callsign,
+            }),
+        }
+    }
+}
+
+/// Post a milestone for `callsign` unless they opted out or it was already
+/// posted. Failures are logged rather than failing the triggering request.
+pub async fn emit(pool: &PgPool, callsign: &str, milestone: Milestone) {
+    if let Err(e) = try_emit(pool, callsign, &milestone).await {
+        tracing::warn!(
+            error = ?e,
+            activity_type = milestone.activity_type,
+            "Failed to post milestone activity"
+        );
+    }
+}
+
+/// Post `badgeEarned` for badges of `challenge` awarded outside a progress
+/// report, given as `(callsign, badge_id)` pairs.
+pub async fn emit_badges_earned(pool: &PgPool, challenge: &Challenge, awarded: &[(String, Uuid)]) {
+    if awarded.is_empty() {
+        return;
+    }
+
+    let badges = match db::list_badges(pool, challenge.id).await {
+        Ok(badges) => badges,
+        Err(e) => {
+            tracing::warn!(error = ?e, "Failed to load badges for milestone activities");
+            return;
+        }
+    };
+
+    for (callsign, badge_id) in awarded {
+        if let Some(badge) = badges.iter().find(|b| b.id == *badge_id) {
+            let milestone = Milestone::badge_earned(challenge, badge.id, &badge.name);
+            emit(pool, callsign, milestone).await;
+        }
+    }
+}
+
+async fn try_emit(pool: &PgPool, callsign: &str, milestone: &Milestone) -> Result<(), AppError> {
+    let user = db::get_or_create_user(pool, callsign).await?;
+    let key = dedupe_key(milestone.activity_type, &milestone.details);
+
+    db::insert_milestone_activity(
+        pool,
+        user.id,
+        &user.callsign,
+        milestone.activity_type,
+        &milestone.details,
+        key.as_deref(),
+    )
+    .await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::normalize_activity;
+
+    #[test]
+    fn test_dedupe_key() {
+        let details = json!({ "challengeId": "c1", "tierId": "tier-100", "tierName": "DXCC" });
+        assert_eq!(
+            dedupe_key("challengeTierUnlocked", &details).as_deref(),
+            Some("challengeTierUnlocked:c1:tier-100")
+        );
+
+        // Missing identifying field
+        assert_eq!(
+            dedupe_key("challengeTierUnlocked", &json!({ "challengeId": "c1" })),
+            None
+        );
+
+        // Not a milestone type
+        assert_eq!(dedupe_key("newDXCCEntity", &details), None);
+    }
+
+    #[test]
+    fn test_milestones_match_registry() {
+        let friend = Milestone::friend_added(Uuid::new_v4(), "W1ABC");
+        let (activity_type, details) =
+            normalize_activity(friend.activity_type, &friend.details, 0).unwrap();
+        assert_eq!(activity_type, friend.activity_type);
+        assert_eq!(details, friend.details);
+    }
+
+    #[test]
+    fn test_milestones_have_dedupe_keys() {
+        let friend = Milestone::friend_added(Uuid::nil(), "W1ABC");
+        assert_eq!(
+            dedupe_key(friend.activity_type, &friend.details),
+            Some(format!("friendAdded:{}", Uuid::nil()))
+        );
+    }
+}
@@ -1,8 +1,14 @@
+use std::collections::BTreeMap;
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use sqlx::types::Json;
 use sqlx::FromRow;
 use uuid::Uuid;
 
+use super::{ActivityAudience, ReactionKind};
+
 /// Database row for an activity.
 #[derive(Debug, Clone, FromRow)]
 pub struct Activity {
@@ -12,6 +18,7 @@ pub struct Activity {
     pub activity_type: String,
     pub timestamp: DateTime<Utc>,
     pub details: serde_json::Value,
+    pub audience: String,
     pub created_at: DateTime<Utc>,
 }
 
@@ -22,6 +29,8 @@ pub struct ReportActivityRequest {
     pub activity_type: String,
     pub timestamp: DateTime<Utc>,
     pub details: serde_json::Value,
+    /// Who can see the activity; defaults to the user's privacy setting.
+    pub audience: Option<ActivityAudience>,
 }
 
 /// Response for a reported activity (matches iOS ReportedActivityDTO).
@@ -33,11 +42,13 @@ pub struct ActivityResponse {
     pub activity_type: String,
     pub timestamp: DateTime<Utc>,
     pub details: serde_json::Value,
+    pub audience: ActivityAudience,
 }
 
 impl From<Activity> for ActivityResponse {
     fn from(a: Activity) -> Self {
         Self {
+            audience: ActivityAudience::parse(&a.audience).unwrap_or(ActivityAudience::Friends),
             id: a.id,
             callsign: a.callsign,
             activity_type: a.activity_type,
@@ -47,16 +58,24 @@ impl From<Activity> for ActivityResponse {
     }
 }
 
-/// Feed item row from the feed query (activity + friend's display info).
+/// Feed item row from the feed query (activity + friend's display info,
+/// reaction and comment counts).
 #[derive(Debug, Clone, FromRow)]
 pub struct FeedItemRow {
     pub id: Uuid,
     pub callsign: String,
     pub user_id: Uuid,
+    pub display_name: Option<String>,
     pub activity_type: String,
     pub timestamp: DateTime<Utc>,
     pub details: serde_json::Value,
     pub created_at: DateTime<Utc>,
+    /// Reaction kind -> count.
+    pub reaction_counts: Json<BTreeMap<String, i64>>,
+    /// Comments that haven't been deleted.
+    pub comment_count: i64,
+    /// Kinds the viewer has reacted with.
+    pub my_reactions: Vec<String>,
 }
 
 /// Response for a feed item (matches iOS FeedItemDTO).
@@ -70,18 +89,120 @@ pub struct FeedItemResponse {
     pub activity_type: String,
     pub timestamp: DateTime<Utc>,
     pub details: serde_json::Value,
+    pub reaction_counts: BTreeMap<String, i64>,
+    pub comment_count: i64,
+    pub my_reactions: Vec<ReactionKind>,
 }
 
 impl From<FeedItemRow> for FeedItemResponse {
     fn from(row: FeedItemRow) -> Self {
         Self {
+            my_reactions: row
+                .my_reactions
+                .iter()
+                .filter_map(|k| ReactionKind::parse(k))
+                .collect(),
             id: row.id,
             callsign: row.callsign,
             user_id: row.user_id,
-            display_name: None,
+            display_name: row.display_name,
             activity_type: row.activity_type,
             timestamp: row.timestamp,
             details: row.details,
+            reaction_counts: row.reaction_counts.0,
+            comment_count: row.comment_count,
         }
     }
 }
+
+/// Whose activities GET /v1/feed returns.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FeedScope {
+    /// Friends' non-private activities.
+    #[default]
+    Friends,
+    /// The caller's own activities, including private ones.
+    Mine,
+    /// Both of the above.
+    All,
+}
+
+impl FeedScope {
+    pub fn includes_friends(self) -> bool {
+        matches!(self, Self::Friends | Self::All)
+    }
+
+    pub fn includes_own(self) -> bool {
+        matches!(self, Self::Mine | Self::All)
+    }
+}
+
+/// Position in a feed ordered by `(created_at, id)` descending. Sent to
+/// clients as an opaque string so items sharing a timestamp aren't skipped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FeedCursor {
+    pub created_at: DateTime<Utc>,
+    pub id: Uuid,
+}
+
+impl FeedCursor {
+    pub fn encode(&self) -> String {
+        URL_SAFE_NO_PAD.encode(format!("{}|{}", self.created_at.to_rfc3339(), self.id))
+    }
+
+    /// Decode an opaque cursor. A bare RFC 3339 timestamp from older clients
+    /// is accepted and means "strictly before this time".
+    pub fn decode(s: &str) -> Option<Self> {
+        if let Ok(ts) = DateTime::parse_from_rfc3339(s) {
+            return Some(Self {
+                created_at: ts.with_timezone(&Utc),
+                id: Uuid::nil(),
+            });
+        }
+
+        let decoded = String::from_utf8(URL_SAFE_NO_PAD.decode(s).ok()?).ok()?;
+        let (ts, id) = decoded.split_once('|')?;
+        Some(Self {
+            created_at: DateTime::parse_from_rfc3339(ts).ok()?.with_timezone(&Utc),
+            id: id.parse().ok()?,
+        })
+    }
+}
+
+impl From<&FeedItemRow> for FeedCursor {
+    fn from(row: &FeedItemRow) -> Self {
+        Self {
+            created_at: row.created_at,
+            id: row.id,
+        }
+    }
+}
+
+/// Type filter and page position shared by the feed and user timelines.
+#[derive(Debug, Clone, Default)]
+pub struct FeedFilter {
+    /// Only these activity types, if set.
+    pub activity_types: Option<Vec<String>>,
+    pub before: Option<FeedCursor>,
+    pub limit: i64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_feed_cursor_round_trip() {
+        let cursor = FeedCursor {
+            created_at: Utc::now(),
+            id: Uuid::new_v4(),
+        };
+        assert_eq!(FeedCursor::decode(&cursor.encode()), Some(cursor));
+
+        let legacy = FeedCursor::decode("2025-01-15T12:00:00Z").unwrap();
+        assert_eq!(legacy.id, Uuid::nil());
+
+        assert_eq!(FeedCursor::decode("not a cursor"), None);
+    }
+}
@@ -0,0 +1,434 @@
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Longest unregistered activity type accepted, in characters.
+const MAX_CUSTOM_TYPE_LENGTH: usize = 64;
+
+/// Typed `details` for a registered activity type. Deserializing validates
+/// the shape; `check` adds any rules serde can't express.
+trait ActivityDetails: DeserializeOwned + Serialize {
+    fn check(&self) -> Result<(), String> {
+        Ok(())
+    }
+}
+
+/// Details for `newDxccEntity`.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NewDxccEntityDetails {
+    pub entity_code: u16,
+    pub entity_name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub band: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mode: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub worked_callsign: Option<String>,
+}
+
+impl ActivityDetails for NewDxccEntityDetails {}
+
+/// Details for `stateWorked`.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StateWorkedDetails {
+    /// Two-letter state abbreviation, e.g. "CT".
+    pub state: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub band: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mode: Option<String>,
+}
+
+impl ActivityDetails for StateWorkedDetails {
+    fn check(&self) -> Result<(), String> {
+        if self.state.len() != 2 || !self.state.chars().all(|c| c.is_ascii_uppercase()) {
+            return Err("state must be a two-letter uppercase abbreviation".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// Details for `potaActivation`.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PotaActivationDetails {
+    /// Park reference, e.g. "US-1234".
+    pub park_reference: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub park_name: Option<String>,
+    pub qso_count: u32,
+}
+
+impl ActivityDetails for PotaActivationDetails {
+    fn check(&self) -> Result<(), String> {
+        let valid = self
+            .park_reference
+            .split_once('-')
+            .is_some_and(|(prefix, number)| {
+                !prefix.is_empty()
+                    && prefix.chars().all(|c| c.is_ascii_alphanumeric())
+                    && !number.is_empty()
+                    && number.chars().all(|c| c.is_ascii_digit())
+            });
+        if !valid {
+            return Err("parkReference must look like \"US-1234\"".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// Details for `streak`.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StreakDetails {
+    /// What the streak counts, e.g. "dailyQso" or "potaActivation".
+    pub streak_type: String,
+    pub days: u32,
+}
+
+impl ActivityDetails for StreakDetails {
+    fn check(&self) -> Result<(), String> {
+        if self.days == 0 {
+            return Err("days must be at least 1".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// Details for `milestone`.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MilestoneDetails {
+    /// What was counted, e.g. "qsos" or "countries".
+    pub metric: String,
+    pub value: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+impl ActivityDetails for MilestoneDetails {}
+
+/// Details for `challengeJoined`.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChallengeJoinedDetails {
+    pub challenge_id: Uuid,
+    pub challenge_name: String,
+}
+
+impl ActivityDetails for ChallengeJoinedDetails {}
+
+/// Details for `challengeTierUnlocked`.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChallengeTierUnlockedDetails {
+    pub challenge_id: Uuid,
+    pub challenge_name: String,
+    pub tier_id: String,
+    pub tier_name: String,
+}
+
+impl ActivityDetails for ChallengeTierUnlockedDetails {}
+
+/// Details for `badgeEarned`.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BadgeEarnedDetails {
+    pub challenge_id: Uuid,
+    pub challenge_name: String,
+    pub badge_id: Uuid,
+    pub badge_name: String,
+}
+
+impl ActivityDetails for BadgeEarnedDetails {}
+
+/// Details for `friendAdded`.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FriendAddedDetails {
+    pub friend_user_id: Uuid,
+    pub friend_callsign: String,
+}
+
+impl ActivityDetails for FriendAddedDetails {}
+
+/// One field of an activity type's details, for GET /v1/activities/types.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ActivityFieldInfo {
+    pub name: &'static str,
+    /// "string", "integer", "uuid" or "object".
+    #[serde(rename = "type")]
+    pub field_type: &'static str,
+    pub required: bool,
+}
+
+/// A registered activity type.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ActivityTypeInfo {
+    #[serde(rename = "type")]
+    pub name: &'static str,
+    pub description: &'static str,
+    /// Posted by the server itself; clients may also report it.
+    pub server_generated: bool,
+    pub fields: &'static [ActivityFieldInfo],
+    #[serde(skip)]
+    normalize: fn(&serde_json::Value) -> Result<serde_json::Value, String>,
+}
+
+const fn field(name: &'static str, field_type: &'static str, required: bool) -> ActivityFieldInfo {
+    ActivityFieldInfo {
+        name,
+        field_type,
+        required,
+    }
+}
+
+/// Every known activity type. Keep `fields` in sync with the details struct.
+pub static ACTIVITY_TYPES: &[ActivityTypeInfo] = &[
+    ActivityTypeInfo {
+        name: "newDxccEntity",
+        description: "Worked a new DXCC entity",
+        server_generated: false,
+        fields: &[
+            field("entityCode", "integer", true),
+            field("entityName", "string", true),
+            field("band", "string", false),
+            field("mode", "string", false),
+            field("workedCallsign", "string", false),
+        ],
+        normalize: normalize::<NewDxccEntityDetails>,
+    },
+    ActivityTypeInfo {
+        name: "stateWorked",
+        description: "Worked a new US state",
+        server_generated: false,
+        fields: &[
+            field("state", "string", true),
+            field("band", "string", false),
+            field("mode", "string", false),
+        ],
+        normalize: normalize::<StateWorkedDetails>,
+    },
+    ActivityTypeInfo {
+        name: "potaActivation",
+        description: "Activated a Parks on the Air park",
+        server_generated: false,
+        fields: &[
+            field("parkReference", "string", true),
+            field("parkName", "string", false),
+            field("qsoCount", "integer", true),
+        ],
+        normalize: normalize::<PotaActivationDetails>,
+    },
+    ActivityTypeInfo {
+        name: "streak",
+        description: "Extended an operating streak",
+        server_generated: false,
+        fields: &[
+            field("streakType", "string", true),
+            field("days", "integer", true),
+        ],
+        normalize: normalize::<StreakDetails>,
+    },
+    ActivityTypeInfo {
+        name: "milestone",
+        description: "Reached a round number, e.g. 1,000 QSOs",
+        server_generated: false,
+        fields: &[
+            field("metric", "string", true),
+            field("value", "integer", true),
+            field("description", "string", false),
+        ],
+        normalize: normalize::<MilestoneDetails>,
+    },
+    ActivityTypeInfo {
+        name: "challengeJoined",
+        description: "Joined a challenge",
+        server_generated: true,
+        fields: &[
+            field("challengeId", "uuid", true),
+            field("challengeName", "string", true),
+        ],
+        normalize: normalize::<ChallengeJoinedDetails>,
+    },
+    ActivityTypeInfo {
+        name: "challengeTierUnlocked",
+        description: "Reached a new tier in a challenge",
+        server_generated: true,
+        fields: &[
+            field("challengeId", "uuid", true),
+            field("challengeName", "string", true),
+            field("tierId", "string", true),
+            field("tierName", "string", true),
+        ],
+        normalize: normalize::<ChallengeTierUnlockedDetails>,
+    },
+    ActivityTypeInfo {
+        name: "badgeEarned",
+        description: "Earned a challenge badge",
+        server_generated: true,
+        fields: &[
+            field("challengeId", "uuid", true),
+            field("challengeName", "string", true),
+            field("badgeId", "uuid", true),
+            field("badgeName", "string", true),
+        ],
+        normalize: normalize::<BadgeEarnedDetails>,
+    },
+    ActivityTypeInfo {
+        name: "friendAdded",
+        description: "Became friends with another user",
+        server_generated: true,
+        fields: &[
+            field("friendUserId", "uuid", true),
+            field("friendCallsign", "string", true),
+        ],
+        normalize: normalize::<FriendAddedDetails>,
+    },
+];
+
+/// Parse details into `T`, check them and re-serialize, dropping any
+/// fields the schema doesn't know.
+fn normalize<T: ActivityDetails>(details: &serde_json::Value) -> Result<serde_json::Value, String> {
+    let parsed: T = serde_json::from_value(details.clone()).map_err(|e| e.to_string())?;
+    parsed.check()?;
+    serde_json::to_value(parsed).map_err(|e| e.to_string())
+}
+
+pub fn find_activity_type(name: &str) -> Option<&'static ActivityTypeInfo> {
+    ACTIVITY_TYPES.iter().find(|t| t.name == name)
+}
+
+/// Validate a reported activity against the registry, returning the type
+/// and details to store. Unregistered types keep their name and details,
+/// provided the name is 1-64 ASCII letters and digits and the details are an
+/// object of at most `custom_max_bytes`. They are rejected when it is 0.
+pub fn normalize_activity(
+    activity_type: &str,
+    details: &serde_json::Value,
+    custom_max_bytes: usize,
+) -> Result<(String, serde_json::Value), String> {
+    if let Some(info) = find_activity_type(activity_type) {
+        let normalized = (info.normalize)(details)
+            .map_err(|e| format!("Invalid details for {}: {}", info.name, e))?;
+        return Ok((info.name.to_string(), normalized));
+    }
+
+    if custom_max_bytes == 0 {
+        return Err(format!("Unknown activity type: {}", activity_type));
+    }
+    if activity_type.is_empty()
+        || activity_type.len() > MAX_CUSTOM_TYPE_LENGTH
+        || !activity_type.chars().all(|c| c.is_ascii_alphanumeric())
+    {
+        return Err(format!(
+            "Activity type must be 1-{} letters and digits",
+            MAX_CUSTOM_TYPE_LENGTH
+        ));
+    }
+    if !details.is_object() {
+        return Err(format!("Details for {} must be an object", activity_type));
+    }
+    if details.to_string().len() > custom_max_bytes {
+        return Err(format!(
+            "Details for unregistered activity types must be at most {} bytes",
+            custom_max_bytes
+        ));
+    }
+
+    Ok((activity_type.to_string(), details.clone()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    /// Details with a sample value for every required field.
+    fn sample_details(info: &ActivityTypeInfo) -> serde_json::Value {
+        let mut details = serde_json::Map::new();
+        for f in info.fields.iter().filter(|f| f.required) {
+            let value = match (info.name, f.name) {
+                ("stateWorked", "state") => json!("CT"),
+                ("potaActivation", "parkReference") => json!("US-1234"),
+                (_, _) => match f.field_type {
+                    "integer" => json!(1),
+                    "uuid" => json!(Uuid::nil()),
+                    "object" => json!({}),
+                    _ => json!("x"),
+                },
+            };
+            details.insert(f.name.to_string(), value);
+        }
+        serde_json::Value::Object(details)
+    }
+
+    #[test]
+    fn test_registry_fields_match_schemas() {
+        for info in ACTIVITY_TYPES {
+            let details = sample_details(info);
+            let normalized =
+                (info.normalize)(&details).unwrap_or_else(|e| panic!("{}: {}", info.name, e));
+            assert_eq!(normalized, details, "{}", info.name);
+
+            // Every required field is actually required
+            for f in info.fields.iter().filter(|f| f.required) {
+                let mut missing = details.clone();
+                missing.as_object_mut().unwrap().remove(f.name);
+                assert!(
+                    (info.normalize)(&missing).is_err(),
+                    "{}.{}",
+                    info.name,
+                    f.name
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_normalize_activity() {
+        let (kind, details) = normalize_activity(
+            "stateWorked",
+            &json!({ "state": "CT", "band": "20m", "extra": true }),
+            4096,
+        )
+        .unwrap();
+        assert_eq!(kind, "stateWorked");
+        assert_eq!(details, json!({ "state": "CT", "band": "20m" }));
+
+        assert!(
+            normalize_activity("stateWorked", &json!({ "state": "Connecticut" }), 4096).is_err()
+        );
+        assert!(normalize_activity(
+            "streak",
+            &json!({ "streakType": "dailyQso", "days": 0 }),
+            4096
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_unknown_activity_types() {
+        let (kind, details) =
+            normalize_activity("sotaSummit", &json!({ "summit": "W1/HA-001" }), 4096).unwrap();
+        assert_eq!(kind, "sotaSummit");
+        assert_eq!(details, json!({ "summit": "W1/HA-001" }));
+
+        // Too large
+        assert!(normalize_activity("sotaSummit", &json!({ "summit": "W1/HA-001" }), 10).is_err());
+
+        // Not an object
+        assert!(normalize_activity("sotaSummit", &json!("W1/HA-001"), 4096).is_err());
+
+        // Invalid type names
+        assert!(normalize_activity("", &json!({}), 4096).is_err());
+        assert!(normalize_activity("sota summit", &json!({}), 4096).is_err());
+        assert!(normalize_activity(&"a".repeat(65), &json!({}), 4096).is_err());
+
+        // Unregistered types disabled
+        assert!(normalize_activity("sotaSummit", &json!({}), 0).is_err());
+    }
+}
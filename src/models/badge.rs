@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
@@ -54,3 +56,59 @@ pub struct CreateBadgeFields {
     pub name: String,
     pub tier_id: Option<String>,
 }
+
+/// Rewrite `tiers[].badgeId` in a challenge configuration using `badge_ids`.
+/// References to badges not in the map are dropped, since they would point
+/// at badges belonging to another challenge or server.
+pub fn remap_badge_ids(configuration: &mut serde_json::Value, badge_ids: &HashMap<Uuid, Uuid>) {
+    let Some(tiers) = configuration
+        .get_mut("tiers")
+        .and_then(|t| t.as_array_mut())
+    else {
+        return;
+    };
+
+    for tier in tiers.iter_mut().filter_map(|t| t.as_object_mut()) {
+        let mapped = tier
+            .get("badgeId")
+            .and_then(|v| v.as_str())
+            .and_then(|s| Uuid::parse_str(s).ok())
+            .and_then(|id| badge_ids.get(&id));
+
+        match mapped {
+            Some(new_id) => {
+                tier.insert("badgeId".to_string(), new_id.to_string().into());
+            }
+            None => {
+                tier.remove("badgeId");
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_remap_badge_ids() {
+        let old = Uuid::new_v4();
+        let new = Uuid::new_v4();
+        let stray = Uuid::new_v4();
+        let mut configuration = json!({
+            "tiers": [
+                { "id": "t1", "threshold": 10, "badgeId": old.to_string() },
+                { "id": "t2", "threshold": 20, "badgeId": stray.to_string() },
+                { "id": "t3", "threshold": 30 }
+            ]
+        });
+
+        remap_badge_ids(&mut configuration, &HashMap::from([(old, new)]));
+
+        let tiers = configuration["tiers"].as_array().unwrap();
+        assert_eq!(tiers[0]["badgeId"], json!(new.to_string()));
+        assert!(tiers[1].get("badgeId").is_none());
+        assert!(tiers[2].get("badgeId").is_none());
+    }
+}
@@ -0,0 +1,85 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// A user on the other side of a block or mute, with when it was created.
+#[derive(Debug, Clone, FromRow)]
+pub struct RelatedUser {
+    pub user_id: Uuid,
+    pub callsign: String,
+    pub display_name: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockedUserResponse {
+    pub user_id: Uuid,
+    pub callsign: String,
+    pub display_name: Option<String>,
+    pub blocked_at: DateTime<Utc>,
+}
+
+impl From<RelatedUser> for BlockedUserResponse {
+    fn from(u: RelatedUser) -> Self {
+        Self {
+            user_id: u.user_id,
+            callsign: u.callsign,
+            display_name: u.display_name,
+            blocked_at: u.created_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MutedUserResponse {
+    pub user_id: Uuid,
+    pub callsign: String,
+    pub display_name: Option<String>,
+    pub muted_at: DateTime<Utc>,
+}
+
+impl From<RelatedUser> for MutedUserResponse {
+    fn from(u: RelatedUser) -> Self {
+        Self {
+            user_id: u.user_id,
+            callsign: u.callsign,
+            display_name: u.display_name,
+            muted_at: u.created_at,
+        }
+    }
+}
+
+/// Request body for POST /v1/blocks and POST /v1/mutes.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UserTargetRequest {
+    pub user_id: Uuid,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_related_user_responses() {
+        let user = RelatedUser {
+            user_id: Uuid::nil(),
+            callsign: "W1ABC".to_string(),
+            display_name: None,
+            created_at: Utc::now(),
+        };
+
+        let blocked = serde_json::to_value(BlockedUserResponse::from(user.clone())).unwrap();
+        assert_eq!(blocked["callsign"], json!("W1ABC"));
+        assert_eq!(blocked["blockedAt"], json!(user.created_at));
+        assert!(blocked.get("mutedAt").is_none());
+
+        let muted = serde_json::to_value(MutedUserResponse::from(user.clone())).unwrap();
+        assert_eq!(muted["userId"], json!(Uuid::nil()));
+        assert_eq!(muted["mutedAt"], json!(user.created_at));
+    }
+}
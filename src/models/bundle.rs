@@ -0,0 +1,67 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::{BadgeResponse, ChallengeResponse};
+
+/// Current challenge bundle format. Bump when the bundle layout changes in a
+/// way older servers can't read.
+pub const CHALLENGE_BUNDLE_FORMAT_VERSION: u32 = 1;
+
+/// Portable challenge definition for sharing between servers.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChallengeBundle {
+    pub format_version: u32,
+    pub exported_at: DateTime<Utc>,
+    pub challenge: BundleChallenge,
+    #[serde(default)]
+    pub badges: Vec<BundleBadge>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BundleChallenge {
+    pub name: String,
+    pub description: String,
+    pub author: Option<String>,
+    pub category: String,
+    #[serde(rename = "type")]
+    pub challenge_type: String,
+    pub configuration: serde_json::Value,
+    pub invite_config: Option<serde_json::Value>,
+    pub hamalert_config: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BundleBadge {
+    /// Badge ID on the exporting server; tiers refer to it via `badgeId`.
+    pub id: Uuid,
+    pub name: String,
+    pub tier_id: Option<String>,
+    pub content_type: String,
+    /// Image bytes, standard base64.
+    pub image_data: String,
+}
+
+/// A bundle badge after its image has been decoded and validated.
+#[derive(Debug)]
+pub struct DecodedBadge {
+    pub source_id: Uuid,
+    pub name: String,
+    pub tier_id: Option<String>,
+    pub content_type: String,
+    pub image_data: Vec<u8>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportBundleResponse {
+    pub challenge: ChallengeResponse,
+    pub badges: Vec<BadgeResponse>,
+    /// Source badge ID to newly assigned badge ID.
+    pub badge_ids: HashMap<Uuid, Uuid>,
+}
@@ -0,0 +1,126 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// What a certificate recognizes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum CertificateKind {
+    /// Reached the last tier in the challenge's `tiers`.
+    TopTier,
+    /// Every collection goal completed on the challenge's score basis.
+    Completion,
+}
+
+impl CertificateKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::TopTier => "top_tier",
+            Self::Completion => "completion",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "top_tier" => Some(Self::TopTier),
+            "completion" => Some(Self::Completion),
+            _ => None,
+        }
+    }
+}
+
+/// Database row for certificates. Challenge and tier names are copied at
+/// issue time so a certificate reads the same after the challenge changes.
+#[derive(Debug, Clone, FromRow)]
+pub struct Certificate {
+    pub challenge_id: Uuid,
+    pub callsign: String,
+    pub kind: String,
+    pub challenge_name: String,
+    pub tier_id: Option<String>,
+    pub tier_name: Option<String>,
+    pub verification_code: String,
+    pub achieved_at: DateTime<Utc>,
+}
+
+impl Certificate {
+    pub fn kind(&self) -> CertificateKind {
+        CertificateKind::parse(&self.kind).unwrap_or(CertificateKind::Completion)
+    }
+
+    /// What was achieved, e.g. "DXCC 300" or "Completed".
+    pub fn achievement(&self) -> String {
+        match self.kind() {
+            CertificateKind::TopTier => self
+                .tier_name
+                .clone()
+                .or_else(|| self.tier_id.clone())
+                .unwrap_or_default(),
+            CertificateKind::Completion => "Completed".to_string(),
+        }
+    }
+
+    pub fn into_response(self, verify_base_url: &str) -> CertificateResponse {
+        CertificateResponse {
+            kind: self.kind(),
+            achievement: self.achievement(),
+            image_url: format!("/v1/certificates/{}/image", self.verification_code),
+            verify_url: format!(
+                "{}/certificates/{}",
+                verify_base_url, self.verification_code
+            ),
+            challenge_id: self.challenge_id,
+            callsign: self.callsign,
+            challenge_name: self.challenge_name,
+            tier_id: self.tier_id,
+            tier_name: self.tier_name,
+            verification_code: self.verification_code,
+            achieved_at: self.achieved_at,
+        }
+    }
+}
+
+/// A certificate as returned to its holder and by the public verification
+/// endpoint.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CertificateResponse {
+    pub verification_code: String,
+    pub kind: CertificateKind,
+    pub callsign: String,
+    pub challenge_id: Uuid,
+    pub challenge_name: String,
+    pub tier_id: Option<String>,
+    pub tier_name: Option<String>,
+    pub achievement: String,
+    pub achieved_at: DateTime<Utc>,
+    /// SVG certificate.
+    pub image_url: String,
+    /// Human-readable verification page.
+    pub verify_url: String,
+}
+
+/// Database row for certificate_templates.
+#[derive(Debug, Clone, FromRow)]
+pub struct CertificateTemplate {
+    pub challenge_id: Uuid,
+    pub svg: String,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Request body for PUT /v1/admin/challenges/:id/certificate-template.
+#[derive(Debug, Deserialize)]
+pub struct CertificateTemplateRequest {
+    pub svg: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CertificateTemplateResponse {
+    pub challenge_id: Uuid,
+    pub svg: String,
+    /// False when the challenge uses the built-in design.
+    pub custom: bool,
+    pub updated_at: Option<DateTime<Utc>>,
+}
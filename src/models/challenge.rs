@@ -15,9 +15,109 @@ pub struct Challenge {
     pub configuration: serde_json::Value,
     pub invite_config: Option<serde_json::Value>,
     pub hamalert_config: Option<serde_json::Value>,
-    pub is_active: bool,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    pub status: String,
+    pub publish_at: Option<DateTime<Utc>>,
+    pub deleted_at: Option<DateTime<Utc>>,
+}
+
+/// Lifecycle state of a challenge.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChallengeStatus {
+    Draft,
+    Scheduled,
+    Published,
+    Ended,
+    Archived,
+}
+
+impl ChallengeStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Draft => "draft",
+            Self::Scheduled => "scheduled",
+            Self::Published => "published",
+            Self::Ended => "ended",
+            Self::Archived => "archived",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "draft" => Some(Self::Draft),
+            "scheduled" => Some(Self::Scheduled),
+            "published" => Some(Self::Published),
+            "ended" => Some(Self::Ended),
+            "archived" => Some(Self::Archived),
+            _ => None,
+        }
+    }
+
+    /// Whether an admin may move a challenge from this state to `next`.
+    /// Archived is terminal: archived challenges are kept read-only.
+    pub fn can_transition_to(self, next: Self) -> bool {
+        use ChallengeStatus::*;
+        matches!(
+            (self, next),
+            (Draft, Scheduled | Published | Archived)
+                | (Scheduled, Draft | Published | Archived)
+                | (Published, Ended | Archived)
+                | (Ended, Published | Archived)
+        )
+    }
+
+    /// Statuses visible on public routes. Drafts and not-yet-due scheduled
+    /// challenges are only visible to admins.
+    pub fn is_public(self) -> bool {
+        matches!(self, Self::Published | Self::Ended | Self::Archived)
+    }
+}
+
+impl Challenge {
+    /// Current lifecycle status, treating a scheduled challenge whose
+    /// `publish_at` has passed as published.
+    pub fn effective_status(&self) -> ChallengeStatus {
+        match ChallengeStatus::parse(&self.status) {
+            Some(ChallengeStatus::Scheduled)
+                if self.publish_at.is_some_and(|at| at <= Utc::now()) =>
+            {
+                ChallengeStatus::Published
+            }
+            Some(status) => status,
+            None => ChallengeStatus::Draft,
+        }
+    }
+
+    /// Participant cap and waitlist setting. `inviteConfig` takes precedence
+    /// over the same keys in `configuration`.
+    pub fn capacity(&self) -> ChallengeCapacity {
+        let lookup = |key: &str| {
+            self.invite_config
+                .as_ref()
+                .and_then(|c| c.get(key))
+                .filter(|v| !v.is_null())
+                .or_else(|| self.configuration.get(key))
+        };
+
+        ChallengeCapacity {
+            max_participants: lookup("maxParticipants")
+                .and_then(|v| v.as_i64())
+                .filter(|max| *max > 0),
+            waitlist: lookup("waitlist")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(false),
+        }
+    }
+}
+
+/// How many active participants a challenge admits, and whether joins past
+/// the limit are waitlisted instead of rejected.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ChallengeCapacity {
+    pub max_participants: Option<i64>,
+    pub waitlist: bool,
 }
 
 #[derive(Debug, Serialize)]
@@ -35,12 +135,16 @@ pub struct ChallengeResponse {
     pub invite_config: Option<serde_json::Value>,
     pub hamalert_config: Option<serde_json::Value>,
     pub is_active: bool,
+    pub status: ChallengeStatus,
+    pub publish_at: Option<DateTime<Utc>>,
+    pub deleted_at: Option<DateTime<Utc>>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
 
 impl From<Challenge> for ChallengeResponse {
     fn from(c: Challenge) -> Self {
+        let status = c.effective_status();
         Self {
             id: c.id,
             version: c.version,
@@ -52,7 +156,10 @@ impl From<Challenge> for ChallengeResponse {
             configuration: c.configuration,
             invite_config: c.invite_config,
             hamalert_config: c.hamalert_config,
-            is_active: c.is_active,
+            is_active: status == ChallengeStatus::Published,
+            status,
+            publish_at: c.publish_at,
+            deleted_at: c.deleted_at,
             created_at: c.created_at,
             updated_at: c.updated_at,
         }
@@ -70,6 +177,68 @@ pub struct ChallengeListItem {
     pub challenge_type: String,
     pub participant_count: i64,
     pub is_active: bool,
+    pub status: String,
+    pub publish_at: Option<DateTime<Utc>>,
+}
+
+/// Database row for a historical challenge configuration.
+#[derive(Debug, Clone, FromRow)]
+pub struct ChallengeVersion {
+    pub challenge_id: Uuid,
+    pub version: i32,
+    pub name: String,
+    pub description: String,
+    pub author: Option<String>,
+    pub category: String,
+    pub challenge_type: String,
+    pub configuration: serde_json::Value,
+    pub invite_config: Option<serde_json::Value>,
+    pub hamalert_config: Option<serde_json::Value>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChallengeVersionResponse {
+    pub challenge_id: Uuid,
+    pub version: i32,
+    pub name: String,
+    pub description: String,
+    pub author: Option<String>,
+    pub category: String,
+    #[serde(rename = "type")]
+    pub challenge_type: String,
+    pub configuration: serde_json::Value,
+    pub invite_config: Option<serde_json::Value>,
+    pub hamalert_config: Option<serde_json::Value>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<ChallengeVersion> for ChallengeVersionResponse {
+    fn from(v: ChallengeVersion) -> Self {
+        Self {
+            challenge_id: v.challenge_id,
+            version: v.version,
+            name: v.name,
+            description: v.description,
+            author: v.author,
+            category: v.category,
+            challenge_type: v.challenge_type,
+            configuration: v.configuration,
+            invite_config: v.invite_config,
+            hamalert_config: v.hamalert_config,
+            created_at: v.created_at,
+        }
+    }
+}
+
+/// Summary of one version in a challenge's history.
+#[derive(Debug, Serialize, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct ChallengeVersionListItem {
+    pub version: i32,
+    pub name: String,
+    pub created_at: DateTime<Utc>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -84,6 +253,23 @@ pub struct CreateChallengeRequest {
     pub configuration: serde_json::Value,
     pub invite_config: Option<serde_json::Value>,
     pub hamalert_config: Option<serde_json::Value>,
+    /// Initial status on create (draft, scheduled or published; default draft).
+    /// Ignored on update; use the status endpoint instead.
+    pub status: Option<ChallengeStatus>,
+    pub publish_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Deserialize, Default)]
+pub struct PurgeChallengeQuery {
+    /// Must equal the challenge ID to confirm permanent deletion.
+    pub confirm: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateChallengeStatusRequest {
+    pub status: ChallengeStatus,
+    pub publish_at: Option<DateTime<Utc>>,
 }
 
 #[derive(Debug, Deserialize, Default)]
@@ -93,6 +279,77 @@ pub struct ListChallengesQuery {
     #[serde(rename = "type")]
     pub challenge_type: Option<String>,
     pub active: Option<bool>,
+    pub status: Option<ChallengeStatus>,
+    /// Admin only: list soft-deleted challenges instead.
+    pub deleted: Option<bool>,
     pub limit: Option<i64>,
     pub offset: Option<i64>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_status_transitions() {
+        use ChallengeStatus::*;
+        assert!(Draft.can_transition_to(Published));
+        assert!(Scheduled.can_transition_to(Draft));
+        assert!(Published.can_transition_to(Ended));
+        assert!(Ended.can_transition_to(Archived));
+        assert!(!Published.can_transition_to(Draft));
+        assert!(!Archived.can_transition_to(Published));
+        assert!(!Draft.can_transition_to(Ended));
+    }
+
+    #[test]
+    fn test_status_round_trip() {
+        for status in [
+            ChallengeStatus::Draft,
+            ChallengeStatus::Scheduled,
+            ChallengeStatus::Published,
+            ChallengeStatus::Ended,
+            ChallengeStatus::Archived,
+        ] {
+            assert_eq!(ChallengeStatus::parse(status.as_str()), Some(status));
+        }
+        assert_eq!(ChallengeStatus::parse("active"), None);
+    }
+
+    #[test]
+    fn test_capacity() {
+        let now = Utc::now();
+        let mut challenge = Challenge {
+            id: Uuid::nil(),
+            version: 1,
+            name: "Test".to_string(),
+            description: String::new(),
+            author: None,
+            category: "other".to_string(),
+            challenge_type: "collection".to_string(),
+            configuration: serde_json::json!({ "maxParticipants": 50 }),
+            invite_config: None,
+            hamalert_config: None,
+            created_at: now,
+            updated_at: now,
+            status: "published".to_string(),
+            publish_at: None,
+            deleted_at: None,
+        };
+        assert_eq!(challenge.capacity().max_participants, Some(50));
+        assert!(!challenge.capacity().waitlist);
+
+        challenge.invite_config =
+            Some(serde_json::json!({ "maxParticipants": 10, "waitlist": true }));
+        assert_eq!(
+            challenge.capacity(),
+            ChallengeCapacity {
+                max_participants: Some(10),
+                waitlist: true,
+            }
+        );
+
+        challenge.invite_config = Some(serde_json::json!({ "maxParticipants": 0 }));
+        assert_eq!(challenge.capacity().max_participants, None);
+    }
+}
@@ -1,5 +1,5 @@
 use chrono::{DateTime, Utc};
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 use uuid::Uuid;
 
@@ -10,19 +10,38 @@ pub struct FriendInvite {
     pub user_id: Uuid,
     pub created_at: DateTime<Utc>,
     pub expires_at: DateTime<Utc>,
+    /// When the last allowed use was consumed.
     pub used_at: Option<DateTime<Utc>>,
-    pub used_by_user_id: Option<Uuid>,
+    pub max_uses: i32,
+    pub use_count: i32,
+    pub revoked_at: Option<DateTime<Utc>>,
 }
 
-#[derive(Debug, Serialize)]
-#[serde(rename_all = "camelCase")]
-pub struct FriendInviteResponse {
-    pub token: String,
-    pub url: String,
-    pub expires_at: DateTime<Utc>,
+/// Whether a friend invite link can still be redeemed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FriendInviteStatus {
+    Active,
+    /// Every allowed use has been redeemed.
+    Used,
+    Expired,
+    Revoked,
 }
 
 impl FriendInvite {
+    /// Revocation wins over exhaustion, which wins over expiry.
+    pub fn status(&self, now: DateTime<Utc>) -> FriendInviteStatus {
+        if self.revoked_at.is_some() {
+            FriendInviteStatus::Revoked
+        } else if self.use_count >= self.max_uses {
+            FriendInviteStatus::Used
+        } else if self.expires_at <= now {
+            FriendInviteStatus::Expired
+        } else {
+            FriendInviteStatus::Active
+        }
+    }
+
     pub fn into_response(self, base_url: &str) -> FriendInviteResponse {
         FriendInviteResponse {
             url: format!("{}/invite/{}", base_url, self.token),
@@ -30,4 +49,103 @@ impl FriendInvite {
             expires_at: self.expires_at,
         }
     }
+
+    pub fn into_detail_response(
+        self,
+        base_url: &str,
+        redemptions: Vec<FriendInviteRedemption>,
+    ) -> FriendInviteDetailResponse {
+        FriendInviteDetailResponse {
+            status: self.status(Utc::now()),
+            url: format!("{}/invite/{}", base_url, self.token),
+            token: self.token,
+            max_uses: self.max_uses,
+            use_count: self.use_count,
+            expires_at: self.expires_at,
+            created_at: self.created_at,
+            used_at: self.used_at,
+            revoked_at: self.revoked_at,
+            redemptions,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FriendInviteResponse {
+    pub token: String,
+    pub url: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// A user who redeemed a friend invite link.
+#[derive(Debug, Clone, Serialize, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct FriendInviteRedemption {
+    #[serde(skip)]
+    pub invite_id: Uuid,
+    pub user_id: Uuid,
+    pub callsign: String,
+    pub friend_request_id: Option<Uuid>,
+    pub redeemed_at: DateTime<Utc>,
+}
+
+/// Invite link with usage, for the owner's invite list.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FriendInviteDetailResponse {
+    pub token: String,
+    pub url: String,
+    pub status: FriendInviteStatus,
+    pub max_uses: i32,
+    pub use_count: i32,
+    pub expires_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+    /// When the last allowed use was redeemed.
+    pub used_at: Option<DateTime<Utc>>,
+    pub revoked_at: Option<DateTime<Utc>>,
+    pub redemptions: Vec<FriendInviteRedemption>,
+}
+
+/// Request body for POST /v1/friends/invites.
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateFriendInviteRequest {
+    /// Number of users who can redeem the link (default 1).
+    pub max_uses: Option<i32>,
+    /// Overrides the server's default invite expiry.
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    #[test]
+    fn test_friend_invite_status() {
+        let now = Utc::now();
+        let mut invite = FriendInvite {
+            id: Uuid::nil(),
+            token: "inv_test".to_string(),
+            user_id: Uuid::nil(),
+            created_at: now,
+            expires_at: now + Duration::days(1),
+            used_at: None,
+            max_uses: 2,
+            use_count: 1,
+            revoked_at: None,
+        };
+        assert_eq!(invite.status(now), FriendInviteStatus::Active);
+        assert_eq!(
+            invite.status(now + Duration::days(2)),
+            FriendInviteStatus::Expired
+        );
+
+        invite.use_count = 2;
+        assert_eq!(invite.status(now), FriendInviteStatus::Used);
+
+        invite.revoked_at = Some(now);
+        assert_eq!(invite.status(now), FriendInviteStatus::Revoked);
+    }
 }
@@ -1,4 +1,4 @@
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Duration, Utc};
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 use uuid::Uuid;
@@ -78,6 +78,7 @@ pub struct FriendSuggestionsBody {
 pub struct FriendSuggestionResponse {
     pub user_id: Uuid,
     pub callsign: String,
+    pub display_name: Option<String>,
 }
 
 
@@ -87,6 +88,7 @@ pub struct FriendResponse {
     pub friendship_id: Uuid,
     pub callsign: String,
     pub user_id: Uuid,
+    pub display_name: Option<String>,
     pub accepted_at: DateTime<Utc>,
 }
 
@@ -96,3 +98,55 @@ pub struct PendingRequestsResponse {
     pub incoming: Vec<FriendRequestResponse>,
     pub outgoing: Vec<FriendRequestResponse>,
 }
+
+/// When a sender whose request was declined at `declined_at` may ask the
+/// same user again, or `None` if they already may. A non-positive
+/// `cooldown_days` disables the cooldown.
+pub fn friend_request_retry_after(
+    declined_at: DateTime<Utc>,
+    cooldown_days: i64,
+    now: DateTime<Utc>,
+) -> Option<DateTime<Utc>> {
+    if cooldown_days <= 0 {
+        return None;
+    }
+    let retry_after = declined_at + Duration::days(cooldown_days);
+    (retry_after > now).then_some(retry_after)
+}
+
+/// Pending requests sent before this time have expired, or `None` if a
+/// non-positive `expiry_days` disables expiry.
+pub fn friend_request_expiry_cutoff(now: DateTime<Utc>, expiry_days: i64) -> Option<DateTime<Utc>> {
+    (expiry_days > 0).then(|| now - Duration::days(expiry_days))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_friend_request_retry_after() {
+        let declined_at = Utc::now() - Duration::days(10);
+
+        assert_eq!(
+            friend_request_retry_after(declined_at, 30, Utc::now()),
+            Some(declined_at + Duration::days(30))
+        );
+        // Cooldown already over
+        assert_eq!(friend_request_retry_after(declined_at, 7, Utc::now()), None);
+        // Disabled
+        assert_eq!(friend_request_retry_after(declined_at, 0, Utc::now()), None);
+    }
+
+    #[test]
+    fn test_friend_request_expiry_cutoff() {
+        let now = Utc::now();
+
+        assert_eq!(
+            friend_request_expiry_cutoff(now, 30),
+            Some(now - Duration::days(30))
+        );
+        assert_eq!(friend_request_expiry_cutoff(now, 0), None);
+        assert_eq!(friend_request_expiry_cutoff(now, -1), None);
+    }
+}
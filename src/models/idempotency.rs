@@ -0,0 +1,11 @@
+use sqlx::FromRow;
+
+/// Database row for a stored idempotency key.
+#[derive(Debug, Clone, FromRow)]
+pub struct IdempotencyRecord {
+    pub request_hash: String,
+    /// `None` while the original request is in flight.
+    pub status_code: Option<i16>,
+    pub content_type: Option<String>,
+    pub response_body: Option<Vec<u8>>,
+}
@@ -25,6 +25,13 @@ pub struct InviteResponse {
 }
 
 impl InviteToken {
+    /// Whether the token is neither expired nor used up.
+    pub fn is_valid(&self, now: DateTime<Utc>) -> bool {
+        let expired = self.expires_at.is_some_and(|at| at <= now);
+        let exhausted = self.max_uses.is_some_and(|max| self.use_count >= max);
+        !expired && !exhausted
+    }
+
     pub fn into_response(self, base_url: &str) -> InviteResponse {
         InviteResponse {
             url: format!("{}/join/{}", base_url, self.token),
@@ -1,19 +1,37 @@
 pub mod activity;
+pub mod activity_type;
 pub mod badge;
+pub mod block;
+pub mod bundle;
+pub mod certificate;
 pub mod challenge;
 pub mod friend_invite;
 pub mod friend_request;
+pub mod idempotency;
 pub mod invite;
 pub mod participant;
+pub mod privacy;
+pub mod profile;
 pub mod progress;
+pub mod reaction;
+pub mod template;
 pub mod user;
 
 pub use activity::*;
+pub use activity_type::*;
 pub use badge::*;
+pub use block::*;
+pub use bundle::*;
+pub use certificate::*;
 pub use challenge::*;
 pub use friend_invite::*;
 pub use friend_request::*;
+pub use idempotency::*;
 pub use invite::*;
 pub use participant::*;
+pub use privacy::*;
+pub use profile::*;
 pub use progress::*;
+pub use reaction::*;
+pub use template::*;
 pub use user::*;
@@ -23,6 +23,13 @@ pub struct ChallengeParticipant {
     pub status: String,
 }
 
+impl ChallengeParticipant {
+    /// Whether this participation may report progress (not waitlisted or left).
+    pub fn is_participating(&self) -> bool {
+        matches!(self.status.as_str(), "active" | "completed")
+    }
+}
+
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct JoinChallengeRequest {
@@ -0,0 +1,153 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+/// How a user appears on public leaderboards to non-friends.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LeaderboardVisibility {
+    Public,
+    /// Shown as "Anonymous".
+    Anonymous,
+    /// Shown with the suffix masked, e.g. "W1***".
+    Masked,
+}
+
+impl LeaderboardVisibility {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Public => "public",
+            Self::Anonymous => "anonymous",
+            Self::Masked => "masked",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "public" => Some(Self::Public),
+            "anonymous" => Some(Self::Anonymous),
+            "masked" => Some(Self::Masked),
+            _ => None,
+        }
+    }
+
+    /// Callsign to show for a user with this visibility.
+    pub fn display_callsign(self, callsign: &str) -> String {
+        match self {
+            Self::Public => callsign.to_string(),
+            Self::Anonymous => "Anonymous".to_string(),
+            Self::Masked => mask_callsign(callsign),
+        }
+    }
+}
+
+/// Who can see an activity.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ActivityAudience {
+    Public,
+    Friends,
+    /// Only the author.
+    Private,
+}
+
+impl ActivityAudience {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Public => "public",
+            Self::Friends => "friends",
+            Self::Private => "private",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "public" => Some(Self::Public),
+            "friends" => Some(Self::Friends),
+            "private" => Some(Self::Private),
+            _ => None,
+        }
+    }
+}
+
+/// Database row for user_privacy_settings (defaults when no row exists).
+#[derive(Debug, Clone, FromRow)]
+pub struct PrivacySettings {
+    pub hide_from_search: bool,
+    pub leaderboard_visibility: String,
+    pub default_activity_audience: String,
+    /// Whether the server posts challenge, badge and friendship milestones.
+    pub share_milestones: bool,
+}
+
+impl PrivacySettings {
+    pub fn leaderboard_visibility(&self) -> LeaderboardVisibility {
+        LeaderboardVisibility::parse(&self.leaderboard_visibility)
+            .unwrap_or(LeaderboardVisibility::Public)
+    }
+
+    pub fn default_activity_audience(&self) -> ActivityAudience {
+        ActivityAudience::parse(&self.default_activity_audience)
+            .unwrap_or(ActivityAudience::Friends)
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PrivacySettingsResponse {
+    pub hide_from_search: bool,
+    pub leaderboard_visibility: LeaderboardVisibility,
+    pub default_activity_audience: ActivityAudience,
+    pub share_milestones: bool,
+}
+
+impl From<PrivacySettings> for PrivacySettingsResponse {
+    fn from(s: PrivacySettings) -> Self {
+        Self {
+            leaderboard_visibility: s.leaderboard_visibility(),
+            default_activity_audience: s.default_activity_audience(),
+            hide_from_search: s.hide_from_search,
+            share_milestones: s.share_milestones,
+        }
+    }
+}
+
+/// Request body for PUT /v1/settings/privacy. Omitted fields are unchanged.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdatePrivacySettingsRequest {
+    pub hide_from_search: Option<bool>,
+    pub leaderboard_visibility: Option<LeaderboardVisibility>,
+    pub default_activity_audience: Option<ActivityAudience>,
+    pub share_milestones: Option<bool>,
+}
+
+/// Mask a callsign's suffix, keeping the prefix through its first digit
+/// after the first character: "W1ABC" -> "W1***", "9A1AA" -> "9A1**".
+pub fn mask_callsign(callsign: &str) -> String {
+    let chars: Vec<char> = callsign.chars().collect();
+    let keep = chars
+        .iter()
+        .skip(1)
+        .position(|c| c.is_ascii_digit())
+        .map(|i| i + 2)
+        .unwrap_or(1)
+        .min(chars.len());
+
+    let mut masked: String = chars[..keep].iter().collect();
+    masked.push_str(&"*".repeat(chars.len() - keep));
+    masked
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mask_callsign() {
+        assert_eq!(mask_callsign("W1ABC"), "W1***");
+        assert_eq!(mask_callsign("VE3XYZ"), "VE3***");
+        assert_eq!(mask_callsign("9A1AA"), "9A1**");
+        assert_eq!(mask_callsign("ABCD"), "A***");
+        assert_eq!(mask_callsign(""), "");
+    }
+}
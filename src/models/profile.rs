@@ -0,0 +1,218 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+use super::LeaderboardVisibility;
+
+pub const MAX_DISPLAY_NAME_LENGTH: usize = 50;
+pub const MAX_QTH_LENGTH: usize = 100;
+pub const MAX_BIO_LENGTH: usize = 500;
+
+/// Profile fields for a user (without avatar bytes).
+#[derive(Debug, Clone, FromRow)]
+pub struct UserProfile {
+    pub user_id: Uuid,
+    pub callsign: String,
+    pub display_name: Option<String>,
+    pub qth: Option<String>,
+    pub grid: Option<String>,
+    pub bio: Option<String>,
+    pub has_avatar: bool,
+    pub member_since: DateTime<Utc>,
+}
+
+/// Avatar image row.
+#[derive(Debug, Clone, FromRow)]
+pub struct UserAvatar {
+    pub avatar_data: Vec<u8>,
+    pub avatar_content_type: String,
+}
+
+/// Minimal user identity with display name, used by search, suggestions
+/// and friend lists.
+#[derive(Debug, Clone, FromRow)]
+pub struct UserSummary {
+    pub id: Uuid,
+    pub callsign: String,
+    pub display_name: Option<String>,
+}
+
+/// Request body for PUT /v1/profile. Omitted or empty fields are cleared.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateProfileRequest {
+    pub display_name: Option<String>,
+    pub qth: Option<String>,
+    pub grid: Option<String>,
+    pub bio: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfileResponse {
+    pub user_id: Uuid,
+    pub callsign: String,
+    pub display_name: Option<String>,
+    pub qth: Option<String>,
+    pub grid: Option<String>,
+    pub bio: Option<String>,
+    pub avatar_url: Option<String>,
+    pub member_since: DateTime<Utc>,
+}
+
+impl UserProfile {
+    pub fn into_response(self, base_url: &str) -> ProfileResponse {
+        ProfileResponse {
+            avatar_url: self
+                .has_avatar
+                .then(|| format!("{}/v1/users/{}/avatar", base_url, self.user_id)),
+            user_id: self.user_id,
+            callsign: self.callsign,
+            display_name: self.display_name,
+            qth: self.qth,
+            grid: self.grid,
+            bio: self.bio,
+            member_since: self.member_since,
+        }
+    }
+}
+
+/// Earned badge on a public profile.
+#[derive(Debug, Serialize, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfileBadge {
+    pub badge_id: Uuid,
+    pub name: String,
+    pub tier_id: Option<String>,
+    pub challenge_id: Uuid,
+    pub challenge_name: String,
+    pub earned_at: DateTime<Utc>,
+}
+
+/// Challenge participation row for a public profile, with the data needed
+/// to work out completion.
+#[derive(Debug, Clone, FromRow)]
+pub struct ProfileChallengeRow {
+    pub challenge_id: Uuid,
+    pub challenge_name: String,
+    pub configuration: serde_json::Value,
+    pub joined_at: DateTime<Utc>,
+    pub completed_goals: Vec<String>,
+    pub goal_statuses: Vec<String>,
+    pub current_value: i32,
+    pub score: i32,
+    pub current_tier: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfileChallenge {
+    pub challenge_id: Uuid,
+    pub challenge_name: String,
+    pub joined_at: DateTime<Utc>,
+    pub score: i32,
+    pub percentage: f64,
+    pub current_tier: Option<String>,
+    pub completed: bool,
+}
+
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfileStats {
+    pub challenges_joined: usize,
+    pub challenges_completed: usize,
+    pub badges_earned: usize,
+    pub tiers_reached: usize,
+}
+
+/// Response for GET /v1/users/:id/profile.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PublicProfileResponse {
+    #[serde(flatten)]
+    pub profile: ProfileResponse,
+    pub stats: ProfileStats,
+    pub badges: Vec<ProfileBadge>,
+    pub challenges: Vec<ProfileChallenge>,
+}
+
+impl PublicProfileResponse {
+    /// Profile as shown to a viewer the user's leaderboard visibility hides
+    /// them from: the callsign as the leaderboard shows it and the member
+    /// date, without personal details, badges or scores.
+    pub fn hidden(profile: UserProfile, visibility: LeaderboardVisibility) -> Self {
+        Self {
+            profile: ProfileResponse {
+                user_id: profile.user_id,
+                callsign: visibility.display_callsign(&profile.callsign),
+                display_name: None,
+                qth: None,
+                grid: None,
+                bio: None,
+                avatar_url: None,
+                member_since: profile.member_since,
+            },
+            stats: ProfileStats::default(),
+            badges: vec![],
+            challenges: vec![],
+        }
+    }
+}
+
+/// Whether `grid` is a 4, 6 or 8 character Maidenhead locator.
+pub fn is_valid_grid(grid: &str) -> bool {
+    let b = grid.as_bytes();
+    if !matches!(b.len(), 4 | 6 | 8) {
+        return false;
+    }
+
+    let field = |c: u8| (b'A'..=b'R').contains(&c.to_ascii_uppercase());
+    let subsquare = |c: u8| (b'A'..=b'X').contains(&c.to_ascii_uppercase());
+
+    field(b[0])
+        && field(b[1])
+        && b[2].is_ascii_digit()
+        && b[3].is_ascii_digit()
+        && (b.len() < 6 || (subsquare(b[4]) && subsquare(b[5])))
+        && (b.len() < 8 || (b[6].is_ascii_digit() && b[7].is_ascii_digit()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_valid_grid() {
+        assert!(is_valid_grid("FN31"));
+        assert!(is_valid_grid("fn31pr"));
+        assert!(is_valid_grid("FN31PR12"));
+        assert!(!is_valid_grid("FN3"));
+        assert!(!is_valid_grid("ZZ31"));
+        assert!(!is_valid_grid("FN31ZZ"));
+        assert!(!is_valid_grid("FN31PR1"));
+    }
+
+    #[test]
+    fn test_hidden_profile() {
+        let profile = UserProfile {
+            user_id: Uuid::nil(),
+            callsign: "W1ABC".to_string(),
+            display_name: Some("Alice".to_string()),
+            qth: Some("Boston, MA".to_string()),
+            grid: Some("FN42".to_string()),
+            bio: None,
+            has_avatar: true,
+            member_since: Utc::now(),
+        };
+
+        let hidden = PublicProfileResponse::hidden(profile.clone(), LeaderboardVisibility::Masked);
+        assert_eq!(hidden.profile.callsign, "W1***");
+        assert_eq!(hidden.profile.display_name, None);
+        assert_eq!(hidden.profile.avatar_url, None);
+        assert_eq!(hidden.stats.challenges_joined, 0);
+
+        let hidden = PublicProfileResponse::hidden(profile, LeaderboardVisibility::Anonymous);
+        assert_eq!(hidden.profile.callsign, "Anonymous");
+    }
+}
@@ -1,19 +1,199 @@
+use std::collections::{BTreeMap, HashMap};
+
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 use uuid::Uuid;
 
+use super::CertificateResponse;
+
+pub const MAX_EVIDENCE_CALLSIGN_LENGTH: usize = 20;
+pub const MAX_EVIDENCE_FIELD_LENGTH: usize = 32;
+
+/// How far a goal's qualifying contact got: worked, or confirmed through
+/// LoTW or a QSL card.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GoalStatus {
+    Worked,
+    Lotw,
+    Qsl,
+}
+
+impl GoalStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Worked => "worked",
+            Self::Lotw => "lotw",
+            Self::Qsl => "qsl",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "worked" => Some(Self::Worked),
+            "lotw" => Some(Self::Lotw),
+            "qsl" => Some(Self::Qsl),
+            _ => None,
+        }
+    }
+
+    pub fn is_confirmed(self) -> bool {
+        self != Self::Worked
+    }
+}
+
+/// Which score a leaderboard ranks by: every worked goal, or only
+/// confirmed ones.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ScoreBasis {
+    Worked,
+    Confirmed,
+}
+
+impl ScoreBasis {
+    /// `progress` column holding the score on this basis.
+    pub fn score_column(self) -> &'static str {
+        match self {
+            Self::Worked => "worked_score",
+            Self::Confirmed => "confirmed_score",
+        }
+    }
+}
+
+/// Progress row. `completed_goals` and the matching `goal_statuses` are
+/// aggregated from `goal_completions`, oldest completion first.
 #[derive(Debug, Clone, FromRow)]
 pub struct Progress {
     pub id: Uuid,
     pub challenge_id: Uuid,
     pub callsign: String,
-    pub completed_goals: serde_json::Value,
+    pub completed_goals: Vec<String>,
+    pub goal_statuses: Vec<String>,
     pub current_value: i32,
+    /// Score on the challenge's basis; the one tiers are awarded on.
     pub score: i32,
+    pub worked_score: i32,
+    pub confirmed_score: i32,
     pub current_tier: Option<String>,
     pub last_qso_date: Option<DateTime<Utc>>,
     pub updated_at: DateTime<Utc>,
+    pub challenge_version: Option<i32>,
+}
+
+impl Progress {
+    /// Completed goals paired with their status.
+    pub fn goals_with_status(&self) -> Vec<(String, GoalStatus)> {
+        pair_goal_statuses(&self.completed_goals, &self.goal_statuses)
+    }
+}
+
+/// Pair goal IDs with statuses aggregated alongside them.
+pub fn pair_goal_statuses(goals: &[String], statuses: &[String]) -> Vec<(String, GoalStatus)> {
+    goals
+        .iter()
+        .zip(statuses)
+        .map(|(goal, status)| {
+            let status = GoalStatus::parse(status).unwrap_or(GoalStatus::Worked);
+            (goal.clone(), status)
+        })
+        .collect()
+}
+
+/// Row from `goal_completions`. Evidence columns are set together, keyed on
+/// `contacted_callsign`.
+#[derive(Debug, Clone, FromRow)]
+pub struct GoalCompletionRow {
+    pub goal_id: String,
+    pub completed_at: DateTime<Utc>,
+    pub status: String,
+    pub contacted_callsign: Option<String>,
+    pub band: Option<String>,
+    pub mode: Option<String>,
+    pub qso_at: Option<DateTime<Utc>>,
+    pub confirmation_source: Option<String>,
+}
+
+/// A completed goal with the contact that qualified it, if reported.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GoalCompletion {
+    pub goal_id: String,
+    pub completed_at: DateTime<Utc>,
+    pub status: GoalStatus,
+    pub evidence: Option<GoalEvidence>,
+}
+
+impl From<GoalCompletionRow> for GoalCompletion {
+    fn from(row: GoalCompletionRow) -> Self {
+        let evidence = row
+            .contacted_callsign
+            .map(|contacted_callsign| GoalEvidence {
+                contacted_callsign,
+                band: row.band,
+                mode: row.mode,
+                qso_at: row.qso_at,
+                confirmation_source: row.confirmation_source,
+            });
+        GoalCompletion {
+            goal_id: row.goal_id,
+            completed_at: row.completed_at,
+            status: GoalStatus::parse(&row.status).unwrap_or(GoalStatus::Worked),
+            evidence,
+        }
+    }
+}
+
+/// The QSO behind a completed goal, e.g. KL7XX on 20m CW for Alaska.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GoalEvidence {
+    pub contacted_callsign: String,
+    pub band: Option<String>,
+    pub mode: Option<String>,
+    pub qso_at: Option<DateTime<Utc>>,
+    /// Where the contact was confirmed, e.g. `lotw` or `qsl`. Absent when
+    /// only worked.
+    pub confirmation_source: Option<String>,
+}
+
+impl GoalEvidence {
+    /// Trim and case-normalize fields (callsign and mode upper, band and
+    /// source lower), rejecting empty callsigns and overlong values.
+    pub fn normalize(self) -> Result<Self, String> {
+        let contacted_callsign = self.contacted_callsign.trim().to_uppercase();
+        if contacted_callsign.is_empty()
+            || contacted_callsign.chars().count() > MAX_EVIDENCE_CALLSIGN_LENGTH
+        {
+            return Err(format!(
+                "contactedCallsign must be 1-{} characters",
+                MAX_EVIDENCE_CALLSIGN_LENGTH
+            ));
+        }
+
+        let field = |name: &str, value: Option<String>, upper: bool| {
+            let value = value
+                .map(|v| v.trim().to_string())
+                .filter(|v| !v.is_empty());
+            match value {
+                Some(v) if v.chars().count() > MAX_EVIDENCE_FIELD_LENGTH => Err(format!(
+                    "{} must be at most {} characters",
+                    name, MAX_EVIDENCE_FIELD_LENGTH
+                )),
+                Some(v) if upper => Ok(Some(v.to_uppercase())),
+                Some(v) => Ok(Some(v.to_lowercase())),
+                None => Ok(None),
+            }
+        };
+
+        Ok(GoalEvidence {
+            contacted_callsign,
+            band: field("band", self.band, false)?,
+            mode: field("mode", self.mode, true)?,
+            qso_at: self.qso_at,
+            confirmation_source: field("confirmationSource", self.confirmation_source, false)?,
+        })
+    }
 }
 
 #[derive(Debug, Deserialize)]
@@ -23,17 +203,185 @@ pub struct ReportProgressRequest {
     pub current_value: i32,
     pub qualifying_qso_count: i32,
     pub last_qso_date: Option<DateTime<Utc>>,
+    /// When the device saw each goal completed. Goals without a time are
+    /// stamped with the time of the report.
+    #[serde(default)]
+    pub goal_completed_at: HashMap<String, DateTime<Utc>>,
+    /// Qualifying contact per completed goal.
+    #[serde(default)]
+    pub goal_evidence: HashMap<String, GoalEvidence>,
+    /// Status per completed goal. Defaults to the evidence's
+    /// `confirmationSource` when that is `lotw` or `qsl`, else `worked`.
+    #[serde(default)]
+    pub goal_status: HashMap<String, GoalStatus>,
+}
+
+impl ReportProgressRequest {
+    /// Merge this report into the stored progress and its goal completions:
+    /// goals are the union of both with the earliest completion time, and
+    /// `current_value` and `last_qso_date` never go backwards. A confirmed
+    /// status is never downgraded to worked, and reported evidence replaces
+    /// stored evidence unless that would drop a confirmation. Whatever the
+    /// report would have lost is returned as conflicts.
+    pub fn merge_into(
+        &self,
+        existing: Option<&Progress>,
+        completions: &[GoalCompletion],
+        now: DateTime<Utc>,
+    ) -> MergedProgress {
+        let mut completions = completions.to_vec();
+        let mut conflicts = Vec::new();
+
+        let missing_goals: Vec<String> = completions
+            .iter()
+            .filter(|c| !self.completed_goals.contains(&c.goal_id))
+            .map(|c| c.goal_id.clone())
+            .collect();
+        if !missing_goals.is_empty() {
+            conflicts.push(ProgressConflict::MissingGoals {
+                goal_ids: missing_goals,
+            });
+        }
+
+        for goal in &self.completed_goals {
+            // Device clocks can run ahead; a completion can't be in the future
+            let reported = self
+                .goal_completed_at
+                .get(goal)
+                .map_or(now, |at| (*at).min(now));
+            let evidence = self.goal_evidence.get(goal);
+            let status = self
+                .goal_status
+                .get(goal)
+                .copied()
+                .or_else(|| {
+                    evidence
+                        .and_then(|e| e.confirmation_source.as_deref())
+                        .and_then(GoalStatus::parse)
+                })
+                .unwrap_or(GoalStatus::Worked);
+
+            match completions.iter_mut().find(|c| &c.goal_id == goal) {
+                Some(completion) => {
+                    completion.completed_at = completion.completed_at.min(reported);
+                    if !completion.status.is_confirmed() {
+                        completion.status = status;
+                    }
+                    let keeps_confirmation = completion
+                        .evidence
+                        .as_ref()
+                        .is_some_and(|e| e.confirmation_source.is_some())
+                        && evidence.is_some_and(|e| e.confirmation_source.is_none());
+                    if evidence.is_some() && !keeps_confirmation {
+                        completion.evidence = evidence.cloned();
+                    }
+                }
+                None => completions.push(GoalCompletion {
+                    goal_id: goal.clone(),
+                    completed_at: reported,
+                    status,
+                    evidence: evidence.cloned(),
+                }),
+            }
+        }
+
+        let stored_value = existing.map_or(0, |p| p.current_value);
+        if self.current_value < stored_value {
+            conflicts.push(ProgressConflict::LowerCurrentValue {
+                reported: self.current_value,
+                kept: stored_value,
+            });
+        }
+
+        MergedProgress {
+            completions,
+            current_value: self.current_value.max(stored_value),
+            last_qso_date: self
+                .last_qso_date
+                .max(existing.and_then(|p| p.last_qso_date)),
+            conflicts,
+        }
+    }
+}
+
+/// Progress after merging a report, before scoring.
+#[derive(Debug)]
+pub struct MergedProgress {
+    pub completions: Vec<GoalCompletion>,
+    pub current_value: i32,
+    pub last_qso_date: Option<DateTime<Utc>>,
+    pub conflicts: Vec<ProgressConflict>,
+}
+
+impl MergedProgress {
+    /// Completed goals paired with their status.
+    pub fn goals_with_status(&self) -> Vec<(String, GoalStatus)> {
+        self.completions
+            .iter()
+            .map(|c| (c.goal_id.clone(), c.status))
+            .collect()
+    }
+}
+
+/// Where a report disagreed with the server, which kept its own state.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(
+    tag = "type",
+    rename_all = "camelCase",
+    rename_all_fields = "camelCase"
+)]
+pub enum ProgressConflict {
+    /// Goals already completed that the report left out.
+    MissingGoals { goal_ids: Vec<String> },
+    /// The report's value was lower than the stored one.
+    LowerCurrentValue { reported: i32, kept: i32 },
+}
+
+/// Query for GET /v1/challenges/:id/progress.
+#[derive(Debug, Deserialize, Default)]
+pub struct ProgressQuery {
+    /// Comma-separated extras; only `evidence` is supported.
+    pub include: Option<String>,
+}
+
+impl ProgressQuery {
+    pub fn include_evidence(&self) -> Result<bool, String> {
+        let mut evidence = false;
+        for part in self.include.iter().flat_map(|i| i.split(',')) {
+            match part.trim() {
+                "" => {}
+                "evidence" => evidence = true,
+                other => return Err(format!("Unknown include: {}", other)),
+            }
+        }
+        Ok(evidence)
+    }
 }
 
 #[derive(Debug, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ProgressResponse {
     pub completed_goals: Vec<String>,
+    /// First time each completed goal was reported.
+    pub goal_completed_at: BTreeMap<String, DateTime<Utc>>,
+    pub goal_status: BTreeMap<String, GoalStatus>,
+    /// Qualifying contact per goal that has one; only with `include=evidence`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub goal_evidence: Option<BTreeMap<String, GoalEvidence>>,
     pub current_value: i32,
+    /// Percentage on the challenge's score basis.
     pub percentage: f64,
+    pub worked_percentage: f64,
+    pub confirmed_percentage: f64,
+    /// Basis of `score`, `percentage` and tiers.
+    pub score_basis: ScoreBasis,
     pub score: i32,
+    pub worked_score: i32,
+    pub confirmed_score: i32,
     pub rank: i64,
     pub current_tier: Option<String>,
+    /// Challenge version this progress was scored against.
+    pub challenge_version: Option<i32>,
 }
 
 #[derive(Debug, Serialize)]
@@ -42,6 +390,10 @@ pub struct ReportProgressResponse {
     pub accepted: bool,
     pub server_progress: ProgressResponse,
     pub new_badges: Vec<Uuid>,
+    /// Certificates issued by this report.
+    pub new_certificates: Vec<CertificateResponse>,
+    /// Empty when the report was at least as far along as the server.
+    pub conflicts: Vec<ProgressConflict>,
 }
 
 #[derive(Debug, Serialize, Clone, FromRow)]
@@ -58,6 +410,8 @@ pub struct LeaderboardEntry {
 #[serde(rename_all = "camelCase")]
 pub struct LeaderboardResponse {
     pub leaderboard: Vec<LeaderboardEntry>,
+    /// Score the leaderboard is ranked by.
+    pub rank_by: ScoreBasis,
     pub total: i64,
     pub user_position: Option<LeaderboardEntry>,
     pub last_updated: DateTime<Utc>,
@@ -69,4 +423,223 @@ pub struct LeaderboardQuery {
     pub limit: Option<i64>,
     pub offset: Option<i64>,
     pub around: Option<String>,
+    /// Rank by worked or confirmed score; the challenge's basis by default.
+    pub rank_by: Option<ScoreBasis>,
+}
+
+/// Result of recomputing every participant's progress under the current
+/// challenge configuration.
+#[derive(Debug, Serialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct RescoreSummary {
+    pub challenge_id: Uuid,
+    pub version: i32,
+    pub participants_rescored: i64,
+    pub scores_changed: i64,
+    pub tiers_changed: i64,
+    pub ranks_changed: i64,
+    pub badges_awarded: i64,
+    pub badges_revoked: i64,
+    pub certificates_issued: i64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn at(day: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 3, day, 12, 0, 0).unwrap()
+    }
+
+    fn report(goals: &[&str], current_value: i32) -> ReportProgressRequest {
+        ReportProgressRequest {
+            completed_goals: goals.iter().map(|g| g.to_string()).collect(),
+            current_value,
+            qualifying_qso_count: 0,
+            last_qso_date: None,
+            goal_completed_at: HashMap::new(),
+            goal_evidence: HashMap::new(),
+            goal_status: HashMap::new(),
+        }
+    }
+
+    fn stored(current_value: i32) -> Progress {
+        Progress {
+            id: Uuid::new_v4(),
+            challenge_id: Uuid::new_v4(),
+            callsign: "W1AW".to_string(),
+            completed_goals: Vec::new(),
+            goal_statuses: Vec::new(),
+            current_value,
+            score: 0,
+            worked_score: 0,
+            confirmed_score: 0,
+            current_tier: None,
+            last_qso_date: Some(at(2)),
+            updated_at: at(2),
+            challenge_version: Some(1),
+        }
+    }
+
+    fn completion(goal_id: &str, day: u32, evidence: Option<GoalEvidence>) -> GoalCompletion {
+        GoalCompletion {
+            goal_id: goal_id.to_string(),
+            completed_at: at(day),
+            status: GoalStatus::Worked,
+            evidence,
+        }
+    }
+
+    fn evidence(call: &str, confirmation_source: Option<&str>) -> GoalEvidence {
+        GoalEvidence {
+            contacted_callsign: call.to_string(),
+            band: Some("20m".to_string()),
+            mode: Some("CW".to_string()),
+            qso_at: Some(at(1)),
+            confirmation_source: confirmation_source.map(String::from),
+        }
+    }
+
+    #[test]
+    fn test_merge_first_report() {
+        let merged = report(&["CA", "NY"], 2).merge_into(None, &[], at(10));
+        assert_eq!(
+            merged
+                .completions
+                .iter()
+                .map(|c| c.goal_id.as_str())
+                .collect::<Vec<_>>(),
+            vec!["CA", "NY"]
+        );
+        assert_eq!(merged.completions[0].completed_at, at(10));
+        assert_eq!(merged.current_value, 2);
+        assert!(merged.conflicts.is_empty());
+    }
+
+    #[test]
+    fn test_merge_stale_report() {
+        let existing = stored(5);
+        let completions = [completion("CA", 1, None), completion("NY", 2, None)];
+        let merged = report(&["CA", "TX"], 3).merge_into(Some(&existing), &completions, at(10));
+
+        assert_eq!(
+            merged
+                .completions
+                .iter()
+                .map(|c| c.goal_id.as_str())
+                .collect::<Vec<_>>(),
+            vec!["CA", "NY", "TX"]
+        );
+        assert_eq!(merged.completions[0].completed_at, at(1));
+        assert_eq!(merged.completions[2].completed_at, at(10));
+        assert_eq!(merged.current_value, 5);
+        assert_eq!(merged.last_qso_date, Some(at(2)));
+        assert_eq!(
+            merged.conflicts,
+            vec![
+                ProgressConflict::MissingGoals {
+                    goal_ids: vec!["NY".to_string()]
+                },
+                ProgressConflict::LowerCurrentValue {
+                    reported: 3,
+                    kept: 5
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_merge_keeps_earliest_completion() {
+        let existing = stored(1);
+        let mut req = report(&["CA", "NY"], 2);
+        req.goal_completed_at.insert("CA".to_string(), at(3));
+        req.goal_completed_at.insert("NY".to_string(), at(20));
+
+        let merged = req.merge_into(Some(&existing), &[completion("CA", 5, None)], at(10));
+        assert_eq!(merged.completions[0].completed_at, at(3));
+        // Future times are clamped to the report time
+        assert_eq!(merged.completions[1].completed_at, at(10));
+        assert!(merged.conflicts.is_empty());
+    }
+
+    #[test]
+    fn test_merge_evidence() {
+        let existing = stored(0);
+        let completions = [
+            completion("AK", 1, Some(evidence("KL7XX", Some("lotw")))),
+            completion("HI", 1, Some(evidence("KH6XX", None))),
+        ];
+        let mut req = report(&["AK", "HI", "CA"], 0);
+        req.goal_evidence
+            .insert("AK".to_string(), evidence("KL7YY", None));
+        req.goal_evidence
+            .insert("HI".to_string(), evidence("KH6YY", Some("qsl")));
+        req.goal_evidence
+            .insert("CA".to_string(), evidence("W6XX", None));
+
+        let merged = req.merge_into(Some(&existing), &completions, at(10));
+        let call = |i: usize| {
+            merged.completions[i]
+                .evidence
+                .as_ref()
+                .map(|e| e.contacted_callsign.as_str())
+        };
+        // A confirmed contact isn't replaced by an unconfirmed one
+        assert_eq!(call(0), Some("KL7XX"));
+        assert_eq!(call(1), Some("KH6YY"));
+        assert_eq!(call(2), Some("W6XX"));
+    }
+
+    #[test]
+    fn test_merge_status() {
+        let existing = stored(0);
+        let mut confirmed = completion("AK", 1, None);
+        confirmed.status = GoalStatus::Lotw;
+        let completions = [confirmed, completion("HI", 1, None)];
+        let mut req = report(&["AK", "HI", "CA"], 0);
+        req.goal_status.insert("AK".to_string(), GoalStatus::Worked);
+        req.goal_evidence
+            .insert("HI".to_string(), evidence("KH6YY", Some("qsl")));
+
+        let merged = req.merge_into(Some(&existing), &completions, at(10));
+        let statuses: Vec<GoalStatus> = merged.completions.iter().map(|c| c.status).collect();
+        // Confirmed stays confirmed; evidence sets the status when none is given
+        assert_eq!(
+            statuses,
+            vec![GoalStatus::Lotw, GoalStatus::Qsl, GoalStatus::Worked]
+        );
+    }
+
+    #[test]
+    fn test_normalize_evidence() {
+        let normalized = GoalEvidence {
+            contacted_callsign: " kl7xx ".to_string(),
+            band: Some("20M".to_string()),
+            mode: Some("cw".to_string()),
+            qso_at: None,
+            confirmation_source: Some(" ".to_string()),
+        }
+        .normalize()
+        .unwrap();
+        assert_eq!(normalized.contacted_callsign, "KL7XX");
+        assert_eq!(normalized.band.as_deref(), Some("20m"));
+        assert_eq!(normalized.mode.as_deref(), Some("CW"));
+        assert_eq!(normalized.confirmation_source, None);
+
+        assert!(evidence("", None).normalize().is_err());
+        let mut long = evidence("KL7XX", None);
+        long.mode = Some("X".repeat(33));
+        assert!(long.normalize().is_err());
+    }
+
+    #[test]
+    fn test_include_evidence() {
+        let query = |include: Option<&str>| ProgressQuery {
+            include: include.map(String::from),
+        };
+        assert_eq!(query(None).include_evidence(), Ok(false));
+        assert_eq!(query(Some("evidence")).include_evidence(), Ok(true));
+        assert!(query(Some("evidence,bogus")).include_evidence().is_err());
+    }
 }
@@ -0,0 +1,181 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// Longest comment body accepted, in characters.
+pub const MAX_COMMENT_LENGTH: usize = 1000;
+
+/// The fixed set of reactions an activity can receive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReactionKind {
+    #[serde(rename = "73")]
+    SeventyThree,
+    Congrats,
+    Like,
+    Fire,
+    Wow,
+}
+
+impl ReactionKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::SeventyThree => "73",
+            Self::Congrats => "congrats",
+            Self::Like => "like",
+            Self::Fire => "fire",
+            Self::Wow => "wow",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "73" => Some(Self::SeventyThree),
+            "congrats" => Some(Self::Congrats),
+            "like" => Some(Self::Like),
+            "fire" => Some(Self::Fire),
+            "wow" => Some(Self::Wow),
+            _ => None,
+        }
+    }
+}
+
+/// A reaction with the reacting user's display info.
+#[derive(Debug, Clone, FromRow)]
+pub struct ReactionRow {
+    pub user_id: Uuid,
+    pub callsign: String,
+    pub display_name: Option<String>,
+    pub kind: String,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReactionResponse {
+    pub user_id: Uuid,
+    pub callsign: String,
+    pub display_name: Option<String>,
+    pub kind: ReactionKind,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<ReactionRow> for ReactionResponse {
+    fn from(r: ReactionRow) -> Self {
+        Self {
+            kind: ReactionKind::parse(&r.kind).unwrap_or(ReactionKind::Like),
+            user_id: r.user_id,
+            callsign: r.callsign,
+            display_name: r.display_name,
+            created_at: r.created_at,
+        }
+    }
+}
+
+/// A comment with its author's display info.
+#[derive(Debug, Clone, FromRow)]
+pub struct CommentRow {
+    pub id: Uuid,
+    pub activity_id: Uuid,
+    pub user_id: Uuid,
+    pub callsign: String,
+    pub display_name: Option<String>,
+    pub parent_id: Option<Uuid>,
+    pub body: String,
+    pub created_at: DateTime<Utc>,
+    pub deleted_at: Option<DateTime<Utc>>,
+}
+
+/// Response for a comment. Deleted comments keep their place in the thread
+/// but lose their author and body.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommentResponse {
+    pub id: Uuid,
+    pub parent_id: Option<Uuid>,
+    pub user_id: Option<Uuid>,
+    pub callsign: Option<String>,
+    pub display_name: Option<String>,
+    pub body: Option<String>,
+    pub deleted: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<CommentRow> for CommentResponse {
+    fn from(c: CommentRow) -> Self {
+        if c.deleted_at.is_some() {
+            return Self {
+                id: c.id,
+                parent_id: c.parent_id,
+                user_id: None,
+                callsign: None,
+                display_name: None,
+                body: None,
+                deleted: true,
+                created_at: c.created_at,
+            };
+        }
+
+        Self {
+            id: c.id,
+            parent_id: c.parent_id,
+            user_id: Some(c.user_id),
+            callsign: Some(c.callsign),
+            display_name: c.display_name,
+            body: Some(c.body),
+            deleted: false,
+            created_at: c.created_at,
+        }
+    }
+}
+
+/// Request body for POST /v1/activities/:id/comments.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateCommentRequest {
+    pub body: String,
+    /// Comment being replied to, on the same activity.
+    pub parent_id: Option<Uuid>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reaction_kind_round_trip() {
+        for kind in [
+            ReactionKind::SeventyThree,
+            ReactionKind::Congrats,
+            ReactionKind::Like,
+            ReactionKind::Fire,
+            ReactionKind::Wow,
+        ] {
+            assert_eq!(ReactionKind::parse(kind.as_str()), Some(kind));
+            let json = serde_json::to_string(&kind).unwrap();
+            assert_eq!(json, format!("\"{}\"", kind.as_str()));
+        }
+        assert_eq!(ReactionKind::parse("heart"), None);
+    }
+
+    #[test]
+    fn test_deleted_comment_hides_author_and_body() {
+        let row = CommentRow {
+            id: Uuid::new_v4(),
+            activity_id: Uuid::new_v4(),
+            user_id: Uuid::new_v4(),
+            callsign: "W1ABC".to_string(),
+            display_name: Some("Alice".to_string()),
+            parent_id: None,
+            body: "73!".to_string(),
+            created_at: Utc::now(),
+            deleted_at: Some(Utc::now()),
+        };
+
+        let response = CommentResponse::from(row);
+        assert!(response.deleted);
+        assert_eq!(response.body, None);
+        assert_eq!(response.callsign, None);
+    }
+}
@@ -0,0 +1,238 @@
+use chrono::{DateTime, Datelike, Months, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+use crate::error::AppError;
+
+/// A template period as `(start, end)`.
+pub type Period = (DateTime<Utc>, DateTime<Utc>);
+
+/// How often a template instantiates a new challenge.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Recurrence {
+    Monthly,
+    Yearly,
+}
+
+impl Recurrence {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Monthly => "monthly",
+            Self::Yearly => "yearly",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "monthly" => Some(Self::Monthly),
+            "yearly" => Some(Self::Yearly),
+            _ => None,
+        }
+    }
+
+    fn months(self) -> u32 {
+        match self {
+            Self::Monthly => 1,
+            Self::Yearly => 12,
+        }
+    }
+
+    /// Start of period `n` of a series whose first period starts at
+    /// `anchor`. Counted from the anchor rather than the previous period so
+    /// a series anchored on the 31st goes back to the 31st after February.
+    /// `None` if the date is out of range.
+    pub fn period_start(self, anchor: DateTime<Utc>, n: u32) -> Option<DateTime<Utc>> {
+        anchor.checked_add_months(Months::new(n.checked_mul(self.months())?))
+    }
+
+    /// Index of the period starting at `start` in the series anchored at
+    /// `anchor`.
+    fn period_index(self, anchor: DateTime<Utc>, start: DateTime<Utc>) -> Option<u32> {
+        let month = |d: DateTime<Utc>| i64::from(d.year()) * 12 + i64::from(d.month0());
+        let months = month(start) - month(anchor);
+        u32::try_from(months / i64::from(self.months())).ok()
+    }
+
+    /// Human-readable label for the period starting at `start`, substituted
+    /// for `{period}` in the template name.
+    pub fn period_label(self, start: DateTime<Utc>) -> String {
+        match self {
+            Self::Monthly => start.format("%B %Y").to_string(),
+            Self::Yearly => start.format("%Y").to_string(),
+        }
+    }
+}
+
+/// Database row for challenge_templates.
+#[derive(Debug, Clone, FromRow)]
+pub struct ChallengeTemplate {
+    pub id: Uuid,
+    pub name_template: String,
+    pub source_challenge_id: Uuid,
+    pub last_challenge_id: Option<Uuid>,
+    pub recurrence: String,
+    pub first_period_start: DateTime<Utc>,
+    pub next_period_start: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl ChallengeTemplate {
+    pub fn recurrence(&self) -> Recurrence {
+        Recurrence::parse(&self.recurrence).unwrap_or(Recurrence::Monthly)
+    }
+
+    /// Period to instantiate at `now` as `(start, end)`: the latest period
+    /// that has started, skipping any periods missed while the server was
+    /// down. `None` if the next period hasn't started yet.
+    pub fn due_period(&self, now: DateTime<Utc>) -> Result<Option<Period>, AppError> {
+        if self.next_period_start > now {
+            return Ok(None);
+        }
+
+        let recurrence = self.recurrence();
+        let anchor = self.first_period_start;
+        let out_of_range = || AppError::Internal("Template period is out of range".to_string());
+        let mut n = recurrence
+            .period_index(anchor, self.next_period_start)
+            .ok_or_else(out_of_range)?;
+        let mut start = self.next_period_start;
+        loop {
+            n = n.checked_add(1).ok_or_else(out_of_range)?;
+            let end = recurrence
+                .period_start(anchor, n)
+                .ok_or_else(out_of_range)?;
+            if end > now {
+                return Ok(Some((start, end)));
+            }
+            start = end;
+        }
+    }
+
+    pub fn instance_name(&self, period_start: DateTime<Utc>) -> String {
+        self.name_template
+            .replace("{period}", &self.recurrence().period_label(period_start))
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChallengeTemplateResponse {
+    pub id: Uuid,
+    pub name_template: String,
+    pub source_challenge_id: Uuid,
+    pub last_challenge_id: Option<Uuid>,
+    pub recurrence: Recurrence,
+    pub first_period_start: DateTime<Utc>,
+    pub next_period_start: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<ChallengeTemplate> for ChallengeTemplateResponse {
+    fn from(t: ChallengeTemplate) -> Self {
+        Self {
+            recurrence: t.recurrence(),
+            id: t.id,
+            name_template: t.name_template,
+            source_challenge_id: t.source_challenge_id,
+            last_challenge_id: t.last_challenge_id,
+            first_period_start: t.first_period_start,
+            next_period_start: t.next_period_start,
+            created_at: t.created_at,
+            updated_at: t.updated_at,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateChallengeTemplateRequest {
+    pub source_challenge_id: Uuid,
+    /// Instance name; `{period}` is replaced with e.g. "March 2026" or "2026".
+    pub name_template: String,
+    pub recurrence: Recurrence,
+    /// Start of the first period to instantiate.
+    pub first_period_start: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct CloneChallengeRequest {
+    /// Name for the copy; defaults to "<source name> (copy)".
+    pub name: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn template(
+        recurrence: Recurrence,
+        first: DateTime<Utc>,
+        next: DateTime<Utc>,
+    ) -> ChallengeTemplate {
+        ChallengeTemplate {
+            id: Uuid::nil(),
+            name_template: "Monthly CW Sprint {period}".to_string(),
+            source_challenge_id: Uuid::nil(),
+            last_challenge_id: None,
+            recurrence: recurrence.as_str().to_string(),
+            first_period_start: first,
+            next_period_start: next,
+            created_at: first,
+            updated_at: first,
+        }
+    }
+
+    #[test]
+    fn test_period_start() {
+        let jan31 = Utc.with_ymd_and_hms(2026, 1, 31, 0, 0, 0).unwrap();
+        let day = |m, d| Utc.with_ymd_and_hms(2026, m, d, 0, 0, 0).unwrap();
+
+        // Month-end anchors clamp in short months without drifting
+        assert_eq!(Recurrence::Monthly.period_start(jan31, 0), Some(jan31));
+        assert_eq!(Recurrence::Monthly.period_start(jan31, 1), Some(day(2, 28)));
+        assert_eq!(Recurrence::Monthly.period_start(jan31, 2), Some(day(3, 31)));
+        assert_eq!(
+            Recurrence::Yearly.period_start(day(2, 1), 1),
+            Some(Utc.with_ymd_and_hms(2027, 2, 1, 0, 0, 0).unwrap())
+        );
+        assert_eq!(Recurrence::Yearly.period_start(jan31, u32::MAX), None);
+    }
+
+    #[test]
+    fn test_due_period_skips_missed_periods() {
+        let jan = Utc.with_ymd_and_hms(2026, 1, 1, 0, 0, 0).unwrap();
+        let t = template(Recurrence::Monthly, jan, jan);
+
+        assert_eq!(
+            t.due_period(jan - chrono::Duration::seconds(1)).unwrap(),
+            None
+        );
+
+        let mid_march = Utc.with_ymd_and_hms(2026, 3, 15, 0, 0, 0).unwrap();
+        let (start, end) = t.due_period(mid_march).unwrap().unwrap();
+        assert_eq!(start, Utc.with_ymd_and_hms(2026, 3, 1, 0, 0, 0).unwrap());
+        assert_eq!(end, Utc.with_ymd_and_hms(2026, 4, 1, 0, 0, 0).unwrap());
+        assert_eq!(t.instance_name(start), "Monthly CW Sprint March 2026");
+    }
+
+    #[test]
+    fn test_due_period_counts_from_anchor() {
+        let day = |m, d| Utc.with_ymd_and_hms(2026, m, d, 0, 0, 0).unwrap();
+        let t = template(Recurrence::Monthly, day(1, 31), day(2, 28));
+
+        assert_eq!(
+            t.due_period(day(3, 1)).unwrap(),
+            Some((day(2, 28), day(3, 31)))
+        );
+        assert_eq!(
+            t.due_period(day(4, 2)).unwrap(),
+            Some((day(3, 31), day(4, 30)))
+        );
+    }
+}
@@ -3,6 +3,8 @@ use serde::Serialize;
 use sqlx::FromRow;
 use uuid::Uuid;
 
+use super::UserSummary;
+
 #[derive(Debug, Clone, FromRow)]
 pub struct User {
     pub id: Uuid,
@@ -35,12 +37,12 @@ pub struct UserSearchResponse {
     pub display_name: Option<String>,
 }
 
-impl From<User> for UserSearchResponse {
-    fn from(user: User) -> Self {
+impl From<UserSummary> for UserSearchResponse {
+    fn from(user: UserSummary) -> Self {
         Self {
             user_id: user.id,
             callsign: user.callsign,
-            display_name: None,
+            display_name: user.display_name,
         }
     }
 }
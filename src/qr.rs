@@ -0,0 +1,187 @@
+//! QR code rendering for invite links.
+
+use chrono::{DateTime, Utc};
+use qrcode::render::svg;
+use qrcode::{Color, EcLevel, QrCode};
+use serde::Deserialize;
+
+use crate::error::AppError;
+
+/// Quiet zone around the code, in modules, as required by the QR spec.
+const QUIET_ZONE: usize = 4;
+
+pub const DEFAULT_QR_SIZE: u32 = 256;
+pub const MIN_QR_SIZE: u32 = 64;
+pub const MAX_QR_SIZE: u32 = 2048;
+
+/// Longest a QR code response may be cached, in seconds.
+const MAX_CACHE_SECONDS: i64 = 24 * 60 * 60;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum QrFormat {
+    #[default]
+    Svg,
+    Png,
+}
+
+impl QrFormat {
+    pub fn content_type(self) -> &'static str {
+        match self {
+            Self::Svg => "image/svg+xml",
+            Self::Png => "image/png",
+        }
+    }
+}
+
+/// Error-correction level: how much of the code can be damaged or covered
+/// and still scan (L 7%, M 15%, Q 25%, H 30%).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+pub enum QrErrorCorrection {
+    L,
+    #[default]
+    M,
+    Q,
+    H,
+}
+
+impl From<QrErrorCorrection> for EcLevel {
+    fn from(ec: QrErrorCorrection) -> Self {
+        match ec {
+            QrErrorCorrection::L => EcLevel::L,
+            QrErrorCorrection::M => EcLevel::M,
+            QrErrorCorrection::Q => EcLevel::Q,
+            QrErrorCorrection::H => EcLevel::H,
+        }
+    }
+}
+
+/// Query parameters for QR code endpoints.
+#[derive(Debug, Default, Deserialize)]
+pub struct QrQuery {
+    pub format: Option<QrFormat>,
+    /// Minimum image width and height in pixels.
+    pub size: Option<u32>,
+    pub ecc: Option<QrErrorCorrection>,
+}
+
+impl QrQuery {
+    pub fn size(&self) -> Result<u32, AppError> {
+        let size = self.size.unwrap_or(DEFAULT_QR_SIZE);
+        if !(MIN_QR_SIZE..=MAX_QR_SIZE).contains(&size) {
+            return Err(AppError::Validation {
+                message: format!("size must be between {} and {}", MIN_QR_SIZE, MAX_QR_SIZE),
+            });
+        }
+        Ok(size)
+    }
+}
+
+/// Render `data` as a QR code image. Returns the content type and bytes.
+pub fn render(data: &str, query: &QrQuery) -> Result<(&'static str, Vec<u8>), AppError> {
+    let size = query.size()?;
+    let format = query.format.unwrap_or_default();
+    let code = QrCode::with_error_correction_level(data, query.ecc.unwrap_or_default().into())
+        .map_err(|e| AppError::Internal(format!("QR encoding failed: {}", e)))?;
+
+    let bytes = match format {
+        QrFormat::Svg => code
+            .render::<svg::Color>()
+            .min_dimensions(size, size)
+            .quiet_zone(true)
+            .build()
+            .into_bytes(),
+        QrFormat::Png => render_png(&code, size)?,
+    };
+
+    Ok((format.content_type(), bytes))
+}
+
+/// Grayscale PNG with whole-pixel modules, at least `size` pixels wide.
+fn render_png(code: &QrCode, size: u32) -> Result<Vec<u8>, AppError> {
+    let modules = code.width();
+    let colors = code.to_colors();
+    let total = modules + 2 * QUIET_ZONE;
+    let scale = (size as usize).div_ceil(total);
+    let width = total * scale;
+
+    let mut pixels = vec![255u8; width * width];
+    for (i, color) in colors.iter().enumerate() {
+        if *color != Color::Dark {
+            continue;
+        }
+        let x0 = (i % modules + QUIET_ZONE) * scale;
+        let y0 = (i / modules + QUIET_ZONE) * scale;
+        for y in y0..y0 + scale {
+            pixels[y * width + x0..y * width + x0 + scale].fill(0);
+        }
+    }
+
+    let mut out = Vec::new();
+    let mut encoder = png::Encoder::new(&mut out, width as u32, width as u32);
+    encoder.set_color(png::ColorType::Grayscale);
+    encoder.set_depth(png::BitDepth::Eight);
+    let png_error =
+        |e: png::EncodingError| AppError::Internal(format!("PNG encoding failed: {}", e));
+    let mut writer = encoder.write_header().map_err(png_error)?;
+    writer.write_image_data(&pixels).map_err(png_error)?;
+    writer.finish().map_err(png_error)?;
+
+    Ok(out)
+}
+
+/// `max-age` for the QR code of an invite expiring at `expires_at`: a day
+/// at most, and never past the expiry.
+pub fn cache_max_age(expires_at: Option<DateTime<Utc>>, now: DateTime<Utc>) -> i64 {
+    expires_at.map_or(MAX_CACHE_SECONDS, |at| {
+        (at - now).num_seconds().clamp(0, MAX_CACHE_SECONDS)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_qr() {
+        let url = "https://activities.carrierwave.app/invite/inv_abc";
+
+        let (content_type, svg) = render(url, &QrQuery::default()).unwrap();
+        assert_eq!(content_type, "image/svg+xml");
+        assert!(String::from_utf8(svg).unwrap().contains("<svg"));
+
+        let query = QrQuery {
+            format: Some(QrFormat::Png),
+            size: Some(200),
+            ecc: Some(QrErrorCorrection::H),
+        };
+        let (content_type, png) = render(url, &query).unwrap();
+        assert_eq!(content_type, "image/png");
+        assert_eq!(&png[1..4], b"PNG");
+
+        let too_big = QrQuery {
+            size: Some(MAX_QR_SIZE + 1),
+            ..Default::default()
+        };
+        assert!(render(url, &too_big).is_err());
+    }
+
+    #[test]
+    fn test_cache_max_age() {
+        let now = Utc::now();
+
+        assert_eq!(cache_max_age(None, now), MAX_CACHE_SECONDS);
+        assert_eq!(
+            cache_max_age(Some(now + chrono::Duration::days(30)), now),
+            MAX_CACHE_SECONDS
+        );
+        assert_eq!(
+            cache_max_age(Some(now + chrono::Duration::minutes(10)), now),
+            600
+        );
+        assert_eq!(
+            cache_max_age(Some(now - chrono::Duration::minutes(1)), now),
+            0
+        );
+    }
+}
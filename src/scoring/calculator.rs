@@ -0,0 +1,431 @@
+use std::collections::HashSet;
+
+use crate::models::{GoalStatus, ScoreBasis};
+
+/// Calculate the score for a set of completed goals and a current value
+/// according to the challenge's `scoring.method`, plus any
+/// `scoring.categoryBonuses` for categories completed in full.
+pub fn calculate_score(
+    config: &serde_json::Value,
+    completed_goals: &[String],
+    current_value: i32,
+) -> i32 {
+    let scoring = config.get("scoring");
+    let setting = |key: &str| scoring.and_then(|s| s.get(key)).and_then(|v| v.as_str());
+    let method = setting("method").unwrap_or("count");
+
+    let completed: HashSet<&str> = completed_goals.iter().map(String::as_str).collect();
+    let items = goal_items(config);
+    let completed_items: Vec<&serde_json::Value> = items
+        .iter()
+        .filter(|item| item_id(item).is_some_and(|id| completed.contains(id)))
+        .collect();
+    let item_points = || {
+        completed_items
+            .iter()
+            .map(|item| goal_points(item))
+            .sum::<i64>()
+    };
+
+    let score = match method {
+        // A percentage stays within 0-100, so no bonuses
+        "percentage" => {
+            let total = items.len();
+            return if total > 0 {
+                (completed_goals.len() as f64 / total as f64 * 100.0) as i32
+            } else {
+                0
+            };
+        }
+        "count" => completed_goals.len() as i64,
+        "points" => current_value as i64,
+        "weighted" => item_points(),
+        "multiplier" => {
+            let base = match setting("basePoints") {
+                Some("currentValue") => current_value as i64,
+                _ => item_points(),
+            };
+            let multipliers = match setting("multipliers") {
+                Some("goals") => completed_items.len(),
+                _ => completed_items
+                    .iter()
+                    .filter_map(|item| goal_category(item))
+                    .collect::<HashSet<_>>()
+                    .len(),
+            };
+            base * multipliers as i64
+        }
+        _ => completed_goals.len() as i64,
+    };
+
+    let bonus = category_bonuses(config)
+        .filter(|(category, _)| {
+            items
+                .iter()
+                .filter(|item| goal_category(item) == Some(category))
+                .all(|item| item_id(item).is_some_and(|id| completed.contains(id)))
+        })
+        .map(|(_, points)| points)
+        .sum::<i64>();
+
+    (score + bonus).clamp(0, i32::MAX as i64) as i32
+}
+
+/// Items of a collection's `goals.items`.
+pub fn goal_items(config: &serde_json::Value) -> &[serde_json::Value] {
+    config
+        .get("goals")
+        .and_then(|g| g.get("items"))
+        .and_then(|i| i.as_array())
+        .map(Vec::as_slice)
+        .unwrap_or_default()
+}
+
+fn item_id(item: &serde_json::Value) -> Option<&str> {
+    item.get("id").and_then(|id| id.as_str())
+}
+
+/// A goal item's `points`, 1 when not set.
+pub fn goal_points(item: &serde_json::Value) -> i64 {
+    item.get("points").and_then(|p| p.as_i64()).unwrap_or(1)
+}
+
+pub fn goal_category(item: &serde_json::Value) -> Option<&str> {
+    item.get("category").and_then(|c| c.as_str())
+}
+
+/// `scoring.categoryBonuses` as (category, points) pairs.
+fn category_bonuses(config: &serde_json::Value) -> impl Iterator<Item = (&str, i64)> {
+    config
+        .get("scoring")
+        .and_then(|s| s.get("categoryBonuses"))
+        .and_then(|b| b.as_object())
+        .into_iter()
+        .flatten()
+        .filter_map(|(category, points)| Some((category.as_str(), points.as_i64()?)))
+}
+
+/// Calculate completion percentage based on the challenge's goal type.
+pub fn calculate_percentage(
+    config: &serde_json::Value,
+    completed_goals: &[String],
+    current_value: i32,
+) -> f64 {
+    let goals = config.get("goals");
+    let goal_type = goals
+        .and_then(|g| g.get("type"))
+        .and_then(|t| t.as_str())
+        .unwrap_or("collection");
+
+    match goal_type {
+        "collection" => {
+            let total = get_total_goals(config);
+            if total > 0 {
+                completed_goals.len() as f64 / total as f64 * 100.0
+            } else {
+                0.0
+            }
+        }
+        "cumulative" => {
+            let target = goals
+                .and_then(|g| g.get("targetValue"))
+                .and_then(|t| t.as_i64())
+                .unwrap_or(100) as f64;
+            if target > 0.0 {
+                current_value as f64 / target * 100.0
+            } else {
+                0.0
+            }
+        }
+        _ => 0.0,
+    }
+}
+
+/// Goal statuses that count toward score and tiers, from
+/// `scoring.countedStatuses`. Every status counts by default.
+pub fn counted_statuses(config: &serde_json::Value) -> Vec<GoalStatus> {
+    let statuses: Vec<GoalStatus> = config
+        .get("scoring")
+        .and_then(|s| s.get("countedStatuses"))
+        .and_then(|s| s.as_array())
+        .map(|a| {
+            a.iter()
+                .filter_map(|s| s.as_str().and_then(GoalStatus::parse))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    if statuses.is_empty() {
+        vec![GoalStatus::Worked, GoalStatus::Lotw, GoalStatus::Qsl]
+    } else {
+        statuses
+    }
+}
+
+/// Worked when any completion counts, confirmed when only confirmations do.
+pub fn score_basis(config: &serde_json::Value) -> ScoreBasis {
+    if is_value_based(config) || counted_statuses(config).contains(&GoalStatus::Worked) {
+        ScoreBasis::Worked
+    } else {
+        ScoreBasis::Confirmed
+    }
+}
+
+/// Whether progress is measured by the reported current value rather than
+/// goals: the `points` method, or cumulative goals. The value has no
+/// confirmation status, so such a challenge is always scored on the worked
+/// basis.
+pub fn is_value_based(config: &serde_json::Value) -> bool {
+    let method = config
+        .get("scoring")
+        .and_then(|s| s.get("method"))
+        .and_then(|m| m.as_str());
+    let goal_type = config
+        .get("goals")
+        .and_then(|g| g.get("type"))
+        .and_then(|t| t.as_str());
+    method == Some("points") || goal_type == Some("cumulative")
+}
+
+/// Goals on a basis. Confirmed means a confirmation the challenge counts,
+/// or either kind when it counts worked goals.
+pub fn goals_on_basis(
+    config: &serde_json::Value,
+    goals: &[(String, GoalStatus)],
+    basis: ScoreBasis,
+) -> Vec<String> {
+    let counted = counted_statuses(config);
+    goals
+        .iter()
+        .filter(|(_, status)| match basis {
+            ScoreBasis::Worked => true,
+            ScoreBasis::Confirmed => {
+                status.is_confirmed()
+                    && (counted.contains(status) || counted.contains(&GoalStatus::Worked))
+            }
+        })
+        .map(|(goal, _)| goal.clone())
+        .collect()
+}
+
+/// Worked, confirmed and official scores.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Scores {
+    /// Score on the challenge's basis; tiers are awarded on this.
+    pub score: i32,
+    pub worked_score: i32,
+    pub confirmed_score: i32,
+}
+
+/// Score goals on both bases. A value-based challenge has no confirmed
+/// score, so it is 0.
+pub fn calculate_scores(
+    config: &serde_json::Value,
+    goals: &[(String, GoalStatus)],
+    current_value: i32,
+) -> Scores {
+    let worked_score = calculate_score(
+        config,
+        &goals_on_basis(config, goals, ScoreBasis::Worked),
+        current_value,
+    );
+    if is_value_based(config) {
+        return Scores {
+            score: worked_score,
+            worked_score,
+            confirmed_score: 0,
+        };
+    }
+
+    let confirmed_score = calculate_score(
+        config,
+        &goals_on_basis(config, goals, ScoreBasis::Confirmed),
+        current_value,
+    );
+    let score = match score_basis(config) {
+        ScoreBasis::Worked => worked_score,
+        ScoreBasis::Confirmed => confirmed_score,
+    };
+
+    Scores {
+        score,
+        worked_score,
+        confirmed_score,
+    }
+}
+
+pub fn get_total_goals(config: &serde_json::Value) -> usize {
+    goal_items(config).len()
+}
+
+/// Highest tier whose threshold the score meets, if any.
+pub fn determine_tier(config: &serde_json::Value, score: i32) -> Option<String> {
+    reached_tiers(config, score).pop()
+}
+
+/// All tier IDs whose threshold the score meets, in configuration order.
+pub fn reached_tiers(config: &serde_json::Value, score: i32) -> Vec<String> {
+    let Some(tiers) = config.get("tiers").and_then(|t| t.as_array()) else {
+        return vec![];
+    };
+
+    tiers
+        .iter()
+        .filter(|tier| {
+            tier.get("threshold")
+                .and_then(|t| t.as_i64())
+                .is_some_and(|threshold| score as i64 >= threshold)
+        })
+        .filter_map(|tier| tier.get("id").and_then(|id| id.as_str()))
+        .map(String::from)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn goals(ids: &[&str]) -> Vec<String> {
+        ids.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_calculate_score_methods() {
+        let config = json!({
+            "goals": { "type": "collection", "items": [{"id": "A"}, {"id": "B"}, {"id": "C"}, {"id": "D"}] },
+            "scoring": { "method": "percentage" }
+        });
+        assert_eq!(calculate_score(&config, &goals(&["A", "B"]), 0), 50);
+
+        let config = json!({ "scoring": { "method": "points" } });
+        assert_eq!(calculate_score(&config, &goals(&["A"]), 42), 42);
+
+        let config = json!({});
+        assert_eq!(calculate_score(&config, &goals(&["A", "B", "C"]), 0), 3);
+    }
+
+    #[test]
+    fn test_weighted_and_multiplier_scoring() {
+        let items = json!([
+            { "id": "K", "category": "NA", "points": 1 },
+            { "id": "VE", "category": "NA", "points": 1 },
+            { "id": "JA", "category": "AS", "points": 3 },
+            { "id": "VK", "category": "OC" }
+        ]);
+
+        let config = json!({
+            "goals": { "type": "collection", "items": items },
+            "scoring": { "method": "weighted" }
+        });
+        assert_eq!(calculate_score(&config, &goals(&["K", "JA", "VK"]), 0), 5);
+        // Goals that aren't items are worth nothing
+        assert_eq!(calculate_score(&config, &goals(&["ZZ"]), 0), 0);
+
+        // Item points times distinct categories
+        let config = json!({
+            "goals": { "type": "collection", "items": items },
+            "scoring": { "method": "multiplier" }
+        });
+        assert_eq!(calculate_score(&config, &goals(&["K", "VE"]), 0), 2);
+        assert_eq!(calculate_score(&config, &goals(&["K", "VE", "JA"]), 0), 10);
+
+        // Reported QSO points times completed goals
+        let config = json!({
+            "goals": { "type": "collection", "items": items },
+            "scoring": {
+                "method": "multiplier",
+                "basePoints": "currentValue",
+                "multipliers": "goals"
+            }
+        });
+        assert_eq!(
+            calculate_score(&config, &goals(&["K", "JA", "VK"]), 40),
+            120
+        );
+    }
+
+    #[test]
+    fn test_category_bonuses() {
+        let config = json!({
+            "goals": { "type": "collection", "items": [
+                { "id": "K", "category": "NA" },
+                { "id": "VE", "category": "NA" },
+                { "id": "JA", "category": "AS" }
+            ] },
+            "scoring": { "method": "count", "categoryBonuses": { "NA": 10, "AS": 5 } }
+        });
+        assert_eq!(calculate_score(&config, &goals(&["K"]), 0), 1);
+        assert_eq!(calculate_score(&config, &goals(&["K", "VE"]), 0), 12);
+        assert_eq!(calculate_score(&config, &goals(&["K", "VE", "JA"]), 0), 18);
+    }
+
+    #[test]
+    fn test_determine_tier() {
+        let config = json!({
+            "tiers": [
+                { "id": "tier-25", "threshold": 25 },
+                { "id": "tier-50", "threshold": 50 }
+            ]
+        });
+        assert_eq!(determine_tier(&config, 10), None);
+        assert_eq!(determine_tier(&config, 30).as_deref(), Some("tier-25"));
+        assert_eq!(determine_tier(&config, 50).as_deref(), Some("tier-50"));
+        assert_eq!(reached_tiers(&config, 50), vec!["tier-25", "tier-50"]);
+    }
+
+    #[test]
+    fn test_calculate_scores_by_status() {
+        let goals = vec![
+            ("A".to_string(), GoalStatus::Worked),
+            ("B".to_string(), GoalStatus::Lotw),
+            ("C".to_string(), GoalStatus::Qsl),
+        ];
+
+        let config = json!({});
+        assert_eq!(score_basis(&config), ScoreBasis::Worked);
+        assert_eq!(
+            calculate_scores(&config, &goals, 0),
+            Scores {
+                score: 3,
+                worked_score: 3,
+                confirmed_score: 2
+            }
+        );
+
+        let config = json!({ "scoring": { "countedStatuses": ["lotw"] } });
+        assert_eq!(score_basis(&config), ScoreBasis::Confirmed);
+        assert_eq!(
+            calculate_scores(&config, &goals, 0),
+            Scores {
+                score: 1,
+                worked_score: 3,
+                confirmed_score: 1
+            }
+        );
+    }
+
+    #[test]
+    fn test_calculate_scores_points_method() {
+        let worked = vec![("A".to_string(), GoalStatus::Worked)];
+
+        // The current value has no confirmation status
+        let config = json!({ "scoring": { "method": "points" } });
+        assert_eq!(
+            calculate_scores(&config, &worked, 42),
+            Scores {
+                score: 42,
+                worked_score: 42,
+                confirmed_score: 0
+            }
+        );
+
+        let config = json!({ "scoring": { "method": "points", "countedStatuses": ["qsl"] } });
+        assert_eq!(score_basis(&config), ScoreBasis::Worked);
+        assert_eq!(calculate_scores(&config, &worked, 42).score, 42);
+
+        let config = json!({ "goals": { "type": "cumulative", "targetValue": 100 } });
+        assert!(is_value_based(&config));
+        assert_eq!(calculate_scores(&config, &worked, 42).confirmed_score, 0);
+    }
+}
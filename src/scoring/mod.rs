@@ -0,0 +1,5 @@
+pub mod calculator;
+pub mod validation;
+
+pub use calculator::*;
+pub use validation::*;
@@ -0,0 +1,197 @@
+use std::collections::HashSet;
+
+use crate::models::GoalStatus;
+
+use super::calculator::{goal_category, goal_items};
+
+pub const SCORING_METHODS: &[&str] = &["count", "percentage", "points", "weighted", "multiplier"];
+
+/// Largest `points` a goal item or category bonus may be worth.
+pub const MAX_POINTS: i64 = 1_000_000;
+
+/// Check the scoring settings of a challenge configuration: `scoring`
+/// and the `points` and `category` of goal items. Returns a message for the
+/// validation error.
+pub fn validate_scoring(config: &serde_json::Value) -> Result<(), String> {
+    let items = goal_items(config);
+    for (i, item) in items.iter().enumerate() {
+        if let Some(points) = item.get("points") {
+            if !points
+                .as_i64()
+                .is_some_and(|p| (0..=MAX_POINTS).contains(&p))
+            {
+                return Err(format!(
+                    "goals.items[{}].points must be an integer from 0 to {}",
+                    i, MAX_POINTS
+                ));
+            }
+        }
+        if item.get("category").is_some_and(|c| !c.is_string()) {
+            return Err(format!("goals.items[{}].category must be a string", i));
+        }
+    }
+    let categories: HashSet<&str> = items.iter().filter_map(goal_category).collect();
+
+    let Some(scoring) = config.get("scoring") else {
+        return Ok(());
+    };
+    let scoring = scoring
+        .as_object()
+        .ok_or_else(|| "scoring must be an object".to_string())?;
+
+    let method = match scoring.get("method") {
+        None => "count",
+        Some(method) => method
+            .as_str()
+            .filter(|m| SCORING_METHODS.contains(m))
+            .ok_or_else(|| {
+                format!(
+                    "scoring.method must be one of {}",
+                    SCORING_METHODS.join(", ")
+                )
+            })?,
+    };
+
+    if let Some(statuses) = scoring.get("countedStatuses") {
+        let valid = statuses.as_array().is_some_and(|a| {
+            a.iter()
+                .all(|s| s.as_str().and_then(GoalStatus::parse).is_some())
+        });
+        if !valid {
+            return Err("scoring.countedStatuses must be a list of worked, lotw, qsl".to_string());
+        }
+    }
+
+    // Weights were never applied; goal item points replace them
+    if scoring.contains_key("weights") {
+        return Err(
+            "scoring.weights is not supported; set points on goals.items instead".to_string(),
+        );
+    }
+
+    if matches!(method, "weighted" | "multiplier") && items.is_empty() {
+        return Err(format!("scoring.method {} needs goals.items", method));
+    }
+
+    let option = |key: &str, allowed: &[&str]| -> Result<Option<&str>, String> {
+        let Some(value) = scoring.get(key) else {
+            return Ok(None);
+        };
+        if method != "multiplier" {
+            return Err(format!(
+                "scoring.{} only applies to the multiplier method",
+                key
+            ));
+        }
+        value
+            .as_str()
+            .filter(|v| allowed.contains(v))
+            .map(Some)
+            .ok_or_else(|| format!("scoring.{} must be one of {}", key, allowed.join(", ")))
+    };
+    option("basePoints", &["goals", "currentValue"])?;
+    let multipliers = option("multipliers", &["categories", "goals"])?;
+
+    let counts_categories = multipliers.unwrap_or("categories") == "categories";
+    if method == "multiplier" && counts_categories && categories.is_empty() {
+        return Err(
+            "scoring.method multiplier counts distinct categories, but no goals.items have a category"
+                .to_string(),
+        );
+    }
+
+    if let Some(bonuses) = scoring.get("categoryBonuses") {
+        if method == "percentage" {
+            return Err(
+                "scoring.categoryBonuses can't be used with the percentage method".to_string(),
+            );
+        }
+        let bonuses = bonuses
+            .as_object()
+            .ok_or_else(|| "scoring.categoryBonuses must be an object".to_string())?;
+        for (category, points) in bonuses {
+            if !categories.contains(category.as_str()) {
+                return Err(format!(
+                    "scoring.categoryBonuses.{}: no goals.items have this category",
+                    category
+                ));
+            }
+            if !points
+                .as_i64()
+                .is_some_and(|p| (0..=MAX_POINTS).contains(&p))
+            {
+                return Err(format!(
+                    "scoring.categoryBonuses.{} must be an integer from 0 to {}",
+                    category, MAX_POINTS
+                ));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_validate_scoring() {
+        let items = json!([
+            { "id": "K", "category": "NA", "points": 1 },
+            { "id": "JA", "category": "AS", "points": 3 }
+        ]);
+        let config = |scoring: serde_json::Value| {
+            json!({
+                "goals": { "type": "collection", "items": items },
+                "scoring": scoring
+            })
+        };
+
+        assert!(validate_scoring(&json!({})).is_ok());
+        assert!(validate_scoring(&config(json!({ "method": "weighted" }))).is_ok());
+        assert!(validate_scoring(&config(json!({
+            "method": "multiplier",
+            "basePoints": "currentValue",
+            "categoryBonuses": { "NA": 10 },
+            "displayFormat": "{value} points"
+        })))
+        .is_ok());
+
+        assert!(validate_scoring(&config(json!({ "method": "rarity" }))).is_err());
+        assert!(validate_scoring(&config(json!({ "countedStatuses": ["eqsl"] }))).is_err());
+        assert!(validate_scoring(&config(json!({ "basePoints": "goals" }))).is_err());
+        assert!(validate_scoring(&config(json!({
+            "method": "weighted",
+            "weights": [{ "rule": "band", "weight": 2 }]
+        })))
+        .is_err());
+        assert!(validate_scoring(&config(json!({
+            "method": "multiplier",
+            "multipliers": "entities"
+        })))
+        .is_err());
+        assert!(validate_scoring(&config(json!({ "categoryBonuses": { "EU": 5 } }))).is_err());
+        assert!(validate_scoring(&config(json!({ "categoryBonuses": { "NA": -5 } }))).is_err());
+        assert!(validate_scoring(&config(json!({
+            "method": "percentage",
+            "categoryBonuses": { "NA": 5 }
+        })))
+        .is_err());
+        assert!(validate_scoring(&json!({
+            "goals": { "type": "cumulative", "targetValue": 100 },
+            "scoring": { "method": "weighted" }
+        }))
+        .is_err());
+        assert!(validate_scoring(&json!({
+            "goals": { "type": "collection", "items": [{ "id": "K", "points": 1.5 }] }
+        }))
+        .is_err());
+        assert!(validate_scoring(&json!({
+            "goals": { "type": "collection", "items": [{ "id": "K" }] },
+            "scoring": { "method": "multiplier" }
+        }))
+        .is_err());
+    }
+}
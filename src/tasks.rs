@@ -0,0 +1,78 @@
+use std::time::Duration;
+
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::config::Config;
+use crate::db;
+use crate::error::AppError;
+
+/// How often periodic maintenance runs.
+const MAINTENANCE_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// Spawn the background maintenance loop. Every job is idempotent, so it is
+/// safe for several server instances to run it against the same database.
+pub fn spawn_maintenance(pool: PgPool, config: Config) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(MAINTENANCE_INTERVAL);
+        loop {
+            interval.tick().await;
+            run_maintenance(&pool, &config).await;
+        }
+    });
+}
+
+async fn run_maintenance(pool: &PgPool, config: &Config) {
+    match db::purge_expired_challenges(pool, config.challenge_retention_days).await {
+        Ok(0) => {}
+        Ok(count) => tracing::info!(count, "Purged soft-deleted challenges past retention"),
+        Err(e) => tracing::warn!(error = ?e, "Failed to purge expired challenges"),
+    }
+
+    match db::instantiate_due_templates(pool).await {
+        Ok(0) => {}
+        Ok(count) => tracing::info!(count, "Instantiated recurring challenges"),
+        Err(e) => tracing::warn!(error = ?e, "Failed to instantiate challenge templates"),
+    }
+
+    match db::expire_friend_requests(pool, config.friend_request_expiry_days).await {
+        Ok(0) => {}
+        Ok(count) => tracing::info!(count, "Expired stale friend requests"),
+        Err(e) => tracing::warn!(error = ?e, "Failed to expire friend requests"),
+    }
+
+    match db::purge_expired_idempotency_keys(pool, config.idempotency_key_ttl_hours).await {
+        Ok(0) => {}
+        Ok(count) => tracing::info!(count, "Purged expired idempotency keys"),
+        Err(e) => tracing::warn!(error = ?e, "Failed to purge idempotency keys"),
+    }
+
+    match db::list_pending_rescores(pool).await {
+        Ok(ids) => {
+            for id in ids {
+                if let Err(e) = rescore_pending(pool, id).await {
+                    tracing::warn!(error = ?e, challenge_id = %id, "Failed to rescore challenge");
+                }
+            }
+        }
+        Err(e) => tracing::warn!(error = ?e, "Failed to list pending rescores"),
+    }
+}
+
+/// Recompute the stored scores of a challenge queued by a migration, then
+/// take it off the queue. Nothing is awarded, issued or posted: the scores
+/// change meaning, not what participants achieved. It stays queued if the
+/// rescore fails, so the next run retries it.
+async fn rescore_pending(pool: &PgPool, challenge_id: Uuid) -> Result<(), AppError> {
+    if let Some(challenge) = db::get_challenge_including_deleted(pool, challenge_id).await? {
+        let (summary, _) =
+            db::rescore_challenge(pool, &challenge, db::RESCORE_BATCH_SIZE, false).await?;
+        tracing::info!(
+            %challenge_id,
+            participants = summary.participants_rescored,
+            "Rescored queued challenge"
+        );
+    }
+
+    db::delete_pending_rescore(pool, challenge_id).await
+}
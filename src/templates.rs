@@ -0,0 +1,133 @@
+//! Server-rendered HTML pages for links opened in a browser. Templates live
+//! in `templates/` and are compiled in by askama, which HTML-escapes every
+//! interpolated value.
+
+use askama::Template;
+use axum::{
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+};
+
+use crate::award::ApplicationRow;
+use crate::models::LeaderboardEntry;
+
+/// Longest description used for Open Graph previews.
+const SUMMARY_LENGTH: usize = 200;
+
+#[derive(Template)]
+#[template(path = "friend_invite.html")]
+pub struct FriendInvitePage {
+    pub title: String,
+    pub heading: String,
+    pub summary: String,
+    pub deep_link: Option<String>,
+    /// QR code for the invite, so it can be opened on a phone. Only set
+    /// while the invite is active.
+    pub qr_url: Option<String>,
+}
+
+/// Badge shown on a challenge page.
+pub struct PageBadge {
+    pub name: String,
+    pub image_url: String,
+}
+
+#[derive(Template)]
+#[template(path = "challenge.html")]
+pub struct ChallengePage {
+    pub title: String,
+    pub summary: String,
+    pub name: String,
+    pub description: String,
+    /// Shown above the description, e.g. when an invite is no longer valid.
+    pub notice: Option<String>,
+    pub participant_count: i64,
+    pub leaderboard: Vec<LeaderboardEntry>,
+    pub badges: Vec<PageBadge>,
+    pub deep_link: Option<String>,
+    /// QR code for the invite the page was opened from, while it is valid.
+    pub qr_url: Option<String>,
+}
+
+/// A page with only a heading and a message, for errors and missing links.
+#[derive(Template)]
+#[template(path = "message.html")]
+pub struct MessagePage {
+    pub title: String,
+    pub summary: String,
+    pub deep_link: Option<String>,
+    pub qr_url: Option<String>,
+}
+
+impl MessagePage {
+    pub fn new(title: &str, summary: &str) -> Self {
+        Self {
+            title: title.to_string(),
+            summary: summary.to_string(),
+            deep_link: None,
+            qr_url: None,
+        }
+    }
+}
+
+/// Printable award application: every goal with its supporting contact.
+/// Standalone rather than on `base.html` so it prints on white paper.
+#[derive(Template)]
+#[template(path = "award_application.html")]
+pub struct AwardApplicationPage {
+    pub challenge_name: String,
+    pub author: Option<String>,
+    pub callsign: String,
+    /// e.g. "50 of 50 goals, 48 confirmed".
+    pub summary: String,
+    pub generated_on: String,
+    pub rows: Vec<ApplicationRow>,
+}
+
+/// Render a page as an HTML response.
+pub fn render(status: StatusCode, page: &impl Template) -> Response {
+    match page.render() {
+        Ok(html) => (
+            status,
+            [(header::CONTENT_TYPE, "text/html; charset=utf-8")],
+            html,
+        )
+            .into_response(),
+        Err(e) => {
+            tracing::error!(error = ?e, "Failed to render page");
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        }
+    }
+}
+
+/// Shorten `text` to at most `SUMMARY_LENGTH` characters for previews.
+pub fn summarize(text: &str) -> String {
+    let text = text.trim();
+    if text.chars().count() <= SUMMARY_LENGTH {
+        return text.to_string();
+    }
+    let mut summary: String = text.chars().take(SUMMARY_LENGTH - 1).collect();
+    summary.push('…');
+    summary
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pages_escape_html() {
+        let page = MessagePage::new("<script>alert(1)</script>", "a & b");
+        let html = page.render().unwrap();
+        assert!(!html.contains("<script>alert"));
+        assert!(html.contains("&lt;script&gt;"));
+        assert!(html.contains("a &amp; b"));
+    }
+
+    #[test]
+    fn test_summarize() {
+        assert_eq!(summarize("  short  "), "short");
+        let long = "x".repeat(300);
+        assert_eq!(summarize(&long).chars().count(), SUMMARY_LENGTH);
+    }
+}
@@ -50,13 +50,13 @@ export async function listChallenges(params?: {
   if (params?.type) searchParams.set('type', params.type);
   if (params?.active !== undefined) searchParams.set('active', String(params.active));
 
-  const url = `${API_BASE}/challenges${searchParams.toString() ? '?' + searchParams : ''}`;
+  const url = `${API_BASE}/admin/challenges${searchParams.toString() ? '?' + searchParams : ''}`;
   const response = await fetch(url, { headers: authHeaders() });
   return handleResponse(response);
 }
 
 export async function getChallenge(id: string): Promise<Challenge> {
-  const response = await fetch(`${API_BASE}/challenges/${id}`, {
+  const response = await fetch(`${API_BASE}/admin/challenges/${id}`, {
     headers: authHeaders(),
   });
   return handleResponse(response);
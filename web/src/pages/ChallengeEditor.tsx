@@ -189,6 +189,7 @@ export default function ChallengeEditor() {
         category: data.category,
         type: data.type,
         isActive: data.isActive,
+        status: data.isActive ? "published" : "draft",
         configuration: {
           goals:
             data.goalsType === "collection"
@@ -447,6 +448,7 @@ export default function ChallengeEditor() {
                   <option value="percentage">Percentage</option>
                   <option value="points">Points</option>
                   <option value="weighted">Weighted</option>
+                  <option value="multiplier">Multiplier</option>
                 </select>
               </div>
 
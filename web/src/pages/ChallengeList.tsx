@@ -150,7 +150,7 @@ export default function ChallengeList() {
                                 : 'bg-gray-100 text-gray-800'
                             }`}
                           >
-                            {challenge.isActive ? 'Active' : 'Inactive'}
+                            {challenge.status.charAt(0).toUpperCase() + challenge.status.slice(1)}
                           </span>
                         </td>
                         <td className="relative whitespace-nowrap py-4 pl-3 pr-4 text-right text-sm font-medium sm:pr-6">
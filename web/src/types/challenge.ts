@@ -1,11 +1,14 @@
 export type ChallengeCategory = 'award' | 'event' | 'club' | 'personal' | 'other';
 export type ChallengeType = 'collection' | 'cumulative' | 'timeBounded';
-export type ScoringMethod = 'count' | 'percentage' | 'points' | 'weighted';
+export type ChallengeStatus = 'draft' | 'scheduled' | 'published' | 'ended' | 'archived';
+export type ScoringMethod = 'count' | 'percentage' | 'points' | 'weighted' | 'multiplier';
 
 export interface Goal {
   id: string;
   name: string;
   category?: string;
+  /** Worth this many points with the weighted and multiplier methods (default 1). */
+  points?: number;
   metadata?: Record<string, string>;
 }
 
@@ -43,7 +46,9 @@ export interface QualificationCriteria {
 
 export interface ScoringConfig {
   method: ScoringMethod;
-  weights?: { rule: string; weight: number }[];
+  basePoints?: 'goals' | 'currentValue';
+  multipliers?: 'categories' | 'goals';
+  categoryBonuses?: Record<string, number>;
   tiebreaker?: string;
   displayFormat: string;
 }
@@ -92,6 +97,8 @@ export interface Challenge {
     autoManage: boolean;
   };
   isActive?: boolean;
+  status?: ChallengeStatus;
+  publishAt?: string;
   createdAt?: string;
   updatedAt?: string;
 }
@@ -104,6 +111,8 @@ export interface ChallengeListItem {
   type: ChallengeType;
   participantCount: number;
   isActive: boolean;
+  status: ChallengeStatus;
+  publishAt?: string;
 }
 
 export interface Badge {